@@ -0,0 +1,54 @@
+/* The rsudoku engine for C, from a build with the ffi feature.
+ *
+ * A board is 81 bytes in reading order, 1 to 9 for a number and 0 for an
+ * empty cell. Every function returns RSUDOKU_OK or one of the errors below. */
+
+#ifndef RSUDOKU_H
+#define RSUDOKU_H
+
+#include <stdint.h>
+
+#define RSUDOKU_OK 0
+/* a null pointer or a cell above 9 */
+#define RSUDOKU_INVALID -1
+#define RSUDOKU_CONFLICT -2
+#define RSUDOKU_UNSOLVABLE -3
+#define RSUDOKU_NOT_UNIQUE -4
+
+typedef struct RsudokuRating {
+    /* 0 easy, 1 medium, 2 hard, 3 expert */
+    int difficulty;
+    /* the technique scores of every placement, 10 for each stuck cell */
+    uint32_t score;
+    /* cells the techniques could not place */
+    uint32_t stuck;
+} RsudokuRating;
+
+#ifdef __cplusplus
+extern "C" {
+#endif
+
+/* the clues a puzzle of a difficulty keeps, 0 easy to 3 expert, or 0 for
+ * any other number */
+uint32_t rsudoku_clues(int difficulty);
+
+/* a new puzzle with clues clues, clamped to 24..=81, or a few more at the low
+ * end when fewer would not keep a single solution; the same seed always
+ * gives the same puzzle */
+int rsudoku_generate(uint32_t clues, uint64_t seed, uint8_t cells[81]);
+
+/* the solution of the puzzle in cells, which may be the same buffer; a
+ * puzzle with several solutions gets one of them */
+int rsudoku_solve(const uint8_t cells[81], uint8_t solution[81]);
+
+/* how hard the board is by the techniques it takes to finish */
+int rsudoku_rate(const uint8_t cells[81], RsudokuRating *rating);
+
+/* RSUDOKU_OK when the clues don't conflict and have exactly one solution */
+int rsudoku_validate(const uint8_t cells[81]);
+
+#ifdef __cplusplus
+}
+#endif
+
+#endif
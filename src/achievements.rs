@@ -0,0 +1,132 @@
+use serde::{Deserialize, Serialize};
+use std::time::Duration;
+
+use crate::Difficulty;
+
+// milestones unlocked by solves, checked every time a puzzle is finished
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+pub enum Achievement {
+    FirstSolve,
+    FirstExpert,
+    QuickEasy,
+    NoHintHard,
+    Flawless,
+    Hardcore,
+    WeekStreak,
+    TenSolves,
+}
+
+impl Achievement {
+    // in the order the achievements screen lists them
+    pub const ALL: [Achievement; 8] = [
+        Achievement::FirstSolve,
+        Achievement::QuickEasy,
+        Achievement::Flawless,
+        Achievement::NoHintHard,
+        Achievement::FirstExpert,
+        Achievement::Hardcore,
+        Achievement::TenSolves,
+        Achievement::WeekStreak,
+    ];
+
+    pub fn title(&self) -> &'static str {
+        match self {
+            Achievement::FirstSolve => "First steps",
+            Achievement::FirstExpert => "Expert",
+            Achievement::QuickEasy => "Quick and easy",
+            Achievement::NoHintHard => "No help needed",
+            Achievement::Flawless => "Flawless",
+            Achievement::Hardcore => "Hardcore",
+            Achievement::WeekStreak => "Week streak",
+            Achievement::TenSolves => "Regular",
+        }
+    }
+
+    pub fn description(&self) -> &'static str {
+        match self {
+            Achievement::FirstSolve => "Solve a puzzle",
+            Achievement::FirstExpert => "Solve an Expert puzzle",
+            Achievement::QuickEasy => "Solve an Easy puzzle in under 5 minutes",
+            Achievement::NoHintHard => "Solve a Hard puzzle without hints",
+            Achievement::Flawless => "Solve a puzzle without a mistake",
+            Achievement::Hardcore => "Solve a puzzle with limited mistakes",
+            Achievement::WeekStreak => "Solve a puzzle 7 days in a row",
+            Achievement::TenSolves => "Solve 10 puzzles",
+        }
+    }
+}
+
+const QUICK_EASY: Duration = Duration::from_secs(5 * 60);
+
+// what a finished game is judged on, after its solve has been recorded
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub(crate) struct Solve {
+    pub(crate) difficulty: Difficulty,
+    pub(crate) time: Duration,
+    pub(crate) hints: usize,
+    pub(crate) mistakes: usize,
+    pub(crate) limited_mistakes: bool,
+    pub(crate) streak: u32,
+    pub(crate) solved: u32, // over all difficulties, this one included
+}
+
+impl Solve {
+    // every achievement this solve meets, unlocked before or not
+    pub(crate) fn achievements(&self) -> Vec<Achievement> {
+        Achievement::ALL
+            .into_iter()
+            .filter(|achievement| match achievement {
+                Achievement::FirstSolve => true,
+                Achievement::FirstExpert => self.difficulty == Difficulty::Expert,
+                Achievement::QuickEasy => {
+                    self.difficulty == Difficulty::Easy && self.time < QUICK_EASY
+                }
+                Achievement::NoHintHard => self.difficulty == Difficulty::Hard && self.hints == 0,
+                Achievement::Flawless => self.mistakes == 0,
+                Achievement::Hardcore => self.limited_mistakes,
+                Achievement::WeekStreak => self.streak >= 7,
+                Achievement::TenSolves => self.solved >= 10,
+            })
+            .collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_solves_meet_achievements() {
+        let solve = Solve {
+            difficulty: Difficulty::Easy,
+            time: Duration::from_secs(200),
+            hints: 2,
+            mistakes: 1,
+            limited_mistakes: false,
+            streak: 1,
+            solved: 1,
+        };
+        assert_eq!(
+            solve.achievements(),
+            vec![Achievement::FirstSolve, Achievement::QuickEasy]
+        );
+
+        let solve = Solve {
+            difficulty: Difficulty::Hard,
+            time: Duration::from_secs(200),
+            hints: 0,
+            mistakes: 0,
+            streak: 7,
+            ..solve
+        };
+        assert_eq!(
+            solve.achievements(),
+            vec![
+                Achievement::FirstSolve,
+                Achievement::Flawless,
+                Achievement::NoHintHard,
+                Achievement::WeekStreak
+            ]
+        );
+    }
+}
@@ -0,0 +1,139 @@
+use serde::Serialize;
+use std::time::Duration;
+
+use crate::{replay::MoveLog, Puzzle};
+
+// one game as data for charts and heatmaps made elsewhere: when each number
+// went in, which ones were wrong and where hints were taken. Rows and
+// columns count from 1 and times are milliseconds into the game.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub(crate) struct Analytics {
+    puzzle: String, // the clues as 81 digits, 0 for an empty cell
+    time_ms: u64,
+    solved: bool,
+    entries: Vec<Entry>,
+    mistakes: Vec<Placement>,
+    hints: Vec<Placement>,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+struct Entry {
+    row: usize,
+    col: usize,
+    value: u8,
+    at_ms: u64,
+    correct: bool,
+    hint: bool,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+struct Placement {
+    row: usize,
+    col: usize,
+    value: u8,
+    at_ms: u64,
+}
+
+impl Analytics {
+    pub(crate) fn new(puzzle: &Puzzle, log: &MoveLog, time: Duration) -> Self {
+        let solution = puzzle.to_solved().grid();
+        let millis = |at: Duration| at.as_millis() as u64;
+        let placement = |at, row, col, value| Placement {
+            row: row + 1,
+            col: col + 1,
+            value,
+            at_ms: millis(at),
+        };
+
+        let entries: Vec<Entry> = log
+            .entries()
+            .into_iter()
+            .map(|(at, row, col, value)| Entry {
+                row: row + 1,
+                col: col + 1,
+                value,
+                at_ms: millis(at),
+                correct: value == solution[row][col].value(),
+                hint: log.hints().contains(&(at, row, col)),
+            })
+            .collect();
+        let mistakes = entries
+            .iter()
+            .filter(|entry| !entry.correct)
+            .map(|entry| Placement {
+                row: entry.row,
+                col: entry.col,
+                value: entry.value,
+                at_ms: entry.at_ms,
+            })
+            .collect();
+        let hints = log
+            .hints()
+            .iter()
+            .map(|&(at, row, col)| placement(at, row, col, solution[row][col].value()))
+            .collect();
+
+        Analytics {
+            puzzle: puzzle.to_board(false),
+            time_ms: millis(time),
+            solved: puzzle.is_solved(),
+            entries,
+            mistakes,
+            hints,
+        }
+    }
+
+    pub(crate) fn to_json(&self) -> String {
+        serde_json::to_string_pretty(self).unwrap_or_default()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::Difficulty;
+
+    #[test]
+    fn test_entries_mistakes_and_hints() {
+        let mut puzzle = Puzzle::new(Difficulty::Easy);
+        let mut log = MoveLog::new(puzzle.grid());
+        let solution = puzzle.to_solved().grid();
+
+        let (row, col) = puzzle.next_empty_cell(0, 0).unwrap();
+        let wrong = solution[row][col].value() % 9 + 1;
+        let before = puzzle.grid();
+        puzzle.insert_number(row, col, wrong).unwrap();
+        log.record(Duration::from_secs(3), &before, &puzzle.grid());
+
+        let before = puzzle.grid();
+        puzzle.clear_cell(row, col).unwrap();
+        puzzle.hint(row, col).unwrap();
+        log.record(Duration::from_secs(7), &before, &puzzle.grid());
+        log.record_hint(Duration::from_secs(7), row, col);
+
+        let analytics = Analytics::new(&puzzle, &log, Duration::from_secs(9));
+        assert_eq!(analytics.time_ms, 9000);
+        assert!(!analytics.solved);
+        let right = solution[row][col].value();
+        assert_eq!(
+            analytics
+                .entries
+                .iter()
+                .map(|e| (e.value, e.correct, e.hint))
+                .collect::<Vec<_>>(),
+            [(wrong, false, false), (right, true, true)]
+        );
+        assert_eq!(
+            analytics.mistakes,
+            [Placement {
+                row: row + 1,
+                col: col + 1,
+                value: wrong,
+                at_ms: 3000
+            }]
+        );
+        assert_eq!(analytics.hints.len(), 1);
+        assert_eq!(analytics.hints[0].value, right);
+        assert!(analytics.to_json().contains("\"mistakes\""));
+    }
+}
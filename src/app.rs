@@ -1,303 +1,3137 @@
-use crossterm::event::{self, Event, KeyCode, KeyEvent, KeyEventKind};
+use crossterm::{
+    event::{
+        DisableBracketedPaste, DisableMouseCapture, EnableBracketedPaste, EnableMouseCapture,
+        KeyCode, KeyEvent, KeyModifiers, MouseButton, MouseEvent, MouseEventKind,
+    },
+    execute,
+    terminal::SetTitle,
+};
 use ratatui::{
+    backend::TestBackend,
     buffer::Buffer,
-    layout::{Alignment, Rect},
-    style::{Style, Stylize},
+    layout::{Alignment, Constraint, Flex, Layout, Rect},
+    style::{Color, Style, Stylize},
     symbols::border,
-    text::{Line, Text},
+    text::{Line, Span, Text},
     widgets::{
         block::{Position, Title},
-        Block, Padding, Paragraph, Widget,
+        Block, Cell, Clear, Padding, Paragraph, Row, Table, Widget, Wrap,
     },
-    DefaultTerminal, Frame,
+    DefaultTerminal, Frame, Terminal,
 };
 use std::{
-    io::{self},
+    io::{self, Write},
+    path::PathBuf,
     time::{Duration, Instant},
 };
 
-use crate::{puzzle::Puzzle, Difficulty};
+#[cfg(feature = "async")]
+use crate::events::StreamEvents;
+#[cfg(not(feature = "async"))]
+use crate::events::TerminalEvents;
+#[cfg(feature = "graphics")]
+use crate::graphics::{self, BoardImage};
+use crate::{
+    achievements::{Achievement, Solve},
+    analytics::Analytics,
+    board::{SudokuGridWidget, Theme, CELL_ASPECT, COMPACT_SIZE, MIN_CELL_SIZE},
+    calendar::Calendar,
+    celebration::Celebration,
+    clipboard,
+    command::{self, Command, NewGame, Prompt},
+    coords::Labels,
+    daily::{daily_puzzle, format_day},
+    dialog::Dialog,
+    events::EventSource,
+    export::ExportFormat,
+    i18n::Lang,
+    keymap::{Action, Keymap},
+    marathon::Marathon,
+    menu::{Menu, MenuItem, SHORTCUTS},
+    msg::Msg,
+    puzzle::{self, Grid, Move, MoveError, Puzzle},
+    replay::{MoveLog, Replay},
+    save::SavedGame,
+    solver::{self, Step},
+    source::{Generator, PuzzleSource},
+    splits::{SplitKind, Splits},
+    stats::{self, Stats},
+    stopwatch::Stopwatch,
+    toast::Toasts,
+    tty,
+    tutorial::{Lesson, LESSONS},
+    Difficulty,
+};
+
+const TICK_RATE: Duration = Duration::from_millis(250);
+// wrong entries allowed before the game is lost, in limited mistakes mode
+const MISTAKE_LIMIT: usize = 3;
+const DEFAULT_HINT_BUDGET: usize = 3;
+// how long wrong entries stay marked after a check
+const CHECK_TICKS: u16 = 8;
+// how long the last changed cell stays highlighted, fading out as it goes
+const LAST_MOVE_TICKS: u8 = 8;
+// how long a cell that rejected input flashes
+const FLASH_TICKS: u8 = 2;
+// lines the tutorial's lesson takes under the board
+const LESSON_HEIGHT: u16 = 5;
+// the splits panel, wide enough for "Digit 5 00:12:34 +1:05" in its border
+const SPLITS_WIDTH: u16 = 26;
+// the time attack clock turns red when this little is left
+const TIME_ATTACK_WARNING: Duration = Duration::from_secs(60);
+
+// what the app is showing, each screen has its own update and render
+#[derive(Debug, Clone, PartialEq, Eq)]
+enum Screen {
+    MainMenu(Menu),
+    Playing,
+    // the new game dialog, drawn over the screen it was opened from
+    NewGame(Menu, Box<Screen>),
+    Paused,
+    // the win animation, followed by the win screen
+    Celebrating(Celebration),
+    Solved,
+    GameOver(Loss),
+    // asks before filling in the solution
+    ConfirmReveal,
+    // asks for the argument a command was given without, over the game
+    Prompt(Prompt, String),
+    // offers back the game in play when the game last panicked, over the menu
+    ConfirmRecovery(Menu),
+    // the logical solver stepping through the board
+    Solver(SolverView),
+    // a board to type clues into, played once they have one solution
+    Editor(Box<Grid>),
+    // playing back the solved game, over the win screen
+    Replay(Box<Replay>),
+    // a guided lesson, indexing LESSONS
+    Tutorial(usize),
+    // the help overlay, returning to the screen it was opened from
+    Help(Box<Screen>),
+    Stats(Box<Screen>),
+    Achievements(Box<Screen>),
+    // the named saves to pick one to load from
+    Saves(SavePicker, Box<Screen>),
+    // a month of daily puzzles, to play one that was missed
+    Calendar(Calendar, Box<Screen>),
+    // the totals shown when a marathon is quit
+    MarathonOver,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+struct SolverView {
+    step: Option<Step>, // highlighted now, placed when the countdown ends
+    paused: bool,
+    speed: u8, // ticks each step stays highlighted
+    countdown: u8,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+struct SavePicker {
+    slots: Vec<(String, SavedGame)>, // by name
+    selected: usize,
+}
+
+// a row and column to fill next, with the cells that prove it
+type Highlight<'a> = (usize, usize, &'a [(usize, usize)]);
+
+const SOLVER_SPEED: u8 = 4;
+const SOLVER_SLOWEST: u8 = 16;
 
+// how a game was lost
 #[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Loss {
+    // too many mistakes in limited mistakes mode
+    Mistakes,
+    // the clock ran out in time attack mode
+    TimeUp,
+}
+
+#[derive(Debug)]
 pub struct App {
     exit: bool,
+    screen: Screen,
     puzzle: Puzzle,
     selected_row: usize,
     selected_col: usize,
-    timer: Instant,
+    timer: Stopwatch,
     level: Difficulty,
     time_to_solve: Duration,
+    keymap: Keymap,
+    box_jump_pending: bool,
+    notes_mode: bool, // digits pencil mark instead of inserting
+    message: Option<String>,
+    custom_clues: Option<usize>, // overrides the level's clue count
+    daily: Option<u64>,          // the day whose daily puzzle is being played
+    command_line: Option<String>,
+    toasts: Toasts,
+    data_dir: Option<PathBuf>, // where stats are persisted, nothing is written without it
+    stats: Stats,
+    solve: Option<SolveSummary>,
+    limited_mistakes: bool,       // lose the game at MISTAKE_LIMIT mistakes
+    strict: bool,                 // refuse numbers that disagree with the solution
+    hint_budget: usize,           // hints allowed per game
+    revealed: bool,               // the solution was shown, so the game holds no records
+    checked: Vec<(usize, usize)>, // wrong entries marked by the last check
+    check_ticks: u16,             // how much longer they stay marked
+    time_attack: bool,            // lose the game when `time_limit` runs out
+    labels: Option<Labels>,       // coordinates drawn around the grid
+    compact: bool,                // always use the compact board
+    // the corner opposite the cursor while selecting a block of cells
+    selection_anchor: Option<(usize, usize)>,
+    last_move: Option<(usize, usize)>,
+    last_move_ticks: u8,
+    move_log: MoveLog,
+    bell: bool,         // ring the terminal bell on rejected input
+    clock_hidden: bool, // keep the elapsed time off screen, it still counts
+    // the games in the other tabs in order, the current one would sit at `tab`
+    tabs: Vec<ParkedGame>,
+    tab: usize,
+    marathon: Option<Marathon>, // queue the next puzzle on every solve
+    splits: Option<Splits>,     // timed as each box or digit is finished
+    ghost: bool,                // race the pace of the best solve
+    generator: Generator,       // deals custom games, and the rest without a source
+    source: Option<Box<dyn PuzzleSource>>,
+    lang: Lang,
+    theme: Theme,
+    autosave: Option<Duration>, // how often the game in play is saved
+    since_autosave: Duration,
+    autosaved_grid: Grid,          // the board as it was last autosaved
+    wrote_save: bool,              // the save holds this game, to remove once it is over
+    output: String,                // escapes to write along with the next frame
+    flash: Option<(usize, usize)>, // the cell that rejected the last input
+    flash_ticks: u8,
+    hovered: Option<(usize, usize)>, // the cell under the mouse
+    // where the last frame drew the board and whether it was compact, to
+    // find the cell under the mouse
+    board_layout: std::cell::Cell<Option<(Rect, bool)>>,
+    #[cfg(feature = "graphics")]
+    board_image: Option<BoardImage>, // None when the terminal can't show images
+    // where the last frame drew a board the image can cover
+    #[cfg(feature = "graphics")]
+    board_area: std::cell::Cell<Option<Rect>>,
+}
+
+// a game set aside in another tab, with what it needs to pick up where it
+// was left
+#[derive(Debug, Clone, PartialEq, Eq)]
+struct ParkedGame {
+    puzzle: Puzzle,
+    timer: Stopwatch, // paused while parked
+    level: Difficulty,
+    custom_clues: Option<usize>,
+    daily: Option<u64>,
+    move_log: MoveLog,
+    revealed: bool,
+    selected: (usize, usize),
+    splits: Option<Splits>,
+}
+
+impl ParkedGame {
+    fn is_unfinished(&self) -> bool {
+        !self.puzzle.is_solved() && !self.revealed
+    }
+}
+
+// how the last solve compares to the records, shown on the win screen
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+struct SolveSummary {
+    previous_best: Option<Duration>,
+    is_new_best: bool,
 }
 
 impl App {
     pub fn new(level: Difficulty) -> Self {
+        let puzzle = Puzzle::new(level);
+        App {
+            move_log: MoveLog::new(puzzle.grid()),
+            puzzle,
+            screen: Screen::Playing,
+            ..App::new_unstarted(level)
+        }
+    }
+
+    fn new_unstarted(level: Difficulty) -> Self {
         App {
             exit: false,
-            puzzle: Puzzle::new(level),
+            screen: Screen::MainMenu(Menu::new(false)),
+            puzzle: Puzzle::empty(),
             selected_col: 0,
             selected_row: 0,
-            timer: Instant::now(),
+            timer: Stopwatch::start(),
             level,
             time_to_solve: Duration::default(),
+            keymap: Keymap::default(),
+            box_jump_pending: false,
+            notes_mode: false,
+            message: None,
+            custom_clues: None,
+            daily: None,
+            command_line: None,
+            toasts: Toasts::default(),
+            data_dir: None,
+            stats: Stats::default(),
+            solve: None,
+            limited_mistakes: false,
+            strict: false,
+            hint_budget: DEFAULT_HINT_BUDGET,
+            revealed: false,
+            checked: Vec::new(),
+            check_ticks: 0,
+            time_attack: false,
+            labels: None,
+            compact: false,
+            selection_anchor: None,
+            last_move: None,
+            last_move_ticks: 0,
+            move_log: MoveLog::new(Puzzle::empty().grid()),
+            bell: true,
+            clock_hidden: false,
+            tabs: Vec::new(),
+            tab: 0,
+            marathon: None,
+            splits: None,
+            ghost: false,
+            generator: Generator::new(),
+            source: None,
+            lang: Lang::default(),
+            theme: Theme::default(),
+            autosave: None,
+            since_autosave: Duration::ZERO,
+            autosaved_grid: Puzzle::empty().grid(),
+            wrote_save: false,
+            output: String::new(),
+            flash: None,
+            flash_ticks: 0,
+            hovered: None,
+            board_layout: std::cell::Cell::new(None),
+            #[cfg(feature = "graphics")]
+            board_image: graphics::is_supported().then(BoardImage::default),
+            #[cfg(feature = "graphics")]
+            board_area: std::cell::Cell::new(None),
         }
     }
 
-    pub fn run(&mut self, terminal: &mut DefaultTerminal) -> io::Result<()> {
-        while !self.exit {
-            terminal.draw(|frame| self.draw(frame))?;
-            self.handle_events(Duration::from_secs(1))?;
+    // start on the difficulty menu instead of straight in a game
+    pub fn new_with_menu() -> Self {
+        App::new_unstarted(Difficulty::Medium)
+    }
+
+    pub fn with_keymap(mut self, keymap: Keymap) -> Self {
+        self.keymap = keymap;
+        let (quit, shortcuts) = (self.key_label(Action::Quit), self.menu_shortcuts());
+        if let Screen::MainMenu(menu) = &mut self.screen {
+            *menu = menu.clone().with_quit_key(quit).with_shortcuts(shortcuts);
+        }
+        self
+    }
+
+    pub fn with_limited_mistakes(mut self, limited_mistakes: bool) -> Self {
+        self.limited_mistakes = limited_mistakes;
+        self
+    }
+
+    // wrong numbers are turned away as they are entered, still counting as
+    // mistakes
+    pub fn with_strict(mut self, strict: bool) -> Self {
+        self.strict = strict;
+        self
+    }
+
+    // keep the notes of every empty cell filled in, in this game and the next
+    pub fn with_auto_notes(mut self, auto_notes: bool) -> Self {
+        self.puzzle.set_auto_notes(auto_notes);
+        self
+    }
+
+    // the difficulty the menu offers first, medium unless set
+    pub fn with_default_difficulty(mut self, level: Difficulty) -> Self {
+        self.level = level;
+        if let Screen::MainMenu(_) = self.screen {
+            self.screen = Screen::MainMenu(self.main_menu());
         }
+        self
+    }
 
-        Ok(())
+    pub fn with_labels(mut self, labels: Option<Labels>) -> Self {
+        self.labels = labels;
+        self
     }
 
-    fn new_game(&mut self) {
-        self.puzzle = Puzzle::new(self.level);
-        self.timer = Instant::now();
+    // draw the dense board even when the full one would fit
+    pub fn with_compact(mut self, compact: bool) -> Self {
+        self.compact = compact;
+        self
     }
 
-    fn draw(&self, frame: &mut Frame) {
-        frame.render_widget(self, frame.area());
+    // ring the terminal bell when input is rejected, on by default
+    pub fn with_bell(mut self, bell: bool) -> Self {
+        self.bell = bell;
+        self
     }
 
-    fn handle_events(&mut self, timeout: Duration) -> io::Result<()> {
-        // Poll for an event with a timeout to avoid blocking
-        if event::poll(timeout)? {
-            if let Event::Key(key_event) = event::read()? {
-                if key_event.kind == KeyEventKind::Press {
-                    self.handle_key_event(key_event);
-                }
+    pub fn with_marathon(mut self, marathon: bool) -> Self {
+        self.marathon = marathon.then(Marathon::default);
+        self
+    }
+
+    pub fn with_splits(mut self, kind: Option<SplitKind>) -> Self {
+        self.splits = kind.map(Splits::new);
+        self
+    }
+
+    pub fn with_lang(mut self, lang: Lang) -> Self {
+        self.lang = lang;
+        if let Screen::MainMenu(menu) = &mut self.screen {
+            *menu = menu.clone().with_lang(lang);
+        }
+        self
+    }
+
+    // deal puzzles from a generator seeded with `seed`, the one already
+    // started included, so the same seed gives everyone the same games
+    pub fn with_seed(mut self, seed: Option<u64>) -> Self {
+        if let Some(seed) = seed {
+            self.generator = Generator::seeded(seed);
+            if self.screen == Screen::Playing {
+                let puzzle = self.generate();
+                self.start(puzzle);
             }
         }
-        Ok(())
+        self
     }
 
-    fn handle_key_event(&mut self, key_event: KeyEvent) {
-        match key_event.code {
-            KeyCode::Char('Q') | KeyCode::Char('q') => self.exit(),
-            KeyCode::Char('N') | KeyCode::Char('n') => {
-                self.new_game();
+    // take the puzzles of new games at a level from `source`, the one
+    // already started included; custom clue counts are still generated
+    pub fn with_source(mut self, source: Box<dyn PuzzleSource>) -> Self {
+        self.source = Some(source);
+        if self.screen == Screen::Playing {
+            let puzzle = self.generate();
+            self.start(puzzle);
+        }
+        self
+    }
+
+    // play the given puzzle instead of a new one, as a custom game since it
+    // has no level
+    pub fn with_puzzle(mut self, puzzle: Puzzle) -> Self {
+        self.custom_clues = Some(puzzle.clues());
+        self.start(puzzle);
+        self
+    }
+
+    // play with this many clues instead of the level's, as a custom game
+    pub fn with_clues(mut self, clues: Option<usize>) -> Self {
+        if clues.is_some() {
+            self.custom_clues = clues;
+            if self.screen == Screen::Playing {
+                let puzzle = self.generate();
+                self.start(puzzle);
             }
-            KeyCode::Char('R') | KeyCode::Char('r') => {
-                self.puzzle.reset();
+        }
+        self
+    }
+
+    // play the daily puzzle of `day` at the current level, see `today()`
+    pub fn with_daily(mut self, day: u64) -> Self {
+        self.start_daily(day);
+        self
+    }
+
+    pub fn with_theme(mut self, theme: Theme) -> Self {
+        self.theme = theme;
+        self
+    }
+
+    pub fn with_ghost(mut self, ghost: bool) -> Self {
+        self.ghost = ghost;
+        self
+    }
+
+    pub fn with_clock_hidden(mut self, clock_hidden: bool) -> Self {
+        self.clock_hidden = clock_hidden;
+        self
+    }
+
+    pub fn with_time_attack(mut self, time_attack: bool) -> Self {
+        self.time_attack = time_attack;
+        self
+    }
+
+    // save the game in play every `interval` and after moves, so a crash
+    // loses little of it; only quitting saves it otherwise
+    pub fn with_autosave(mut self, interval: Option<Duration>) -> Self {
+        self.autosave = interval;
+        self
+    }
+
+    pub fn with_hint_budget(mut self, hint_budget: usize) -> Self {
+        self.hint_budget = hint_budget;
+        self
+    }
+
+    // persist stats in the given directory, see `data_dir()`
+    pub fn with_data_dir(mut self, dir: PathBuf) -> Self {
+        match Stats::load(&dir) {
+            Ok(stats) => self.stats = stats,
+            Err(err) => self.message = Some(format!("could not load stats: {}", err)),
+        }
+        self.data_dir = Some(dir);
+
+        // a game from `App::new` began before there was anywhere to count it
+        if self.screen == Screen::Playing {
+            self.record_start();
+        }
+        // now that it can tell, offer the saved game, and the one a crash
+        // left behind before that
+        if let Screen::MainMenu(_) = self.screen {
+            let menu = self.main_menu();
+            let crashed = self
+                .data_dir
+                .as_ref()
+                .is_some_and(|dir| SavedGame::recovery_path(dir).is_file());
+            self.screen = match crashed {
+                true => Screen::ConfirmRecovery(menu),
+                false => Screen::MainMenu(menu),
+            };
+        }
+        self
+    }
+
+    // carry on with the game left unfinished when last quitting, from the
+    // directory given to `with_data_dir`
+    pub fn with_saved_game(mut self) -> Self {
+        self.resume_saved_game();
+        self
+    }
+
+    // carry on with the game saved under `name`, which stays saved, from the
+    // directory given to `with_data_dir`
+    pub fn with_slot(mut self, name: &str) -> Self {
+        let Some(dir) = &self.data_dir else {
+            self.toasts.push("There is nowhere to keep saved games");
+            return self;
+        };
+        match SavedGame::load_slot(dir, name) {
+            Ok(game) => {
+                self.resume(game);
+                self.toasts.push(format!("Loaded {}", name));
             }
-            KeyCode::Char('H') | KeyCode::Char('h') => {
-                self.puzzle.hint(self.selected_row, self.selected_col);
+            Err(err) => self
+                .toasts
+                .push(format!("Could not load {}: {}", name, err)),
+        }
+        self
+    }
+
+    fn resume_saved_game(&mut self) {
+        let Some(dir) = &self.data_dir else {
+            self.toasts.push("There is nowhere to keep saved games");
+            return;
+        };
+        match SavedGame::load(dir) {
+            Ok(Some(game)) => {
+                if let Err(err) = SavedGame::remove(dir) {
+                    self.toasts
+                        .push(format!("Could not remove the saved game: {}", err));
+                }
+                self.resume_session(game);
+                self.toasts.push("Resumed the saved game");
             }
-            KeyCode::Left => {
-                self.selected_col = self.selected_col.saturating_sub(1);
+            Ok(None) => self.toasts.push("There is no saved game to resume"),
+            Err(err) => self
+                .toasts
+                .push(format!("Could not load the saved game: {}", err)),
+        }
+    }
+
+    // the start menu, with Resume offered when a game was left unfinished
+    fn main_menu(&self) -> Menu {
+        let can_resume = self
+            .data_dir
+            .as_ref()
+            .is_some_and(|dir| SavedGame::path(dir).is_file());
+        Menu::new(can_resume)
+            .with_difficulty(self.level)
+            .with_lang(self.lang)
+            .with_quit_key(self.key_label(Action::Quit))
+            .with_shortcuts(self.menu_shortcuts())
+    }
+
+    // the menu's letter shortcuts that work in at least one case, the
+    // others being taken by the keymap for moving
+    fn menu_shortcuts(&self) -> Vec<char> {
+        SHORTCUTS
+            .iter()
+            .map(|&(c, _)| c)
+            .filter(|&c| {
+                [c, c.to_ascii_uppercase()]
+                    .into_iter()
+                    .any(|c| !is_move(self.keymap.action(&KeyEvent::from(KeyCode::Char(c)))))
+            })
+            .collect()
+    }
+
+    // the key the hints on screen name for an action
+    fn key_label(&self, action: Action) -> String {
+        self.keymap.label(action).unwrap_or_default()
+    }
+
+    // with the async feature the terminal is read through a tokio runtime
+    pub fn run(&mut self, terminal: &mut DefaultTerminal) -> io::Result<()> {
+        #[cfg(feature = "async")]
+        let mut events = StreamEvents::terminal()?;
+        #[cfg(not(feature = "async"))]
+        let mut events = TerminalEvents;
+        self.run_with(terminal, &mut events)
+    }
+
+    // the game loop reading its input from `events`, the terminal or a
+    // script
+    pub fn run_with(
+        &mut self,
+        terminal: &mut DefaultTerminal,
+        events: &mut impl EventSource,
+    ) -> io::Result<()> {
+        // save the terminal's title on its title stack, to be put back on exit
+        write!(terminal.backend_mut(), "\x1b[22;0t")?;
+        // pasted text arrives as one event instead of a burst of key presses
+        execute!(
+            terminal.backend_mut(),
+            EnableBracketedPaste,
+            EnableMouseCapture
+        )?;
+        // a terminal that went away fails the loop, the game is kept anyway
+        let played = self.game_loop(terminal, events);
+        SavedGame::keep_for_recovery(None);
+        self.save_game()?;
+        played?;
+
+        #[cfg(feature = "graphics")]
+        if let Some(image) = &mut self.board_image {
+            image.hide(terminal.backend_mut())?;
+        }
+        execute!(
+            terminal.backend_mut(),
+            DisableBracketedPaste,
+            DisableMouseCapture
+        )?;
+        write!(terminal.backend_mut(), "\x1b[23;0t")?;
+        terminal.backend_mut().flush()
+    }
+
+    fn game_loop(
+        &mut self,
+        terminal: &mut DefaultTerminal,
+        events: &mut impl EventSource,
+    ) -> io::Result<()> {
+        let mut last_tick = Instant::now();
+        let mut title = String::new();
+        while !self.exit && !tty::interrupted() {
+            terminal.draw(|frame| self.view(frame))?;
+            let new_title = self.title(&self.screen);
+            if new_title != title {
+                execute!(terminal.backend_mut(), SetTitle(&new_title))?;
+                title = new_title;
             }
-            KeyCode::Right => {
-                self.selected_col = self.selected_col.saturating_add(1).min(8);
+            if !self.output.is_empty() {
+                let backend = terminal.backend_mut();
+                backend.write_all(std::mem::take(&mut self.output).as_bytes())?;
+                backend.flush()?;
             }
-            KeyCode::Up => {
-                self.selected_row = self.selected_row.saturating_sub(1);
+            #[cfg(feature = "graphics")]
+            self.draw_board_image(terminal.backend_mut())?;
+            self.handle_events(events, TICK_RATE.saturating_sub(last_tick.elapsed()))?;
+
+            if last_tick.elapsed() >= TICK_RATE {
+                self.update(Msg::Tick);
+                last_tick = Instant::now();
             }
-            KeyCode::Down => {
-                self.selected_row = self.selected_row.saturating_add(1).min(8);
+            self.keep_for_recovery();
+        }
+        Ok(())
+    }
+
+    // hand the panic hook the game as it is now, to write out if it comes to it
+    fn keep_for_recovery(&self) {
+        let game = self
+            .data_dir
+            .as_deref()
+            .and_then(|dir| Some((dir, self.saved_session()?)));
+        SavedGame::keep_for_recovery(game);
+    }
+
+    // play scripted events without a terminal, drawing each frame into a
+    // buffer of the given size, and return the last frame as text once the
+    // events run out or the game quits. No time passes between events, so
+    // nothing moves on with the clock. Quitting keeps the unfinished games
+    // as `run` does.
+    pub fn run_headless(
+        &mut self,
+        events: &mut impl EventSource,
+        width: u16,
+        height: u16,
+    ) -> io::Result<String> {
+        let mut terminal = Terminal::new(TestBackend::new(width, height))?;
+        loop {
+            terminal.draw(|frame| self.view(frame))?;
+            if self.exit || !events.poll(Duration::ZERO)? {
+                break;
             }
-            KeyCode::Char(c) if c.is_numeric() => {
-                let num = c as u8 - b'0';
-                self.puzzle
-                    .insert_number(self.selected_row, self.selected_col, num);
+            self.handle_events(events, Duration::ZERO)?;
+        }
+        if self.exit {
+            self.save_game()?;
+        }
+
+        let buffer = terminal.backend().buffer();
+        let lines: Vec<String> = buffer
+            .content()
+            .chunks(buffer.area.width as usize)
+            .map(|row| row.iter().map(|cell| cell.symbol()).collect::<String>())
+            .map(|line| line.trim_end().to_string())
+            .collect();
+        Ok(lines.join("\n"))
+    }
+
+    // the window title, e.g. "Sudoku – Hard – 00:12:34" during a game
+    fn title(&self, screen: &Screen) -> String {
+        match screen {
+            Screen::MainMenu(_) | Screen::ConfirmRecovery(_) => "Sudoku".into(),
+            Screen::Tutorial(_) => "Sudoku – Tutorial".into(),
+            Screen::Editor(_) => "Sudoku – Editor".into(),
+            Screen::MarathonOver => "Sudoku – Marathon".into(),
+            Screen::NewGame(_, previous)
+            | Screen::Help(previous)
+            | Screen::Stats(previous)
+            | Screen::Achievements(previous)
+            | Screen::Saves(_, previous)
+            | Screen::Calendar(_, previous) => self.title(previous),
+            _ => {
+                let level = match (self.custom_clues, self.daily) {
+                    (Some(clues), _) => format!("{} clues", clues),
+                    (None, Some(day)) => format!("Daily {} {}", format_day(day), self.level),
+                    (None, None) => self.level.to_string(),
+                };
+                match self.clock().content {
+                    clock if clock.is_empty() => format!("Sudoku – {}", level),
+                    clock => format!("Sudoku – {} – {}", level, clock),
+                }
+            }
+        }
+    }
+
+    // cover the board with the image, or take it away when no board is shown
+    #[cfg(feature = "graphics")]
+    fn draw_board_image(&mut self, out: &mut impl io::Write) -> io::Result<()> {
+        let Some(image) = &mut self.board_image else {
+            return Ok(());
+        };
+        match self.board_area.get() {
+            Some(area) => image.show(
+                out,
+                &self.puzzle.grid(),
+                (self.selected_row, self.selected_col),
+                area,
+            ),
+            None => image.hide(out),
+        }
+    }
+
+    // advance everything that changes with time alone
+    fn tick(&mut self) {
+        self.toasts.tick();
+
+        self.last_move_ticks = self.last_move_ticks.saturating_sub(1);
+        self.flash_ticks = self.flash_ticks.saturating_sub(1);
+        self.check_ticks = self.check_ticks.saturating_sub(1);
+        if self.check_ticks == 0 {
+            self.checked.clear();
+        }
 
-                if self.puzzle.is_solved() {
-                    self.time_to_solve = self.timer.elapsed();
+        if let Screen::Replay(replay) = &mut self.screen {
+            replay.advance(TICK_RATE);
+        }
+
+        if let Screen::Celebrating(celebration) = &mut self.screen {
+            celebration.tick();
+            if celebration.is_finished() {
+                self.after_celebration();
+            }
+        }
+
+        if let Screen::Solver(view) = &mut self.screen {
+            if !view.paused {
+                view.countdown = view.countdown.saturating_sub(1);
+                if view.countdown == 0 {
+                    self.solver_advance();
                 }
             }
-            KeyCode::Backspace | KeyCode::Delete => {
-                self.puzzle.clear_cell(self.selected_row, self.selected_col);
+        }
+
+        if self.time_attack
+            && self.screen == Screen::Playing
+            && self.timer.elapsed() >= self.time_limit()
+        {
+            self.lose(Loss::TimeUp);
+        }
+
+        // every change to the board is saved, those within a tick together
+        if let Some(interval) = self.autosave {
+            self.since_autosave += TICK_RATE;
+            if self.puzzle.grid() != self.autosaved_grid || self.since_autosave >= interval {
+                self.since_autosave = Duration::ZERO;
+                self.autosaved_grid = self.puzzle.grid();
+                if let Err(err) = self.save_game() {
+                    self.message = Some(format!("could not autosave: {}", err));
+                }
             }
-            _ => {}
         }
     }
 
-    fn exit(&mut self) {
-        self.exit = true;
+    // how long a time attack game lasts, harder puzzles get longer
+    fn time_limit(&self) -> Duration {
+        let minutes = match self.custom_clues {
+            Some(_) => 15,
+            None => match self.level {
+                Difficulty::Easy => 10,
+                Difficulty::Medium => 15,
+                Difficulty::Hard => 20,
+                Difficulty::Expert => 30,
+            },
+        };
+        Duration::from_secs(minutes * 60)
     }
-}
 
-impl Widget for &App {
-    fn render(self, area: Rect, buf: &mut Buffer) {
-        if self.puzzle.is_solved() {
-            let instructions = Title::from(Line::from(vec![
-                " Quit ".into(),
-                "<Q> ".blue().bold(),
-                " New Game ".into(),
-                "<N>".blue().bold(),
-            ]));
+    fn lose(&mut self, loss: Loss) {
+        self.timer.pause();
+        self.screen = Screen::GameOver(loss);
+    }
 
-            let text = Text::from(vec![
-                Line::from(vec!["🎉 Congratulations! You solved the puzzle! 🎉".into()]),
-                Line::from(vec![format!(
-                    "Solved in: {0}",
-                    format_duration(self.time_to_solve)
-                )
-                .into()]),
-            ])
-            .green()
-            .bold()
-            .centered();
+    fn new_game(&mut self) {
+        let puzzle = self.generate();
+        self.start(puzzle);
 
-            Paragraph::new(text)
-                .centered()
-                .bold()
-                .block(
-                    Block::default()
-                        .padding(Padding::new(0, 0, area.height / 2 - 1, 0))
-                        .title(instructions)
-                        .title_position(Position::Bottom),
-                )
-                .render(area, buf);
-        } else {
-            // the outer block
-            let instructions = Title::from(Line::from(vec![
-                " Quit ".into(),
-                "<Q> ".blue().bold(),
-                " Delete ".into(),
-                "<DEL>".blue().bold(),
-                " Reset ".into(),
-                "<R>".blue().bold(),
-                " New Game ".into(),
-                "<N>".blue().bold(),
-                " Hint ".into(),
-                "<H>".blue().bold(),
-            ]));
-
-            let title = Title::from(" Sudoku ".bold());
-            let timer = Title::from(Line::from(vec![
-                format_duration(self.timer.elapsed()).into()
-            ]));
-            let block = Block::bordered()
-                .title(title.alignment(Alignment::Center))
-                .title(timer.alignment(Alignment::Right).position(Position::Bottom))
-                .title(
-                    instructions
-                        .alignment(Alignment::Left)
-                        .position(Position::Bottom),
-                )
-                .border_set(border::THICK);
+        let level = match self.custom_clues {
+            Some(clues) => format!("{} clues", clues),
+            None => self.level.to_string(),
+        };
+        self.toasts.push(format!("New game: {}", level));
+        self.record_start();
+    }
 
-            // inner space of outer block
-            let inner_area = block.inner(area);
+    // the daily puzzle of `day` at the current level
+    fn start_daily(&mut self, day: u64) {
+        self.custom_clues = None;
+        self.start(daily_puzzle(day, self.level));
+        self.daily = Some(day);
+        self.toasts
+            .push(format!("Daily puzzle for {}", format_day(day)));
+    }
 
-            let cell_width = inner_area.width / 9;
-            let cell_height = inner_area.height / 9;
-            let cell_size = std::cmp::min(cell_width, cell_height);
+    fn clue_count(&self) -> usize {
+        self.custom_clues.unwrap_or(self.level as usize)
+    }
 
-            let grid_width = 9 * cell_size;
-            let grid_height = 9 * cell_size;
+    // a source that fails hands over to the generator for that game
+    fn generate(&mut self) -> Puzzle {
+        if let (None, Some(source)) = (self.custom_clues, &mut self.source) {
+            match source.next_puzzle(self.level) {
+                Ok(puzzle) => return puzzle,
+                Err(err) => self
+                    .toasts
+                    .push(format!("Could not get a puzzle, generated one: {}", err)),
+            }
+        }
+        self.generator.with_clues(self.clue_count())
+    }
 
-            // offset to put grid in center of inner area
-            let horizontal_offset = (inner_area.width - grid_width) / 2;
-            let vertical_offset = (inner_area.height - grid_height) / 2;
+    // the current game, paused to be put in a tab
+    fn park(&mut self) -> ParkedGame {
+        let mut timer = self.timer;
+        timer.pause();
+        ParkedGame {
+            puzzle: self.puzzle.clone(),
+            timer,
+            level: self.level,
+            custom_clues: self.custom_clues,
+            daily: self.daily,
+            move_log: self.move_log.clone(),
+            revealed: self.revealed,
+            selected: (self.selected_row, self.selected_col),
+            splits: self.splits.clone(),
+        }
+    }
 
-            let centered_inner_area = Rect {
-                x: inner_area.x + horizontal_offset,
-                y: inner_area.y + vertical_offset,
-                width: grid_width,
-                height: grid_height,
-            };
+    // carry on with a game from a tab, dropping the marks left by the last one
+    fn unpark(&mut self, game: ParkedGame) {
+        self.puzzle = game.puzzle;
+        self.timer = game.timer;
+        self.timer.resume();
+        self.level = game.level;
+        self.custom_clues = game.custom_clues;
+        self.daily = game.daily;
+        self.move_log = game.move_log;
+        self.revealed = game.revealed;
+        self.splits = game.splits;
+        self.select(game.selected.0, game.selected.1);
+        self.selection_anchor = None;
+        self.last_move = None;
+        self.checked.clear();
+        self.screen = Screen::Playing;
+    }
 
-            for row in 0..9 {
-                for col in 0..9 {
-                    let x = centered_inner_area.x + col as u16 * cell_size;
-                    let y = centered_inner_area.y + row as u16 * cell_size;
-
-                    let is_major_row = row % 3 == 0;
-                    let is_major_col = col % 3 == 0;
-
-                    let top_left_corner = match (is_major_row, is_major_col) {
-                        (true, true) => "╬", // Major row and column
-                        //
-                        (true, false) => "╦", // Major row, regular column
-                        //
-                        (false, true) => "╠", // Major column, regular row
-                        //
-                        (false, false) => "┼", // Regular intersection
-                    };
+    // play a puzzle given as 81 cells, as a custom game since it has no level.
+    // It needs a single answer, as generated ones have, or a right number
+    // that differs from the one solution kept would count as a mistake.
+    fn load(&mut self, board: &str) -> Result<(), String> {
+        let puzzle: Puzzle = board.parse()?;
+        if puzzle.solution_count() != 1 {
+            return Err("The puzzle has more than one solution".into());
+        }
+        self.custom_clues = Some(puzzle.clues());
+        self.toasts
+            .push(format!("Loaded a puzzle with {} clues", puzzle.clues()));
+        self.start(puzzle);
+        Ok(())
+    }
 
-                    if row < 9 && x + cell_size <= centered_inner_area.x + grid_width {
-                        let h_line = if is_major_row { "═" } else { "─" };
-                        for i in 0..cell_size {
-                            buf.set_string(x + i, y, h_line, Style::default());
-                        }
-                    }
+    fn start(&mut self, mut puzzle: Puzzle) {
+        puzzle.set_auto_notes(self.puzzle.auto_notes());
+        self.puzzle = puzzle;
+        self.move_log = MoveLog::new(self.puzzle.grid());
+        self.timer = Stopwatch::start();
+        self.screen = Screen::Playing;
+        self.revealed = false;
+        self.daily = None;
+        self.restart_splits();
+        self.select(0, 0);
+    }
 
-                    if col < 9 && y + cell_size <= centered_inner_area.y + grid_height {
-                        let v_line = if is_major_col { "║" } else { "│" };
-                        for i in 0..cell_size {
-                            buf.set_string(x, y + i, v_line, Style::default());
-                        }
-                    }
+    // a saved game as a tab, its clock paused at the time it was saved
+    fn parked(&self, game: SavedGame) -> ParkedGame {
+        ParkedGame {
+            puzzle: game.puzzle,
+            timer: Stopwatch::paused_at(game.elapsed),
+            level: game.level,
+            custom_clues: game.custom_clues,
+            daily: game.daily,
+            move_log: game.move_log,
+            revealed: false,
+            selected: game.selected,
+            splits: self
+                .splits
+                .as_ref()
+                .map(|splits| Splits::new(splits.kind())),
+        }
+    }
 
-                    buf.set_string(x, y, top_left_corner, Style::default());
-
-                    // render the Sudoku values in the grid cells
-                    let cell = self.puzzle.grid()[row][col];
-                    let (symbol, style) = if cell.value() == 0 {
-                        (" ".into(), Style::default()) // empty cell
-                    } else if cell.is_clue() {
-                        (
-                            cell.value().to_string(),
-                            Style::default().fg(ratatui::style::Color::Yellow).bold(),
-                        )
-                    } else {
-                        let cell_style = if cell.possible_wrong() {
-                            Style::default().fg(ratatui::style::Color::Red).bold()
-                        } else {
-                            Style::default().fg(ratatui::style::Color::Blue).bold()
-                        };
+    // a game picked up from a save, its clock carrying on from the time it
+    // was saved
+    fn resume(&mut self, mut game: SavedGame) {
+        let elapsed = game.elapsed;
+        game.tabs.clear();
+        let parked = self.parked(game);
+        self.unpark(parked);
+        // what was finished before the save counts from when it was resumed
+        if let Some(splits) = &mut self.splits {
+            splits.update(&self.puzzle, elapsed);
+        }
+    }
 
-                        (cell.value().to_string(), cell_style)
-                    };
+    // the saved game with the tabs that were open next to it, added after
+    // any open now
+    fn resume_session(&mut self, mut game: SavedGame) {
+        let tabs: Vec<ParkedGame> = std::mem::take(&mut game.tabs)
+            .into_iter()
+            .map(|tab| self.parked(tab))
+            .collect();
+        if self.tabs.is_empty() {
+            self.tab = game.tab.min(tabs.len());
+        }
+        self.tabs.extend(tabs);
+        self.resume(game);
+    }
 
-                    // highlight the selected cell
-                    let is_selected = self.selected_row == row && self.selected_col == col;
-                    let cell_style = if is_selected {
-                        style.underlined()
-                    } else {
-                        style
-                    };
+    // whether quitting now leaves a game worth coming back to
+    fn is_unfinished(&self) -> bool {
+        let mut screen = &self.screen;
+        while let Screen::NewGame(_, previous)
+        | Screen::Help(previous)
+        | Screen::Stats(previous)
+        | Screen::Achievements(previous)
+        | Screen::Saves(_, previous)
+        | Screen::Calendar(_, previous) = screen
+        {
+            screen = previous;
+        }
+        matches!(
+            screen,
+            Screen::Playing
+                | Screen::Paused
+                | Screen::ConfirmReveal
+                | Screen::Prompt(..)
+                | Screen::Editor(_)
+                | Screen::Solver(_)
+        ) && !self.puzzle.is_solved()
+            && !self.revealed
+    }
 
-                    // center the symbol in the cell
-                    let x_offset = (cell_size) / 2;
-                    let y_offset = (cell_size) / 2;
-                    buf.set_stringn(x + x_offset, y + y_offset, &symbol, 1, cell_style);
-                }
+    // keep an unfinished game for `with_saved_game` to carry on with, and
+    // drop the copy of one that has since been finished
+    fn save_game(&mut self) -> io::Result<()> {
+        let Some(dir) = &self.data_dir else {
+            return Ok(());
+        };
+        if let Some(game) = self.saved_session() {
+            game.save(dir)?;
+            self.wrote_save = true;
+        } else if self.wrote_save {
+            SavedGame::remove(dir)?;
+            self.wrote_save = false;
+        }
+        Ok(())
+    }
+
+    fn saved_game(&self) -> SavedGame {
+        SavedGame {
+            puzzle: self.puzzle.clone(),
+            elapsed: self.timer.elapsed(),
+            level: self.level,
+            custom_clues: self.custom_clues,
+            daily: self.daily,
+            move_log: self.move_log.clone(),
+            selected: (self.selected_row, self.selected_col),
+            tabs: Vec::new(),
+            tab: 0,
+        }
+    }
+
+    // every unfinished game across the tabs, the current one's first when it
+    // is unfinished and the others' in order around it. None when there is
+    // nothing to come back to.
+    fn saved_session(&self) -> Option<SavedGame> {
+        let mut games: Vec<SavedGame> = self
+            .tabs
+            .iter()
+            .filter(|tab| tab.is_unfinished())
+            .map(|tab| SavedGame {
+                puzzle: tab.puzzle.clone(),
+                elapsed: tab.timer.elapsed(),
+                level: tab.level,
+                custom_clues: tab.custom_clues,
+                daily: tab.daily,
+                move_log: tab.move_log.clone(),
+                selected: tab.selected,
+                tabs: Vec::new(),
+                tab: 0,
+            })
+            .collect();
+        if self.is_unfinished() {
+            let tab = self.tabs[..self.tab]
+                .iter()
+                .filter(|tab| tab.is_unfinished())
+                .count();
+            return Some(SavedGame {
+                tabs: games,
+                tab,
+                ..self.saved_game()
+            });
+        }
+        if games.is_empty() {
+            return None;
+        }
+        let mut game = games.remove(0);
+        game.tabs = games;
+        Some(game)
+    }
+
+    // keep the game under `name`, for `:load <name>` or the saves screen
+    fn save_slot(&mut self, name: &str) {
+        let Some(dir) = &self.data_dir else {
+            self.message = Some("there is nowhere to keep saved games".into());
+            return;
+        };
+        if !self.is_unfinished() {
+            self.message = Some("there is no unfinished game to save".into());
+            return;
+        }
+        self.message = Some(match self.saved_game().save_slot(dir, name) {
+            Ok(()) => format!("saved the game as {}", name),
+            Err(err) => format!("could not save the game: {}", err),
+        });
+    }
+
+    fn load_slot(&mut self, name: &str) {
+        let Some(dir) = &self.data_dir else {
+            self.message = Some("there is nowhere to keep saved games".into());
+            return;
+        };
+        match SavedGame::load_slot(dir, name) {
+            Ok(game) => {
+                self.resume(game);
+                self.toasts.push(format!("Loaded {}", name));
             }
+            Err(err) => self.message = Some(format!("could not load {}: {}", name, err)),
+        }
+    }
 
-            // draw the final bottom horizontal line
-            let last_row_y = centered_inner_area.y + grid_height;
-            for col in 0..9 {
-                let x = centered_inner_area.x + col as u16 * cell_size;
-                for i in 0..=cell_size {
-                    let symbol = if col % 3 == 0 && (i == 0 || i == cell_size) {
-                        "╬"
-                    } else {
-                        "═"
-                    };
-                    buf.set_string(x + i, last_row_y, symbol, Style::default());
-                }
+    fn open_saves(&mut self) {
+        let slots = match &self.data_dir {
+            Some(dir) => SavedGame::slots(dir),
+            None => Ok(Vec::new()),
+        };
+        match slots {
+            Ok(slots) => {
+                let previous = std::mem::replace(&mut self.screen, Screen::Playing);
+                let picker = SavePicker { slots, selected: 0 };
+                self.screen = Screen::Saves(picker, Box::new(previous));
             }
+            Err(err) => self.message = Some(format!("could not list the saves: {}", err)),
+        }
+    }
 
-            // draw the final right vertical line
-            let last_col_x = centered_inner_area.x + grid_width;
-            for row in 0..9 {
-                let y = centered_inner_area.y + row as u16 * cell_size;
-                for i in 0..=cell_size {
-                    let symbol = if (row % 3 == 0 && i == 0) || (i == cell_size) {
-                        "╬"
-                    } else {
-                        "║"
-                    };
-                    buf.set_string(last_col_x, y + i, symbol, Style::default());
+    fn update_saves(&mut self, key_event: KeyEvent) {
+        let action = self.keymap.action(&key_event);
+        let Screen::Saves(picker, previous) = &mut self.screen else {
+            return;
+        };
+        let len = picker.slots.len().max(1);
+        match (key_event.code, action) {
+            (KeyCode::Esc, _) | (_, Some(Action::Quit)) => {
+                self.screen = std::mem::replace(previous, Screen::Playing);
+            }
+            (KeyCode::Enter, _) => {
+                if let Some((name, game)) = picker.slots.get(picker.selected).cloned() {
+                    self.resume(game);
+                    self.toasts.push(format!("Loaded {}", name));
                 }
             }
+            (_, Some(Action::MoveUp)) => picker.selected = (picker.selected + len - 1) % len,
+            (_, Some(Action::MoveDown)) => picker.selected = (picker.selected + 1) % len,
+            _ => {}
+        }
+    }
+
+    fn open_calendar(&mut self) {
+        let previous = std::mem::replace(&mut self.screen, Screen::Playing);
+        let calendar = Calendar::new(stats::today(), self.level);
+        self.screen = Screen::Calendar(calendar, Box::new(previous));
+    }
+
+    fn update_calendar(&mut self, key_event: KeyEvent) {
+        let action = self.keymap.action(&key_event);
+        let Screen::Calendar(calendar, previous) = &mut self.screen else {
+            return;
+        };
+        match (key_event.code, action) {
+            (KeyCode::Esc, _) | (_, Some(Action::Quit)) => {
+                self.screen = std::mem::replace(previous, Screen::Playing);
+            }
+            (KeyCode::Enter, _) => {
+                let (day, level) = (calendar.selected(), calendar.difficulty());
+                self.level = level;
+                self.start_daily(day);
+                self.record_start();
+            }
+            (KeyCode::Tab, _) => calendar.next_difficulty(),
+            (KeyCode::PageUp | KeyCode::Char('['), _) => calendar.previous_month(),
+            (KeyCode::PageDown | KeyCode::Char(']'), _) => calendar.next_month(),
+            (_, Some(Action::MoveLeft)) => calendar.move_by(-1),
+            (_, Some(Action::MoveRight)) => calendar.move_by(1),
+            (_, Some(Action::MoveUp)) => calendar.move_by(-7),
+            (_, Some(Action::MoveDown)) => calendar.move_by(7),
+            _ => {}
+        }
+    }
+
+    fn restart_splits(&mut self) {
+        if let Some(splits) = &mut self.splits {
+            *splits = Splits::new(splits.kind());
+            // boxes can be complete from the clues alone
+            splits.update(&self.puzzle, Duration::ZERO);
+        }
+    }
+
+    fn record_start(&mut self) {
+        // custom clue counts have no difficulty to hold records for
+        if self.custom_clues.is_none() {
+            self.stats.record_start(self.level);
+            self.save_stats();
+        }
+    }
+
+    // draw the current state, nothing changes while doing so
+    pub fn view(&self, frame: &mut Frame) {
+        frame.render_widget(self, frame.area());
+    }
+
+    fn handle_events(
+        &mut self,
+        events: &mut impl EventSource,
+        timeout: Duration,
+    ) -> io::Result<()> {
+        // Poll for an event with a timeout to avoid blocking
+        if events.poll(timeout)? {
+            if let Some(msg) = Msg::from_event(events.read()?) {
+                self.update(msg);
+            }
+        }
+        Ok(())
+    }
+
+    // the one way the game changes, whether from a key, a paste or time
+    // passing
+    pub fn update(&mut self, msg: Msg) {
+        match msg {
+            Msg::Key(key_event) => self.handle_key_event(key_event),
+            Msg::Paste(text) => self.handle_paste(&text),
+            Msg::Mouse(mouse_event) => self.handle_mouse_event(mouse_event),
+            Msg::Resize(..) => {}
+            Msg::Tick => self.tick(),
+        }
+    }
+
+    // hovering lights up the cell under the mouse, clicking selects it
+    fn handle_mouse_event(&mut self, mouse_event: MouseEvent) {
+        let playing = matches!(
+            self.screen,
+            Screen::Playing | Screen::Tutorial(_) | Screen::Editor(_)
+        );
+        let cell = match self.board_layout.get() {
+            Some((area, compact)) if playing => {
+                let grid = self.puzzle.grid();
+                SudokuGridWidget::new(&grid)
+                    .labels(self.labels)
+                    .compact(compact)
+                    .cell_at(area, mouse_event.column, mouse_event.row)
+            }
+            _ => None,
+        };
 
-            block.render(area, buf);
+        match mouse_event.kind {
+            MouseEventKind::Moved | MouseEventKind::Drag(_) => self.hovered = cell,
+            MouseEventKind::Down(MouseButton::Left) => {
+                if let Some((row, col)) = cell {
+                    self.select(row, col);
+                }
+            }
+            _ => {}
         }
     }
+
+    fn handle_paste(&mut self, text: &str) {
+        if let Some(line) = self.command_line.as_mut() {
+            line.extend(text.chars().filter(|c| !c.is_control()));
+            return;
+        }
+        if let Screen::Prompt(_, input) = &mut self.screen {
+            input.extend(text.chars().filter(|c| !c.is_control()));
+            return;
+        }
+        // only where starting a game over is what the player would expect
+        if !matches!(
+            self.screen,
+            Screen::MainMenu(_)
+                | Screen::NewGame(..)
+                | Screen::Playing
+                | Screen::Solved
+                | Screen::GameOver(_)
+        ) {
+            return;
+        }
+        if let Err(err) = self.load(text) {
+            self.toasts.push(err);
+        }
+    }
+
+    fn handle_key_event(&mut self, key_event: KeyEvent) {
+        // raw mode turns Ctrl+C into a key press, quit on it like the shell
+        // would have
+        if key_event.code == KeyCode::Char('c')
+            && key_event.modifiers.contains(KeyModifiers::CONTROL)
+        {
+            return self.exit();
+        }
+        match self.screen {
+            Screen::MainMenu(_) | Screen::NewGame(..) => self.update_menu(key_event),
+            Screen::Playing => self.update_playing(key_event),
+            Screen::Paused => self.update_paused(key_event),
+            // any key skips the animation
+            Screen::Celebrating(_) => self.after_celebration(),
+            Screen::Solved => self.update_solved(key_event),
+            Screen::GameOver(_) => self.update_game_over(key_event),
+            Screen::ConfirmReveal => self.update_confirm_reveal(key_event),
+            Screen::Prompt(..) => self.update_prompt(key_event),
+            Screen::ConfirmRecovery(_) => self.update_confirm_recovery(key_event),
+            Screen::Help(_) => self.update_help(key_event),
+            Screen::Stats(_) => self.update_stats(key_event),
+            Screen::Achievements(_) => self.update_achievements(key_event),
+            Screen::Saves(..) => self.update_saves(key_event),
+            Screen::Calendar(..) => self.update_calendar(key_event),
+            Screen::Solver(_) => self.update_solver(key_event),
+            Screen::Editor(_) => self.update_editor(key_event),
+            Screen::Replay(_) => self.update_replay(key_event),
+            Screen::Tutorial(_) => self.update_tutorial(key_event),
+            Screen::MarathonOver => self.exit(),
+        }
+    }
+
+    fn update_menu(&mut self, key_event: KeyEvent) {
+        let action = self.keymap.action(&key_event);
+        let is_main_menu = matches!(self.screen, Screen::MainMenu(_));
+        let shortcut = is_main_menu && !is_move(action);
+        let (Screen::MainMenu(menu) | Screen::NewGame(menu, _)) = &mut self.screen else {
+            return;
+        };
+
+        match (key_event.code, action) {
+            (KeyCode::Enter, _) => self.start_from_menu(),
+            (KeyCode::Esc, _) => {
+                if let Screen::NewGame(_, previous) = &mut self.screen {
+                    self.screen = std::mem::replace(previous, Screen::Playing);
+                }
+            }
+            // a letter the keymap moves with moves, vim's l included, so
+            // the menu's shortcuts take whichever case is left
+            (KeyCode::Char('s' | 'S'), _) if shortcut => self.open_stats(),
+            (KeyCode::Char('a' | 'A'), _) if shortcut => self.open_achievements(),
+            (KeyCode::Char('l' | 'L'), _) if shortcut => self.open_saves(),
+            (_, Some(Action::Quit)) if is_main_menu => self.exit(),
+            (_, Some(Action::MoveUp)) => menu.up(),
+            (_, Some(Action::MoveDown)) => menu.down(),
+            (_, Some(Action::MoveLeft)) => menu.less(),
+            (_, Some(Action::MoveRight)) => menu.more(),
+            _ => {}
+        }
+    }
+
+    fn update_playing(&mut self, key_event: KeyEvent) {
+        let before = self.puzzle.grid();
+        if self.command_line.is_some() {
+            self.handle_command_key(key_event);
+        } else if key_event.code == KeyCode::Esc && self.selection_anchor.is_some() {
+            self.selection_anchor = None;
+        } else if let Some(action) = self.keymap.action(&key_event) {
+            // after the box prefix the next digit picks the box instead of inserting
+            let action = match action {
+                Action::Insert(num) if self.box_jump_pending => Action::JumpToBox(num),
+                Action::Insert(num) if self.notes_mode => Action::Note(num),
+                action => action,
+            };
+            self.box_jump_pending = false;
+            self.message = None;
+
+            self.perform(action);
+        } else {
+            self.box_jump_pending = false;
+        }
+        self.track_last_move(&before);
+        if let Some(splits) = &mut self.splits {
+            splits.update(&self.puzzle, self.timer.elapsed());
+        }
+        self.move_log
+            .record(self.timer.elapsed(), &before, &self.puzzle.grid());
+
+        if self.screen == Screen::Playing && self.puzzle.is_solved() {
+            // a revealed game is forfeit, there is no win to celebrate
+            if !self.revealed {
+                self.solved();
+            }
+        } else if self.screen == Screen::Playing
+            && self.limited_mistakes
+            && self.puzzle.mistakes() >= MISTAKE_LIMIT
+        {
+            self.lose(Loss::Mistakes);
+        }
+    }
+
+    fn solved(&mut self) {
+        self.time_to_solve = self.timer.elapsed();
+        self.timer.pause();
+        self.screen = Screen::Celebrating(Celebration::new());
+
+        if let Some(marathon) = &mut self.marathon {
+            marathon.record(self.time_to_solve);
+            self.toasts.push(format!(
+                "Puzzle {} solved in {}",
+                marathon.solved(),
+                format_duration(self.time_to_solve)
+            ));
+        }
+
+        // custom clue counts have no difficulty to hold records for
+        if self.custom_clues.is_some() {
+            self.solve = None;
+            return;
+        }
+
+        // limited mistakes games keep records of their own
+        let hardcore = self.limited_mistakes;
+        let previous_best = self.stats.best_time(self.level, hardcore);
+        let is_new_best =
+            self.stats
+                .record_solve(self.level, self.time_to_solve, hardcore, stats::today());
+        self.solve = Some(SolveSummary {
+            previous_best,
+            is_new_best,
+        });
+
+        if is_new_best && hardcore {
+            self.toasts.push(self.lang.tr("new_best_hardcore"));
+        } else if is_new_best {
+            // the ghost races the plain best
+            self.toasts.push(self.lang.tr("new_best"));
+            self.stats
+                .record_ghost(self.level, &self.move_log.fill_times());
+        }
+        self.unlock_achievements();
+        if let Some(day) = self.daily {
+            if self.stats.record_daily(day, self.level, self.time_to_solve) {
+                self.toasts
+                    .push(format!("Daily puzzle for {} solved", format_day(day)));
+            }
+        }
+        if let Some(splits) = &self.splits {
+            self.stats
+                .record_splits(self.level, splits.kind(), &splits.times());
+        }
+        self.save_stats();
+    }
+
+    // toast whatever the solve just recorded has newly earned
+    fn unlock_achievements(&mut self) {
+        let solve = Solve {
+            difficulty: self.level,
+            time: self.time_to_solve,
+            hints: self.puzzle.hints(),
+            mistakes: self.puzzle.mistakes(),
+            limited_mistakes: self.limited_mistakes,
+            streak: self.stats.current_streak(stats::today()),
+            solved: self.stats.solved(),
+        };
+        for achievement in solve.achievements() {
+            if self.stats.unlock(achievement, stats::today()) {
+                self.toasts
+                    .push(format!("Achievement unlocked: {}", achievement.title()));
+            }
+        }
+    }
+
+    fn save_stats(&mut self) {
+        if let Some(dir) = &self.data_dir {
+            if let Err(err) = self.stats.save(dir) {
+                self.message = Some(format!("could not save stats: {}", err));
+            }
+        }
+    }
+
+    fn update_paused(&mut self, key_event: KeyEvent) {
+        match self.keymap.action(&key_event) {
+            Some(Action::Pause) => {
+                self.timer.resume();
+                self.screen = Screen::Playing;
+            }
+            Some(Action::Quit) => self.quit(),
+            _ => {}
+        }
+    }
+
+    fn update_solved(&mut self, key_event: KeyEvent) {
+        if let KeyCode::Char('w' | 'W') = key_event.code {
+            self.screen = Screen::Replay(Box::new(Replay::new(self.move_log.clone())));
+            return;
+        }
+
+        match self.keymap.action(&key_event) {
+            Some(Action::Quit) => self.quit(),
+            Some(Action::NewGame) => self.open_new_game_dialog(),
+            _ => {}
+        }
+    }
+
+    fn update_replay(&mut self, key_event: KeyEvent) {
+        let Screen::Replay(replay) = &mut self.screen else {
+            return;
+        };
+
+        match key_event.code {
+            KeyCode::Esc => self.screen = Screen::Solved,
+            KeyCode::Char(' ') => replay.toggle_pause(),
+            KeyCode::Char('+') => replay.faster(),
+            KeyCode::Char('-') => replay.slower(),
+            _ => {}
+        }
+    }
+
+    fn update_game_over(&mut self, key_event: KeyEvent) {
+        match self.keymap.action(&key_event) {
+            Some(Action::Quit) => self.quit(),
+            Some(Action::NewGame) => self.open_new_game_dialog(),
+            Some(Action::Reset) => self.retry(),
+            _ => {}
+        }
+    }
+
+    fn update_confirm_reveal(&mut self, key_event: KeyEvent) {
+        match key_event.code {
+            KeyCode::Enter | KeyCode::Char('y') => {
+                // stay on the board so it can be compared, undo brings the
+                // player's own entries back
+                self.puzzle.reveal();
+                self.revealed = true;
+                self.timer.pause();
+                self.screen = Screen::Playing;
+                self.message = Some("solution revealed, U shows your entries again".into());
+            }
+            KeyCode::Esc | KeyCode::Char('n') => self.screen = Screen::Playing,
+            _ => {}
+        }
+    }
+
+    // digits put clues on the board and Enter plays it, Esc goes back to the
+    // game without a change
+    fn update_editor(&mut self, key_event: KeyEvent) {
+        let Screen::Editor(grid) = &mut self.screen else {
+            return;
+        };
+        let (row, col) = (self.selected_row, self.selected_col);
+        match key_event.code {
+            KeyCode::Esc => self.screen = Screen::Playing,
+            KeyCode::Enter => {
+                let board: String = grid
+                    .iter()
+                    .flatten()
+                    .map(|cell| (b'0' + cell.value()) as char)
+                    .collect();
+                if let Err(err) = self.load(&board) {
+                    self.toasts.push(err);
+                }
+            }
+            _ => match self.keymap.action(&key_event) {
+                Some(Action::Insert(num)) => grid[row][col] = puzzle::Cell::new(num, true),
+                Some(Action::Clear) => grid[row][col] = puzzle::Cell::new(0, false),
+                Some(
+                    action @ (Action::MoveLeft
+                    | Action::MoveRight
+                    | Action::MoveUp
+                    | Action::MoveDown),
+                ) => self.perform(action),
+                _ => {}
+            },
+        }
+    }
+
+    // Enter runs the command with what was typed as its argument, the game
+    // carrying on either way
+    fn update_prompt(&mut self, key_event: KeyEvent) {
+        let Screen::Prompt(prompt, input) = &mut self.screen else {
+            return;
+        };
+        match key_event.code {
+            KeyCode::Enter if !input.trim().is_empty() => {
+                let line = format!("{} {}", prompt.name(), input);
+                self.screen = Screen::Playing;
+                match line.parse::<Command>() {
+                    Ok(command) => self.execute(command),
+                    Err(err) => self.message = Some(err),
+                }
+            }
+            KeyCode::Esc => self.screen = Screen::Playing,
+            KeyCode::Backspace => {
+                input.pop();
+            }
+            KeyCode::Char(c) => input.push(c),
+            _ => {}
+        }
+    }
+
+    // the recovered game is played or thrown away, either way it is only
+    // offered once
+    fn update_confirm_recovery(&mut self, key_event: KeyEvent) {
+        let (Screen::ConfirmRecovery(menu), Some(dir)) = (&self.screen, self.data_dir.clone())
+        else {
+            return;
+        };
+        let menu = menu.clone();
+        match key_event.code {
+            KeyCode::Enter | KeyCode::Char('y') => match SavedGame::load_recovery(&dir) {
+                Ok(Some(game)) => {
+                    self.resume_session(game);
+                    self.toasts.push("Restored the game from before the crash");
+                }
+                Ok(None) => self.screen = Screen::MainMenu(menu),
+                Err(err) => {
+                    self.toasts
+                        .push(format!("Could not restore the game: {}", err));
+                    self.screen = Screen::MainMenu(menu);
+                }
+            },
+            KeyCode::Esc | KeyCode::Char('n') => self.screen = Screen::MainMenu(menu),
+            _ => return,
+        }
+        if let Err(err) = SavedGame::remove_recovery(&dir) {
+            self.toasts
+                .push(format!("Could not remove the recovered game: {}", err));
+        }
+    }
+
+    // play the lost puzzle again from the start
+    fn retry(&mut self) {
+        self.puzzle.restart();
+        self.move_log = MoveLog::new(self.puzzle.grid());
+        self.revealed = false;
+        self.message = None;
+        self.timer = Stopwatch::start();
+        self.screen = Screen::Playing;
+        self.restart_splits();
+        self.select(0, 0);
+        self.record_start();
+    }
+
+    fn update_help(&mut self, key_event: KeyEvent) {
+        // the overlay swallows every key until it is dismissed
+        if key_event.code == KeyCode::Esc || self.keymap.action(&key_event) == Some(Action::Help) {
+            if let Screen::Help(previous) = &mut self.screen {
+                self.screen = std::mem::replace(previous, Screen::Playing);
+            }
+        }
+    }
+
+    fn open_solver(&mut self) {
+        self.screen = Screen::Solver(SolverView {
+            step: None,
+            paused: false,
+            speed: SOLVER_SPEED,
+            countdown: SOLVER_SPEED,
+        });
+        self.solver_advance();
+    }
+
+    fn update_solver(&mut self, key_event: KeyEvent) {
+        let Screen::Solver(view) = &mut self.screen else {
+            return;
+        };
+
+        match key_event.code {
+            KeyCode::Esc => {
+                self.screen = Screen::Playing;
+                self.message = None;
+            }
+            KeyCode::Char(' ') => view.paused = !view.paused,
+            KeyCode::Right => self.solver_advance(),
+            KeyCode::Char('+') => view.speed = view.speed.saturating_sub(1).max(1),
+            KeyCode::Char('-') => view.speed = (view.speed + 1).min(SOLVER_SLOWEST),
+            _ => {}
+        }
+    }
+
+    // place the highlighted step, if any, and highlight the one after it
+    fn solver_advance(&mut self) {
+        let Screen::Solver(view) = &mut self.screen else {
+            return;
+        };
+
+        if let Some(step) = view.step.take() {
+            // a board finished by the solver is no win of the player's
+            self.revealed = true;
+            let _ = self.puzzle.insert_number(step.row, step.col, step.num);
+            self.last_move = Some((step.row, step.col));
+            self.last_move_ticks = LAST_MOVE_TICKS;
+        }
+
+        view.step = solver::next_step(&self.puzzle.grid());
+        view.countdown = view.speed;
+
+        let controls = "Space/→/+/-/Esc";
+        self.message = Some(match &view.step {
+            Some(step) => format!(
+                "{}: {} at R{}C{}  [{}]",
+                step.technique.name(),
+                step.num,
+                step.row + 1,
+                step.col + 1,
+                controls
+            ),
+            None if self.puzzle.is_solved() => format!("solved  [{}]", controls),
+            None => format!("no technique applies  [{}]", controls),
+        });
+        if view.step.is_none() {
+            view.paused = true;
+        }
+    }
+
+    // load a lesson's board, nothing played here counts towards the stats
+    fn start_tutorial(&mut self, index: usize) {
+        let lesson = &LESSONS[index];
+        self.puzzle = Puzzle::from_board(lesson.board).expect("lesson boards are solvable");
+        self.move_log = MoveLog::new(self.puzzle.grid());
+        self.timer = Stopwatch::start();
+        self.timer.pause();
+        self.selection_anchor = None;
+        self.message = None;
+        self.screen = Screen::Tutorial(index);
+        self.select(4, 4);
+    }
+
+    fn update_tutorial(&mut self, key_event: KeyEvent) {
+        let Screen::Tutorial(index) = self.screen else {
+            return;
+        };
+        if key_event.code == KeyCode::Esc {
+            self.screen = Screen::MainMenu(self.main_menu());
+            return;
+        }
+        let Some(action) = self.keymap.action(&key_event) else {
+            self.box_jump_pending = false;
+            return;
+        };
+        let action = match action {
+            Action::Insert(num) if self.box_jump_pending => Action::JumpToBox(num),
+            action => action,
+        };
+        self.box_jump_pending = false;
+        self.message = None;
+
+        match action {
+            Action::Insert(num) => self.place_in_tutorial(index, num),
+            // getting around the board is all a lesson needs
+            Action::Quit
+            | Action::Help
+            | Action::MoveLeft
+            | Action::MoveRight
+            | Action::MoveUp
+            | Action::MoveDown
+            | Action::NextEmpty
+            | Action::PrevEmpty
+            | Action::BoxPrefix
+            | Action::JumpToBox(_) => self.perform(action),
+            _ => self.message = Some("not available in the tutorial, Esc leaves it".into()),
+        }
+    }
+
+    // only the lesson's placement is taken, which moves on to the next one
+    fn place_in_tutorial(&mut self, index: usize, num: u8) {
+        let (row, col, answer) = LESSONS[index].target;
+        if (self.selected_row, self.selected_col) != (row, col) {
+            self.message = Some("this lesson is about the green cell".into());
+        } else if num != answer {
+            self.message = Some(format!("{} does not fit there, read the lesson again", num));
+        } else if index + 1 < LESSONS.len() {
+            self.toasts
+                .push(format!("Well done! Next: {}", LESSONS[index + 1].title));
+            self.start_tutorial(index + 1);
+        } else {
+            self.toasts.push("Tutorial complete".to_string());
+            self.screen = Screen::MainMenu(self.main_menu());
+        }
+    }
+
+    fn update_stats(&mut self, key_event: KeyEvent) {
+        if matches!(key_event.code, KeyCode::Esc | KeyCode::Char('s')) {
+            if let Screen::Stats(previous) = &mut self.screen {
+                self.screen = std::mem::replace(previous, Screen::Playing);
+            }
+        }
+    }
+
+    fn update_achievements(&mut self, key_event: KeyEvent) {
+        if matches!(key_event.code, KeyCode::Esc | KeyCode::Char('a')) {
+            if let Screen::Achievements(previous) = &mut self.screen {
+                self.screen = std::mem::replace(previous, Screen::Playing);
+            }
+        }
+    }
+
+    fn open_achievements(&mut self) {
+        let previous = std::mem::replace(&mut self.screen, Screen::Playing);
+        self.screen = Screen::Achievements(Box::new(previous));
+    }
+
+    fn open_stats(&mut self) {
+        let previous = std::mem::replace(&mut self.screen, Screen::Playing);
+        self.screen = Screen::Stats(Box::new(previous));
+    }
+
+    fn open_new_game_dialog(&mut self) {
+        let previous = std::mem::replace(&mut self.screen, Screen::Playing);
+        self.screen = Screen::NewGame(
+            Menu::new_game(self.level, self.custom_clues).with_lang(self.lang),
+            Box::new(previous),
+        );
+    }
+
+    fn perform(&mut self, action: Action) {
+        if self.selection_anchor.is_some() {
+            if let Action::Insert(_) | Action::Clear | Action::Color(_) | Action::ClearColor =
+                action
+            {
+                return self.perform_on_selection(action);
+            }
+        }
+
+        match action {
+            Action::Quit => self.quit(),
+            Action::NewGame => self.open_new_game_dialog(),
+            Action::Reset => {
+                self.puzzle.reset();
+            }
+            Action::Hint => self.hint(),
+            Action::MoveLeft => {
+                self.selected_col = self.selected_col.saturating_sub(1);
+            }
+            Action::MoveRight => {
+                self.selected_col = self.selected_col.saturating_add(1).min(8);
+            }
+            Action::MoveUp => {
+                self.selected_row = self.selected_row.saturating_sub(1);
+            }
+            Action::MoveDown => {
+                self.selected_row = self.selected_row.saturating_add(1).min(8);
+            }
+            Action::NextEmpty => {
+                if let Some((row, col)) = self
+                    .puzzle
+                    .next_empty_cell(self.selected_row, self.selected_col)
+                {
+                    self.select(row, col);
+                }
+            }
+            Action::PrevEmpty => {
+                if let Some((row, col)) = self
+                    .puzzle
+                    .prev_empty_cell(self.selected_row, self.selected_col)
+                {
+                    self.select(row, col);
+                }
+            }
+            Action::BoxPrefix => {
+                self.box_jump_pending = true;
+            }
+            Action::JumpToBox(num) => {
+                // boxes are numbered 1-9 in reading order, land on the center cell
+                let index = (num - 1) as usize;
+                self.select(index / 3 * 3 + 1, index % 3 * 3 + 1);
+            }
+            Action::Insert(num) => {
+                let result = self.insert(self.selected_row, self.selected_col, num);
+                let inserted = result.is_ok();
+                self.report((self.selected_row, self.selected_col), result);
+
+                if let Some(&(row, col)) = self
+                    .puzzle
+                    .conflicts(self.selected_row, self.selected_col)
+                    .first()
+                    .filter(|_| inserted)
+                {
+                    self.message = Some(format!("conflict with R{}C{}", row + 1, col + 1));
+                }
+            }
+            Action::Clear => {
+                let result = self.puzzle.clear_cell(self.selected_row, self.selected_col);
+                self.report((self.selected_row, self.selected_col), result);
+            }
+            Action::Note(num) => {
+                let (row, col) = (self.selected_row, self.selected_col);
+                let on = !self.puzzle.grid()[row][col].notes().contains(&num);
+                let result = self.puzzle.set_note(row, col, num, on);
+                // a rejected note leaves the auto notes as they were
+                if result.is_ok() {
+                    self.stop_auto_notes();
+                }
+                self.report((row, col), result);
+            }
+            Action::NotesMode => {
+                self.notes_mode = !self.notes_mode;
+                self.message = Some(if self.notes_mode {
+                    "notes mode: digits pencil mark".into()
+                } else {
+                    "notes mode off".into()
+                });
+            }
+            Action::Undo => {
+                self.message = Some(match self.puzzle.undo() {
+                    Some(changes) => format!("undid: {}", describe_move(&changes)),
+                    None => "nothing to undo".into(),
+                });
+            }
+            Action::Redo => {
+                self.message = Some(match self.puzzle.redo() {
+                    Some(changes) => format!("redid: {}", describe_move(&changes)),
+                    None => "nothing to redo".into(),
+                });
+            }
+            Action::Pause => {
+                self.timer.pause();
+                self.screen = Screen::Paused;
+            }
+            Action::Help => {
+                let previous = std::mem::replace(&mut self.screen, Screen::Playing);
+                self.screen = Screen::Help(Box::new(previous));
+            }
+            Action::Command => {
+                self.command_line = Some(String::new());
+            }
+            Action::Reveal => {
+                self.screen = Screen::ConfirmReveal;
+            }
+            Action::Color(color) => {
+                self.puzzle
+                    .set_color(self.selected_row, self.selected_col, color);
+            }
+            Action::ClearColor => {
+                self.puzzle
+                    .set_color(self.selected_row, self.selected_col, 0);
+            }
+            Action::Select => {
+                self.selection_anchor = match self.selection_anchor {
+                    Some(_) => None,
+                    None => Some((self.selected_row, self.selected_col)),
+                };
+            }
+            Action::Check => self.check(false),
+            Action::CheckAndClear => self.check(true),
+            Action::NewTab => {
+                let game = self.park();
+                self.tabs.insert(self.tab, game);
+                self.tab = self.tabs.len();
+                self.new_game();
+            }
+            Action::NextTab if self.tabs.is_empty() => {
+                self.toasts.push("No other tabs, Ctrl+T opens one")
+            }
+            Action::NextTab => {
+                let game = self.park();
+                self.tabs.insert(self.tab, game);
+                self.tab = (self.tab + 1) % self.tabs.len();
+                let game = self.tabs.remove(self.tab);
+                self.unpark(game);
+            }
+            Action::ToggleClock => {
+                self.clock_hidden = !self.clock_hidden;
+                self.toasts.push(if self.clock_hidden {
+                    "Clock hidden, the time still counts"
+                } else {
+                    "Clock shown"
+                });
+            }
+            Action::Copy(entries) => {
+                let board = self.puzzle.to_board(entries);
+                self.output.push_str(&clipboard::copy_sequence(&board));
+                self.toasts.push(if entries {
+                    "Copied the puzzle and your entries"
+                } else {
+                    "Copied the puzzle"
+                });
+            }
+        }
+    }
+
+    // remember which value changed, preferring the cursor's cell when a move
+    // touched several
+    fn track_last_move(&mut self, before: &Grid) {
+        let grid = self.puzzle.grid();
+        let changed =
+            |&(row, col): &(usize, usize)| grid[row][col].value() != before[row][col].value();
+
+        let cursor = (self.selected_row, self.selected_col);
+        let last_move = Some(cursor).filter(changed).or_else(|| {
+            (0..9)
+                .flat_map(|row| (0..9).map(move |col| (row, col)))
+                .find(changed)
+        });
+
+        if last_move.is_some() {
+            self.last_move = last_move;
+            self.last_move_ticks = LAST_MOVE_TICKS;
+        }
+    }
+
+    // the block between the selection anchor and the cursor
+    fn selection(&self) -> Vec<(usize, usize)> {
+        let Some((row, col)) = self.selection_anchor else {
+            return Vec::new();
+        };
+        let rows = row.min(self.selected_row)..=row.max(self.selected_row);
+        let cols = col.min(self.selected_col)..=col.max(self.selected_col);
+        rows.flat_map(|r| cols.clone().map(move |c| (r, c)))
+            .collect()
+    }
+
+    // apply a cell action to every selected cell as one move, clues and
+    // filled cells that reject it are skipped
+    fn perform_on_selection(&mut self, action: Action) {
+        let cells = self.selection();
+        let strict = self.strict;
+        let changed = self.puzzle.grouped(|puzzle| {
+            cells
+                .iter()
+                .filter(|&&(row, col)| match action {
+                    Action::Insert(num) if strict => puzzle.insert_if_right(row, col, num).is_ok(),
+                    Action::Insert(num) => puzzle.insert_number(row, col, num).is_ok(),
+                    Action::Clear => puzzle.clear_cell(row, col).is_ok(),
+                    Action::Color(color) => {
+                        puzzle.set_color(row, col, color);
+                        true
+                    }
+                    Action::ClearColor => {
+                        puzzle.set_color(row, col, 0);
+                        true
+                    }
+                    _ => false,
+                })
+                .count()
+        });
+
+        self.selection_anchor = None;
+        self.message = Some(format!("changed {} of {} cells", changed, cells.len()));
+    }
+
+    fn hint(&mut self) {
+        if self.puzzle.hints() >= self.hint_budget {
+            self.message = Some("no hints left".into());
+            return;
+        }
+
+        if let Some((row, col)) = self
+            .puzzle
+            .hint_target(self.selected_row, self.selected_col)
+        {
+            self.select(row, col);
+            let result = self.puzzle.hint(row, col);
+            if result.is_ok() {
+                self.move_log.record_hint(self.timer.elapsed(), row, col);
+            }
+            self.report((row, col), result);
+        }
+    }
+
+    fn check(&mut self, clear: bool) {
+        let wrong = self.puzzle.wrong_entries();
+        self.message = Some(match (wrong.len(), clear) {
+            (0, _) => "no wrong entries so far".into(),
+            (1, false) => "1 wrong entry".into(),
+            (n, false) => format!("{} wrong entries", n),
+            (n, true) => format!(
+                "cleared {} wrong entr{}",
+                n,
+                if n == 1 { "y" } else { "ies" }
+            ),
+        });
+
+        if clear {
+            self.puzzle.clear_wrong_entries();
+        }
+        self.checked = wrong;
+        self.check_ticks = CHECK_TICKS;
+    }
+
+    fn insert(&mut self, row: usize, col: usize, num: u8) -> Result<(), MoveError> {
+        if self.strict {
+            self.puzzle.insert_if_right(row, col, num)
+        } else {
+            self.puzzle.insert_number(row, col, num)
+        }
+    }
+
+    // auto notes would write over notes made by hand, so they stop
+    fn stop_auto_notes(&mut self) {
+        if self.puzzle.auto_notes() {
+            self.puzzle.set_auto_notes(false);
+            self.toasts.push("Notes are no longer updated");
+        }
+    }
+
+    // a rejected move is spelled out in the status bar, flashed and
+    // optionally rung
+    fn report(&mut self, (row, col): (usize, usize), result: Result<(), MoveError>) {
+        if let Err(err) = result {
+            self.message = Some(err.to_string());
+            self.flash = Some((row, col));
+            self.flash_ticks = FLASH_TICKS;
+            if self.bell {
+                self.output.push('\x07');
+            }
+        }
+    }
+
+    fn handle_command_key(&mut self, key_event: KeyEvent) {
+        let Some(line) = self.command_line.as_mut() else {
+            return;
+        };
+
+        match key_event.code {
+            KeyCode::Esc => self.command_line = None,
+            KeyCode::Enter => {
+                let input = self.command_line.take().unwrap_or_default();
+                match input.parse::<Command>() {
+                    Ok(command) => self.execute(command),
+                    Err(err) => self.message = Some(err),
+                }
+            }
+            KeyCode::Tab => {
+                let (completed, candidates) = command::complete(line);
+                *line = completed;
+                self.message = (candidates.len() > 1).then(|| candidates.join(" "));
+            }
+            // backspacing past the start leaves command mode like in vim
+            KeyCode::Backspace if line.is_empty() => self.command_line = None,
+            KeyCode::Backspace => {
+                line.pop();
+            }
+            KeyCode::Char(c) => line.push(c),
+            _ => {}
+        }
+    }
+
+    fn execute(&mut self, command: Command) {
+        match command {
+            Command::New(game) => {
+                match game {
+                    Some(NewGame::Difficulty(level)) => {
+                        self.level = level;
+                        self.custom_clues = None;
+                    }
+                    Some(NewGame::Clues(clues)) => self.custom_clues = Some(clues),
+                    None => {}
+                }
+                self.new_game();
+            }
+            Command::Hint => self.perform(Action::Hint),
+            Command::Reset => self.perform(Action::Reset),
+            Command::Undo => self.perform(Action::Undo),
+            Command::Redo => self.perform(Action::Redo),
+            Command::Help => self.perform(Action::Help),
+            Command::Reveal => self.perform(Action::Reveal),
+            Command::Check(false) => self.perform(Action::Check),
+            Command::Check(true) => self.perform(Action::CheckAndClear),
+            Command::Place { row, col, num } => {
+                let result = self.insert(row, col, num);
+                self.report((row, col), result);
+            }
+            Command::Solver => self.open_solver(),
+            Command::Edit => {
+                self.select(0, 0);
+                self.screen = Screen::Editor(Box::new([[puzzle::Cell::new(0, false); 9]; 9]));
+            }
+            Command::Notes(None) => {
+                self.puzzle.fill_notes();
+                self.message = Some("filled in every candidate".into());
+            }
+            Command::Notes(Some(auto_notes)) => {
+                self.puzzle.set_auto_notes(auto_notes);
+                self.message = Some(if auto_notes {
+                    "notes are kept up to date".into()
+                } else {
+                    "notes are no longer updated".into()
+                });
+            }
+            Command::Singles => {
+                self.message = Some(match self.puzzle.fill_singles() {
+                    0 => "no cell has a single candidate".into(),
+                    1 => "filled 1 cell".into(),
+                    n => format!("filled {} cells", n),
+                });
+            }
+            // a board is 81 digits and dots, anything else names a save, an
+            // all digit name like 2024 included
+            Command::Load(board)
+                if board.len() == 81 && board.chars().all(|c| c.is_ascii_digit() || c == '.') =>
+            {
+                if let Err(err) = self.load(&board) {
+                    self.message = Some(err);
+                }
+            }
+            Command::Load(name) => self.load_slot(&name),
+            Command::Save(name) => self.save_slot(&name),
+            Command::Saves => self.open_saves(),
+            Command::Export { path, entries } => {
+                let board = ExportFormat::from_path(&path).write(&self.puzzle, entries);
+                self.message = Some(match std::fs::write(&path, board) {
+                    Ok(()) => format!("wrote the board to {}", path.display()),
+                    Err(err) => format!("could not write {}: {}", path.display(), err),
+                });
+            }
+            Command::ExportAnalytics(path) => {
+                let analytics = Analytics::new(&self.puzzle, &self.move_log, self.timer.elapsed());
+                self.message = Some(match std::fs::write(&path, analytics.to_json()) {
+                    Ok(()) => format!("wrote the game's analytics to {}", path.display()),
+                    Err(err) => format!("could not write {}: {}", path.display(), err),
+                });
+            }
+            Command::Prompt(prompt) => self.screen = Screen::Prompt(prompt, String::new()),
+            Command::Quit => self.perform(Action::Quit),
+        }
+    }
+
+    fn start_from_menu(&mut self) {
+        let (Screen::MainMenu(menu) | Screen::NewGame(menu, _)) = &self.screen else {
+            return;
+        };
+
+        match menu.selected() {
+            MenuItem::Difficulty(level) => {
+                self.level = level;
+                self.custom_clues = None;
+            }
+            MenuItem::Custom => {
+                self.custom_clues = Some(menu.custom_clues());
+            }
+            MenuItem::Resume => return self.resume_saved_game(),
+            MenuItem::Tutorial => return self.start_tutorial(0),
+            MenuItem::Calendar => return self.open_calendar(),
+            MenuItem::Statistics => return self.open_stats(),
+            MenuItem::Achievements => return self.open_achievements(),
+        }
+
+        self.new_game();
+    }
+
+    fn select(&mut self, row: usize, col: usize) {
+        self.selected_row = row;
+        self.selected_col = col;
+    }
+
+    // a marathon shows its totals before the game ends
+    fn quit(&mut self) {
+        match self.marathon {
+            Some(_) if self.screen != Screen::MarathonOver => {
+                self.timer.pause();
+                self.screen = Screen::MarathonOver;
+            }
+            _ => self.exit(),
+        }
+    }
+
+    // the win screen, or the next puzzle straight away in a marathon
+    fn after_celebration(&mut self) {
+        match self.marathon {
+            Some(_) => self.new_game(),
+            None => self.screen = Screen::Solved,
+        }
+    }
+
+    fn exit(&mut self) {
+        self.exit = true;
+    }
+
+    fn render_screen(&self, screen: &Screen, area: Rect, buf: &mut Buffer) {
+        match screen {
+            Screen::MainMenu(menu) => menu.render(area, buf),
+            Screen::Playing => self.render_playing(area, buf),
+            Screen::NewGame(menu, previous) => {
+                self.render_screen(previous, area, buf);
+                menu.render(area, buf);
+            }
+            Screen::Paused => self.render_paused(area, buf),
+            Screen::Celebrating(celebration) => {
+                self.render_playing(area, buf);
+                celebration.render(area, buf);
+            }
+            Screen::Solved => self.render_solved(area, buf),
+            Screen::GameOver(loss) => self.render_game_over(*loss, area, buf),
+            Screen::ConfirmReveal => {
+                self.render_playing(area, buf);
+                Dialog::new(" Reveal Solution ")
+                    .body(Text::from(vec![
+                        Line::from("Fill in the whole board from the solution?"),
+                        Line::from("The game will not count towards best times."),
+                    ]))
+                    .button("Cancel", "Esc")
+                    .button("Reveal", "Enter")
+                    .render(area, buf);
+            }
+            Screen::Prompt(prompt, input) => {
+                self.render_playing(area, buf);
+                Dialog::new(prompt.title())
+                    .body(prompt.question())
+                    .input(input)
+                    .button("Cancel", "Esc")
+                    .button(prompt.button(), "Enter")
+                    .render(area, buf);
+            }
+            Screen::ConfirmRecovery(menu) => {
+                menu.render(area, buf);
+                Dialog::new(" Restore Game ")
+                    .body(Text::from(vec![
+                        Line::from("The last game ended in a crash."),
+                        Line::from("Carry on with it where it was?"),
+                    ]))
+                    .button("Discard", "Esc")
+                    .button("Restore", "Enter")
+                    .render(area, buf);
+            }
+            Screen::Help(_) => self.render_help(area, buf),
+            Screen::Stats(_) => self.render_stats(area, buf),
+            Screen::Achievements(_) => self.render_achievements(area, buf),
+            Screen::Saves(picker, _) => self.render_saves(picker, area, buf),
+            Screen::Calendar(calendar, _) => self.render_calendar(calendar, area, buf),
+            Screen::Solver(_) | Screen::Replay(_) => self.render_playing(area, buf),
+            Screen::Tutorial(index) => self.render_tutorial(&LESSONS[*index], *index, area, buf),
+            Screen::Editor(grid) => self.render_editor(grid, area, buf),
+            Screen::MarathonOver => self.render_marathon_over(area, buf),
+        }
+    }
+
+    fn render_status(&self, area: Rect, compact: bool, buf: &mut Buffer) {
+        if let Screen::Replay(replay) = &self.screen {
+            let state = if replay.is_finished() {
+                "finished"
+            } else if replay.is_paused() {
+                "paused"
+            } else {
+                "playing"
+            };
+            return Line::from(format!(
+                " replay {} {}x {}  [Space/+/-/Esc]",
+                state,
+                replay.speed(),
+                format_duration(replay.clock())
+            ))
+            .yellow()
+            .render(area, buf);
+        }
+
+        if let Screen::Editor(grid) = &self.screen {
+            let clues = grid
+                .iter()
+                .flatten()
+                .filter(|cell| cell.value() != 0)
+                .count();
+            let key = if clues == 1 {
+                "editor_clue"
+            } else {
+                "editor_clues"
+            };
+            let clues = self.lang.format(key, &[&clues]);
+            return Line::from(format!(" {}  [1-9/0/Enter/Esc]", clues))
+                .yellow()
+                .render(area, buf);
+        }
+
+        let line = match (&self.command_line, &self.message) {
+            (Some(line), _) => Line::from(format!(":{}█", line)),
+            (None, Some(message)) => Line::from(format!(" {}", message).yellow()),
+            // cells are often too small to show notes, so the selected one's
+            // are spelled out here
+            (None, None) => {
+                let notes = self.puzzle.grid()[self.selected_row][self.selected_col].notes();
+                if notes.is_empty() {
+                    Line::default()
+                } else {
+                    let notes: Vec<String> = notes.iter().map(u8::to_string).collect();
+                    Line::from(format!(" notes: {}", notes.join(" ")).dark_gray())
+                }
+            }
+        };
+        line.render(area, buf);
+
+        // the compact board has no border to hold the clock
+        if compact {
+            return Line::from(format!("{} ", self.clock()))
+                .right_aligned()
+                .render(area, buf);
+        }
+
+        let hints_left = self.hint_budget.saturating_sub(self.puzzle.hints());
+        let mistakes = if self.limited_mistakes {
+            format!("{}/{}", self.puzzle.mistakes(), MISTAKE_LIMIT)
+        } else {
+            self.puzzle.mistakes().to_string()
+        };
+        let marathon = match self.marathon {
+            Some(marathon) => format!("Marathon: {} solved  ", marathon.solved()),
+            None => String::new(),
+        };
+        Line::from(format!(
+            "{}Hints: {}/{}  Mistakes: {} ",
+            marathon, hints_left, self.hint_budget, mistakes
+        ))
+        .right_aligned()
+        .render(area, buf);
+    }
+
+    fn render_paused(&self, area: Rect, buf: &mut Buffer) {
+        // the board stays hidden so the pause can't be used to think for free
+        let lang = self.lang;
+        let body = if self.clock_hidden {
+            Text::from(lang.tr("clock_stopped"))
+        } else {
+            Text::from(lang.format("elapsed", &[&format_duration(self.timer.elapsed())]))
+        };
+        let (pause, quit) = (self.key_label(Action::Pause), self.key_label(Action::Quit));
+        Dialog::new(format!(" {} ", lang.tr("paused")))
+            .body(body)
+            .button(lang.tr("resume"), &pause)
+            .button(lang.tr("quit"), &quit)
+            .render(area, buf);
+    }
+
+    fn render_solved(&self, area: Rect, buf: &mut Buffer) {
+        let lang = self.lang;
+        let level = match self.custom_clues {
+            Some(clues) => lang.format("custom_level", &[&clues]),
+            None => lang.difficulty(self.level).into(),
+        };
+
+        let mut lines = vec![
+            Line::from(lang.tr("congratulations")).green().bold(),
+            Line::default(),
+            Line::from(lang.format("difficulty", &[&level])),
+            Line::from(lang.format("solved_in", &[&format_duration(self.time_to_solve)])).bold(),
+            Line::from(lang.format(
+                "mistakes_hints",
+                &[&self.puzzle.mistakes(), &self.puzzle.hints()],
+            )),
+        ];
+
+        if let Some(solve) = self.solve {
+            let (previous_best, previous_best_none, new_best) = if self.limited_mistakes {
+                (
+                    "previous_best_hardcore",
+                    "previous_best_hardcore_none",
+                    "new_best_hardcore",
+                )
+            } else {
+                ("previous_best", "previous_best_none", "new_best")
+            };
+            lines.push(Line::from(match solve.previous_best {
+                Some(best) => lang.format(previous_best, &[&format_duration(best)]),
+                None => lang.tr(previous_best_none).into(),
+            }));
+
+            if solve.is_new_best {
+                lines.push(Line::from(lang.tr(new_best)).yellow().bold());
+            } else if let Some(best) = solve.previous_best {
+                let behind = self.time_to_solve.saturating_sub(best);
+                lines.push(Line::from(
+                    lang.format("behind_best", &[&format_duration(behind)]),
+                ));
+            }
+        }
+        let text = Text::from(lines);
+
+        let (quit, new_game) = (
+            self.key_label(Action::Quit),
+            self.key_label(Action::NewGame),
+        );
+        Dialog::new(format!(" {} ", lang.tr("solved")))
+            .body(text)
+            .button(lang.tr("quit"), &quit)
+            .button(lang.tr("new_game"), &new_game)
+            .button(lang.tr("replay"), "W")
+            .render(area, buf);
+    }
+
+    fn render_marathon_over(&self, area: Rect, buf: &mut Buffer) {
+        let marathon = self.marathon.unwrap_or_default();
+        let time = |duration: Option<Duration>| duration.map_or("-".into(), format_duration);
+        Dialog::new(" Marathon Over ")
+            .body(Text::from(vec![
+                Line::from(format!("Puzzles solved: {}", marathon.solved())).bold(),
+                Line::default(),
+                Line::from(format!("Total time: {}", format_duration(marathon.total()))),
+                Line::from(format!("Average: {}", time(marathon.average()))),
+                Line::from(format!("Fastest: {}", time(marathon.fastest()))),
+            ]))
+            .button("Quit", "any key")
+            .render(area, buf);
+    }
+
+    fn render_game_over(&self, loss: Loss, area: Rect, buf: &mut Buffer) {
+        let lang = self.lang;
+        let reason = match loss {
+            Loss::Mistakes => lang.format("lost_mistakes", &[&MISTAKE_LIMIT]),
+            Loss::TimeUp => lang.tr("time_up").into(),
+        };
+
+        Dialog::new(format!(" {} ", lang.tr("game_over")))
+            .body(Text::from(vec![
+                Line::from(reason).red().bold(),
+                Line::default(),
+                Line::from(lang.format("time_played", &[&format_duration(self.timer.elapsed())])),
+            ]))
+            .button(lang.tr("retry"), &self.key_label(Action::Reset))
+            .button(lang.tr("new_game"), &self.key_label(Action::NewGame))
+            .button(lang.tr("quit"), &self.key_label(Action::Quit))
+            .render(area, buf);
+    }
+
+    fn render_help(&self, area: Rect, buf: &mut Buffer) {
+        let help = self.keymap.help();
+        let key_width = help.iter().map(|(keys, _)| keys.chars().count()).max();
+
+        let mut lines: Vec<Line> = help
+            .into_iter()
+            .map(|(keys, description)| {
+                Line::from(vec![
+                    format!("{:>width$}  ", keys, width = key_width.unwrap_or_default())
+                        .blue()
+                        .bold(),
+                    description.into(),
+                ])
+            })
+            .collect();
+        lines.push(Line::default());
+        lines.push(Line::from(" Modes ".bold()));
+        lines.push(Line::from(
+            "Box jump: after the box key, the next digit picks a box instead of inserting",
+        ));
+
+        let instructions = Title::from(Line::from(vec![" Close ".into(), "<Esc> ".blue().bold()]));
+        let block = Block::bordered()
+            .title(Title::from(" Help ".bold()).alignment(Alignment::Center))
+            .title(instructions.position(Position::Bottom))
+            .border_set(border::THICK)
+            .padding(Padding::uniform(1));
+
+        Clear.render(area, buf);
+        Paragraph::new(lines).block(block).render(area, buf);
+    }
+
+    fn render_stats(&self, area: Rect, buf: &mut Buffer) {
+        let header = Row::new([
+            "Difficulty",
+            "Played",
+            "Solved",
+            "Rate",
+            "Average",
+            "Best",
+            "Hardcore",
+        ])
+        .bold()
+        .bottom_margin(1);
+        let rows = Difficulty::all().map(|difficulty| {
+            let stats = self.stats.difficulty(difficulty);
+            let time = |time: Option<Duration>| time.map(format_duration).unwrap_or("-".into());
+            Row::new([
+                difficulty.to_string(),
+                stats.played.to_string(),
+                stats.solved.to_string(),
+                stats
+                    .completion_rate()
+                    .map(|rate| format!("{:.0}%", rate * 100.0))
+                    .unwrap_or("-".into()),
+                time(stats.average_time()),
+                time(stats.best_time()),
+                time(stats.hardcore_best_time()),
+            ])
+        });
+        // narrower than the name column so the table fits in 80 columns
+        let mut widths = [Constraint::Length(8); 7];
+        widths[0] = Constraint::Length(10);
+        let table = Table::new(rows, widths).header(header).column_spacing(2);
+
+        let days = |days: u32| format!("{} day{}", days, if days == 1 { "" } else { "s" });
+        let streak = Line::from(format!(
+            "Current streak: {}, longest {}",
+            days(self.stats.current_streak(stats::today())),
+            days(self.stats.longest_streak())
+        ));
+
+        let instructions = Title::from(Line::from(vec![" Close ".into(), "<Esc> ".blue().bold()]));
+        let block = Block::bordered()
+            .title(Title::from(" Statistics ".bold()).alignment(Alignment::Center))
+            .title(instructions.position(Position::Bottom))
+            .border_set(border::THICK)
+            .padding(Padding::uniform(1));
+        let inner_area = block.inner(area);
+
+        Clear.render(area, buf);
+        block.render(area, buf);
+
+        let [table_area, _, streak_area] = Layout::vertical([
+            Constraint::Length(6),
+            Constraint::Length(1),
+            Constraint::Length(1),
+        ])
+        .areas(inner_area);
+        Widget::render(table, table_area, buf);
+        streak.render(streak_area, buf);
+    }
+
+    fn render_saves(&self, picker: &SavePicker, area: Rect, buf: &mut Buffer) {
+        let header = Row::new(["Name", "Difficulty", "Progress", "Elapsed"])
+            .bold()
+            .bottom_margin(1);
+        let rows = picker.slots.iter().enumerate().map(|(i, (name, game))| {
+            let level = match (game.custom_clues, game.daily) {
+                (Some(clues), _) => format!("{} clues", clues),
+                (None, Some(_)) => format!("Daily {}", game.level),
+                (None, None) => game.level.to_string(),
+            };
+            let row = Row::new([
+                name.clone(),
+                level,
+                format!("{:.0}%", game.progress() * 100.0),
+                format_duration(game.elapsed),
+            ]);
+            if i == picker.selected {
+                row.blue().bold()
+            } else {
+                row
+            }
+        });
+        let table = Table::new(
+            rows,
+            [
+                Constraint::Min(12),
+                Constraint::Length(12),
+                Constraint::Length(8),
+                Constraint::Length(8),
+            ],
+        )
+        .header(header)
+        .column_spacing(2);
+
+        let instructions = Title::from(Line::from(vec![
+            " Load ".into(),
+            "<Enter>".blue().bold(),
+            " Close ".into(),
+            "<Esc> ".blue().bold(),
+        ]));
+        let block = Block::bordered()
+            .title(Title::from(" Saved Games ".bold()).alignment(Alignment::Center))
+            .title(instructions.position(Position::Bottom))
+            .border_set(border::THICK)
+            .padding(Padding::uniform(1));
+        let inner_area = block.inner(area);
+
+        Clear.render(area, buf);
+        block.render(area, buf);
+        if picker.slots.is_empty() {
+            Paragraph::new("No saved games yet, :save <name> keeps one")
+                .centered()
+                .render(inner_area, buf);
+        } else {
+            Widget::render(table, inner_area, buf);
+        }
+    }
+
+    fn render_calendar(&self, calendar: &Calendar, area: Rect, buf: &mut Buffer) {
+        let level = calendar.difficulty();
+        let header = Row::new(["Mon", "Tue", "Wed", "Thu", "Fri", "Sat", "Sun"]).bold();
+        // each day is its number over the time of its solve, if there was one
+        let mut cells = vec![Cell::default(); calendar.leading_days()];
+        for day in calendar.days() {
+            let time = self.stats.daily_time(day, level);
+            let text = Text::from(vec![
+                Line::from((day - calendar.days().start + 1).to_string()),
+                Line::from(time.map(format_duration).unwrap_or_default()),
+            ]);
+            let cell = match time {
+                _ if day == calendar.selected() => Cell::from(text).reversed().bold(),
+                Some(_) => Cell::from(text).green(),
+                None if day > calendar.today() => Cell::from(text).dark_gray(),
+                None => Cell::from(text),
+            };
+            cells.push(cell);
+        }
+        let rows: Vec<Row> = cells
+            .chunks(7)
+            .map(|week| Row::new(week.to_vec()).height(2))
+            .collect();
+        let weeks = rows.len() as u16;
+        let table = Table::new(rows, [Constraint::Length(8); 7])
+            .header(header)
+            .column_spacing(1);
+
+        let day = calendar.selected();
+        let status = match self.stats.daily_time(day, level) {
+            Some(time) => format!("{} solved in {}", format_day(day), format_duration(time)),
+            None => format!("{} not solved yet, Enter plays it", format_day(day)),
+        };
+        let solved = calendar
+            .days()
+            .filter(|&day| self.stats.solved_daily(day, level))
+            .count();
+
+        let title = format!(" Daily Puzzles: {} ({}) ", calendar.title(), level);
+        let instructions = Title::from(Line::from(vec![
+            " Play ".into(),
+            "<Enter>".blue().bold(),
+            " Month ".into(),
+            "<[ ]>".blue().bold(),
+            " Difficulty ".into(),
+            "<Tab>".blue().bold(),
+            " Close ".into(),
+            "<Esc> ".blue().bold(),
+        ]));
+        let block = Block::bordered()
+            .title(Title::from(title.bold()).alignment(Alignment::Center))
+            .title(
+                Title::from(format!(" {}/{} ", solved, calendar.days().count()))
+                    .alignment(Alignment::Right),
+            )
+            .title(instructions.position(Position::Bottom))
+            .border_set(border::THICK)
+            .padding(Padding::uniform(1));
+        let inner_area = block.inner(area);
+
+        Clear.render(area, buf);
+        block.render(area, buf);
+        let [table_area, _, status_area] = Layout::vertical([
+            Constraint::Length(1 + weeks * 2),
+            Constraint::Length(1),
+            Constraint::Length(1),
+        ])
+        .areas(inner_area);
+        Widget::render(table, table_area, buf);
+        Line::from(status).render(status_area, buf);
+    }
+
+    fn render_achievements(&self, area: Rect, buf: &mut Buffer) {
+        let unlocked = self.stats.achievements();
+        let rows = Achievement::ALL.map(|achievement| match self.stats.unlocked(achievement) {
+            Some(day) => Row::new([
+                achievement.title().into(),
+                achievement.description().into(),
+                day.map(format_day).unwrap_or_default(),
+            ])
+            .green(),
+            None => Row::new([
+                achievement.title().into(),
+                achievement.description().into(),
+                String::new(),
+            ])
+            .dark_gray(),
+        });
+        let table = Table::new(
+            rows,
+            [
+                Constraint::Length(16),
+                Constraint::Min(0),
+                Constraint::Length(10),
+            ],
+        )
+        .column_spacing(2);
+
+        let instructions = Title::from(Line::from(vec![" Close ".into(), "<Esc> ".blue().bold()]));
+        let block = Block::bordered()
+            .title(Title::from(" Achievements ".bold()).alignment(Alignment::Center))
+            .title(
+                Title::from(format!(" {}/{} ", unlocked.len(), Achievement::ALL.len()))
+                    .alignment(Alignment::Right),
+            )
+            .title(instructions.position(Position::Bottom))
+            .border_set(border::THICK)
+            .padding(Padding::uniform(1));
+        let inner_area = block.inner(area);
+
+        Clear.render(area, buf);
+        block.render(area, buf);
+        Widget::render(table, inner_area, buf);
+    }
+
+    fn render_editor(&self, grid: &Grid, area: Rect, buf: &mut Buffer) {
+        let board = SudokuGridWidget::new(grid)
+            .theme(self.theme)
+            .selected((self.selected_row, self.selected_col))
+            .labels(self.labels);
+        if self.is_compact(area) {
+            self.board_layout.set(Some((area, true)));
+            return board.compact(true).render(area, buf);
+        }
+
+        let label = |key| format!(" {} ", self.lang.tr(key)).into();
+        let keys = Line::from(vec![
+            label("play"),
+            "<Enter>".blue().bold(),
+            label("leave"),
+            "<Esc> ".blue().bold(),
+        ]);
+        let title = format!(" {} ", self.lang.tr("editor"));
+        let block = Block::bordered()
+            .title(Title::from(title.bold()).alignment(Alignment::Center))
+            .title(
+                Title::from(keys)
+                    .alignment(Alignment::Left)
+                    .position(Position::Bottom),
+            )
+            .border_set(border::THICK);
+        let inner_area = block.inner(area);
+        self.board_layout.set(Some((inner_area, false)));
+        board.render(inner_area, buf);
+        block.render(area, buf);
+    }
+
+    fn render_tutorial(&self, lesson: &Lesson, index: usize, area: Rect, buf: &mut Buffer) {
+        let [board_area, lesson_area] =
+            Layout::vertical([Constraint::Min(0), Constraint::Length(LESSON_HEIGHT)]).areas(area);
+        self.render_playing(board_area, buf);
+
+        let title = format!(
+            " Tutorial {}/{}: {} ",
+            index + 1,
+            LESSONS.len(),
+            lesson.title
+        );
+        Paragraph::new(lesson.explanation)
+            .wrap(Wrap { trim: true })
+            .block(
+                Block::bordered().title(Title::from(title.bold())).title(
+                    Title::from(Line::from(vec![" Leave ".into(), "<Esc> ".blue().bold()]))
+                        .position(Position::Bottom),
+                ),
+            )
+            .render(lesson_area, buf);
+    }
+
+    // shown by the solver and the tutorial
+    fn highlight(&self) -> Option<Highlight<'_>> {
+        match &self.screen {
+            Screen::Solver(SolverView {
+                step: Some(step), ..
+            }) => Some((step.row, step.col, step.cells.as_slice())),
+            Screen::Tutorial(index) => {
+                let (row, col, _) = LESSONS[*index].target;
+                Some((row, col, LESSONS[*index].highlight))
+            }
+            _ => None,
+        }
+    }
+
+    // the room the board needs, with MIN_CELL_SIZE cells and its border or
+    // in the compact layout
+    fn board_size(&self, compact: bool) -> (u16, u16) {
+        if compact {
+            return COMPACT_SIZE;
+        }
+        let labels = if self.labels.is_some() { 1 } else { 0 };
+        (
+            9 * MIN_CELL_SIZE * CELL_ASPECT + 1 + 2 + labels,
+            9 * MIN_CELL_SIZE + 1 + 2 + labels,
+        )
+    }
+
+    // lines taken under the board by the status line and any lesson
+    fn below_board(&self) -> u16 {
+        match self.screen {
+            Screen::Tutorial(_) => 1 + LESSON_HEIGHT,
+            _ => 1,
+        }
+    }
+
+    // fall back to the compact layout when the full one doesn't fit
+    fn is_compact(&self, board_area: Rect) -> bool {
+        let (width, height) = self.board_size(false);
+        self.compact || board_area.width < width || board_area.height < height
+    }
+
+    fn render_too_small(&self, area: Rect, buf: &mut Buffer) {
+        let (width, height) = self.board_size(true);
+        let height = height + self.below_board();
+        let have = format!("have {}x{}", area.width, area.height);
+        let [area] = Layout::vertical([Constraint::Length(3)])
+            .flex(Flex::Center)
+            .areas(area);
+        Paragraph::new(vec![
+            Line::from("terminal too small".bold()),
+            Line::from(format!("need {}x{}", width, height)),
+            Line::from(have.dark_gray()),
+        ])
+        .centered()
+        .wrap(Wrap { trim: true })
+        .render(area, buf);
+    }
+
+    // the board as it should be drawn, a replay shows its own
+    fn shown_grid(&self) -> Grid {
+        match &self.screen {
+            Screen::Replay(replay) => replay.grid(),
+            _ => self.puzzle.grid(),
+        }
+    }
+
+    // the marks only the game knows about, layered over the board's own
+    fn mark_cell(&self, row: usize, col: usize, style: Style) -> Style {
+        let style = match self.hovered {
+            Some(hovered) if hovered == (row, col) => style.bg(Color::Indexed(236)),
+            _ => style,
+        };
+        let style = match self.last_move {
+            Some(last_move) if last_move == (row, col) && self.last_move_ticks > 0 => {
+                style.bg(Color::Indexed(235 + self.last_move_ticks))
+            }
+            _ => style,
+        };
+        let style = if self.selection().contains(&(row, col)) {
+            style.reversed()
+        } else {
+            style
+        };
+        // wrong entries found by a check stand out for a moment
+        let style = if self.checked.contains(&(row, col)) {
+            style.on_red()
+        } else {
+            style
+        };
+        let style = match &self.screen {
+            Screen::Celebrating(celebration) => match celebration.wave(row, col) {
+                0 => style,
+                brightness => style.bg(Color::Indexed(16 + 6 * brightness)),
+            },
+            _ => style,
+        };
+        match self.flash {
+            Some(flash) if flash == (row, col) && self.flash_ticks > 0 => {
+                style.bg(Color::Indexed(130))
+            }
+            _ => style,
+        }
+    }
+
+    // time attack counts down instead of up, a hidden clock is empty except
+    // for a time attack's last minute
+    fn clock(&self) -> Span<'static> {
+        if self.time_attack {
+            let left = self.time_limit().saturating_sub(self.timer.elapsed());
+            let clock = format_duration(left);
+            if left <= TIME_ATTACK_WARNING {
+                clock.red().bold()
+            } else if self.clock_hidden {
+                Span::default()
+            } else {
+                clock.into()
+            }
+        } else if self.clock_hidden {
+            Span::default()
+        } else {
+            format_duration(self.timer.elapsed()).into()
+        }
+    }
+
+    // the splits next to the board, against the best solve's where there is one
+    fn render_splits(&self, splits: &Splits, area: Rect, buf: &mut Buffer) {
+        let best = match self.custom_clues {
+            Some(_) => Vec::new(),
+            None => self.stats.best_splits(self.level, splits.kind()),
+        };
+        let label = splits.kind().label();
+        let lines: Vec<Line> = (0..9)
+            .map(|i| match (splits.done().get(i), best.get(i)) {
+                (Some(&(item, time)), best) => {
+                    let delta = match best {
+                        Some(&best) if time <= best => {
+                            format!(" -{}", format_delta(best - time)).green()
+                        }
+                        Some(&best) => format!(" +{}", format_delta(time - best)).red(),
+                        None => Span::default(),
+                    };
+                    Line::from(vec![
+                        format!("{} {} {}", label, item, format_duration(time)).into(),
+                        delta,
+                    ])
+                }
+                (None, Some(&best)) => Line::from(format!(
+                    "{:w$} {}",
+                    "-",
+                    format_duration(best),
+                    w = label.len() + 2
+                ))
+                .dark_gray(),
+                (None, None) => Line::from("-".dark_gray()),
+            })
+            .collect();
+
+        Paragraph::new(lines)
+            .block(Block::bordered().title(Title::from(" Splits ".bold())))
+            .render(area, buf);
+    }
+
+    // how far through the board you are next to how far the best solve was at
+    // this point, as shares of the empty cells since the puzzles differ
+    fn ghost_race(&self) -> Line<'static> {
+        let ghost = self.stats.ghost(self.level);
+        if !self.ghost || self.custom_clues.is_some() || ghost.is_empty() {
+            return Line::default();
+        }
+
+        let elapsed = self.timer.elapsed();
+        let ghost_filled = ghost.iter().filter(|&&at| at <= elapsed).count();
+        let empty = 81 - self.puzzle.clues();
+        let filled = self
+            .puzzle
+            .grid()
+            .iter()
+            .flatten()
+            .filter(|cell| cell.value() != 0)
+            .count();
+        let percent = |done: usize, total: usize| done * 100 / total.max(1);
+
+        Line::from(vec![
+            format!(" you {}%", percent(filled - self.puzzle.clues(), empty)).into(),
+            format!(" ghost {}% ", percent(ghost_filled, ghost.len())).dark_gray(),
+        ])
+    }
+
+    fn render_playing(&self, area: Rect, buf: &mut Buffer) {
+        // the splits take the right side while the board still fits next to them
+        let area = match &self.splits {
+            Some(splits) if area.width >= self.board_size(false).0 + SPLITS_WIDTH => {
+                let [board_area, splits_area] =
+                    Layout::horizontal([Constraint::Min(0), Constraint::Length(SPLITS_WIDTH)])
+                        .areas(area);
+                self.render_splits(splits, splits_area, buf);
+                board_area
+            }
+            _ => area,
+        };
+
+        let grid = self.shown_grid();
+        let mark = |row, col, style| self.mark_cell(row, col, style);
+        let mut board = SudokuGridWidget::new(&grid)
+            .theme(self.theme)
+            .selected((self.selected_row, self.selected_col))
+            .labels(self.labels)
+            .cell_style(&mark);
+        if let Some((row, col, cells)) = self.highlight() {
+            board = board.highlight((row, col), cells);
+        }
+
+        if self.is_compact(area) {
+            self.board_layout.set(Some((area, true)));
+            return board.compact(true).render(area, buf);
+        }
+
+        // the outer block
+        let label = |key| format!(" {} ", self.lang.tr(key)).into();
+        let keys = [
+            ("quit", Action::Quit),
+            ("delete", Action::Clear),
+            ("reset", Action::Reset),
+            ("new_game", Action::NewGame),
+            ("hint", Action::Hint),
+            ("help", Action::Help),
+        ];
+        let instructions = Title::from(Line::from(
+            keys.into_iter()
+                .flat_map(|(name, action)| {
+                    [
+                        label(name),
+                        format!("<{}>", self.key_label(action)).blue().bold(),
+                    ]
+                })
+                .collect::<Vec<_>>(),
+        ));
+
+        let title = Title::from(" Sudoku ".bold());
+        let tabs = Title::from(if self.tabs.is_empty() {
+            String::new()
+        } else {
+            format!(" Tab {}/{} ", self.tab + 1, self.tabs.len() + 1)
+        });
+        let timer = Title::from(Line::from(vec![self.clock()]));
+        let block = Block::bordered()
+            .title(title.alignment(Alignment::Center))
+            .title(tabs.alignment(Alignment::Left))
+            .title(Title::from(self.ghost_race()).alignment(Alignment::Right))
+            .title(timer.alignment(Alignment::Right).position(Position::Bottom))
+            .title(
+                instructions
+                    .alignment(Alignment::Left)
+                    .position(Position::Bottom),
+            )
+            .border_set(border::THICK);
+
+        // inner space of outer block
+        let inner_area = block.inner(area);
+
+        // the image leaves out the transient marks, so they get the cells
+        #[cfg(feature = "graphics")]
+        if self.screen == Screen::Playing
+            && self.toasts.is_empty()
+            && self.checked.is_empty()
+            && self.selection_anchor.is_none()
+        {
+            self.board_area.set(Some(board.grid_area(inner_area)));
+        }
+
+        self.board_layout.set(Some((inner_area, false)));
+        board.render(inner_area, buf);
+        block.render(area, buf);
+    }
+}
+
+impl Widget for &App {
+    fn render(self, area: Rect, buf: &mut Buffer) {
+        self.board_layout.set(None);
+        #[cfg(feature = "graphics")]
+        self.board_area.set(None);
+
+        if matches!(
+            self.screen,
+            Screen::MainMenu(_)
+                | Screen::ConfirmRecovery(_)
+                | Screen::Help(_)
+                | Screen::Stats(_)
+                | Screen::Achievements(_)
+                | Screen::Saves(..)
+                | Screen::Calendar(..)
+        ) {
+            return self.render_screen(&self.screen, area, buf);
+        }
+
+        // a board squeezed below its minimum garbles, explain instead
+        let (width, height) = self.board_size(true);
+        if area.width < width || area.height < height + self.below_board() {
+            return self.render_too_small(area, buf);
+        }
+
+        // one line under the game for feedback and the command prompt
+        let [area, status_area] =
+            Layout::vertical([Constraint::Min(0), Constraint::Length(1)]).areas(area);
+        let board_area = Rect {
+            height: area.height.saturating_sub(self.below_board() - 1),
+            ..area
+        };
+        self.render_status(status_area, self.is_compact(board_area), buf);
+        self.render_screen(&self.screen, area, buf);
+
+        if !self.toasts.is_empty() {
+            let toast_area = Rect {
+                x: area.x + 1,
+                y: area.y + 1,
+                width: area.width.saturating_sub(2),
+                height: area.height.saturating_sub(2),
+            };
+            self.toasts.render(toast_area, buf);
+        }
+    }
+}
+
+fn is_move(action: Option<Action>) -> bool {
+    matches!(
+        action,
+        Some(Action::MoveUp | Action::MoveDown | Action::MoveLeft | Action::MoveRight)
+    )
+}
+
+// short human readable summary of a move, e.g. "5 at R3C7"
+fn describe_move(changes: &Move) -> String {
+    match changes.as_slice() {
+        [change] => {
+            let what = match change.after.value() {
+                _ if change.before.value() == change.after.value() => {
+                    if change.before.notes() == change.after.notes() {
+                        "color".to_string()
+                    } else {
+                        "notes".to_string()
+                    }
+                }
+                0 => "clear".to_string(),
+                value => value.to_string(),
+            };
+            format!("{} at R{}C{}", what, change.row + 1, change.col + 1)
+        }
+        changes => format!("{} cells", changes.len()),
+    }
+}
+
+// how far ahead or behind a split is, e.g. 1:05
+fn format_delta(delta: Duration) -> String {
+    let secs = delta.as_secs();
+    format!("{}:{:02}", secs / 60, secs % 60)
 }
 
 fn format_duration(duration: Duration) -> String {
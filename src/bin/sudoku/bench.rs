@@ -0,0 +1,108 @@
+use argh::FromArgs;
+use rand::{rngs::StdRng, SeedableRng};
+use serde_json::json;
+use std::{
+    hint,
+    io::{self, Write},
+    process::ExitCode,
+    time::{Duration, Instant},
+};
+
+use rsudoku::{Difficulty, Puzzle};
+
+#[derive(FromArgs, Debug)]
+/// Time generating, solving and rating puzzles at each difficulty and print
+/// how many of each are done per second
+#[argh(subcommand, name = "bench")]
+pub struct Bench {
+    /// puzzles per difficulty
+    #[argh(option, default = "20")]
+    count: usize,
+
+    /// only this difficulty (options: easy, medium, hard, expert)
+    #[argh(option)]
+    difficulty: Option<Difficulty>,
+
+    /// seed for the generator, 0 unless given so runs time the same puzzles
+    #[argh(option, default = "0")]
+    seed: u64,
+
+    /// print a json object per difficulty instead, one per line
+    #[argh(switch)]
+    json: bool,
+}
+
+// how long `f` took on every puzzle, called with each one's index
+fn time(count: usize, mut f: impl FnMut(usize)) -> Duration {
+    let started = Instant::now();
+    for i in 0..count {
+        f(i);
+    }
+    started.elapsed()
+}
+
+fn per_second(count: usize, time: Duration) -> f64 {
+    count as f64 / time.as_secs_f64().max(f64::EPSILON)
+}
+
+impl Bench {
+    pub fn run(self) -> io::Result<ExitCode> {
+        let mut rng = StdRng::seed_from_u64(self.seed);
+        let mut stdout = io::stdout().lock();
+        if !self.json {
+            writeln!(
+                stdout,
+                "{:<8} {:>10} {:>10} {:>10}",
+                "", "generate/s", "solve/s", "rate/s"
+            )?;
+        }
+
+        let difficulties = match self.difficulty {
+            Some(difficulty) => vec![difficulty],
+            None => Difficulty::all().to_vec(),
+        };
+        for difficulty in difficulties {
+            let mut puzzles = Vec::with_capacity(self.count);
+            let generate = time(self.count, |_| {
+                puzzles.push(Puzzle::with_rng(difficulty as usize, &mut rng));
+            });
+            let boards: Vec<String> = puzzles.iter().map(|p| p.to_board(false)).collect();
+            // what `sudoku solve` does: read the board, then make sure its
+            // solution is the only one
+            let solve = time(self.count, |i| {
+                if let Ok(puzzle) = boards[i].parse::<Puzzle>() {
+                    hint::black_box(puzzle.solution_count());
+                }
+            });
+            let rate = time(self.count, |i| {
+                hint::black_box(rsudoku::rate(&puzzles[i].grid()));
+            });
+
+            let (generate, solve, rate) = (
+                per_second(self.count, generate),
+                per_second(self.count, solve),
+                per_second(self.count, rate),
+            );
+            if self.json {
+                let report = json!({
+                    "difficulty": difficulty,
+                    "count": self.count,
+                    "generate_per_sec": generate,
+                    "solve_per_sec": solve,
+                    "rate_per_sec": rate,
+                });
+                writeln!(stdout, "{}", report)?;
+            } else {
+                writeln!(
+                    stdout,
+                    "{:<8} {:>10.1} {:>10.1} {:>10.1}",
+                    difficulty.to_string(),
+                    generate,
+                    solve,
+                    rate
+                )?;
+            }
+        }
+        Ok(ExitCode::SUCCESS)
+    }
+}
@@ -0,0 +1,61 @@
+use argh::FromArgs;
+use std::{
+    collections::HashSet,
+    io::{self, Write},
+    process::ExitCode,
+};
+
+use rsudoku::Puzzle;
+
+use crate::input;
+
+#[derive(FromArgs, Debug)]
+/// Print the id and canonical form of each puzzle, tab separated; puzzles that
+/// are the same up to symmetry and renamed digits share both
+#[argh(subcommand, name = "canonicalize")]
+pub struct Canonicalize {
+    /// the puzzle as 81 cells (0 or . for empty), or a file of them; read from
+    /// stdin when omitted
+    #[argh(positional)]
+    puzzle: Option<String>,
+
+    /// print the puzzles themselves instead, dropping each one that is a
+    /// disguise of an earlier one
+    #[argh(switch)]
+    dedupe: bool,
+}
+
+impl Canonicalize {
+    pub fn run(self) -> io::Result<ExitCode> {
+        let mut code = ExitCode::SUCCESS;
+        let mut stdout = io::stdout().lock();
+        let mut seen = HashSet::new();
+        let mut duplicates = 0;
+
+        for (i, board) in input::read_puzzles(self.puzzle.as_deref())?
+            .iter()
+            .enumerate()
+        {
+            let puzzle = match board.parse::<Puzzle>() {
+                Ok(puzzle) => puzzle,
+                Err(err) => {
+                    eprintln!("puzzle {}: {}", i + 1, err);
+                    code = ExitCode::from(1);
+                    continue;
+                }
+            };
+            let form = rsudoku::canonical_form(&puzzle.grid());
+            if !self.dedupe {
+                writeln!(stdout, "{}\t{}", rsudoku::puzzle_id(&form), form)?;
+            } else if seen.insert(form) {
+                writeln!(stdout, "{}", puzzle.to_board(false))?;
+            } else {
+                duplicates += 1;
+            }
+        }
+        if self.dedupe {
+            eprintln!("dropped {} duplicates", duplicates);
+        }
+        Ok(code)
+    }
+}
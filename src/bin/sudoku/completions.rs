@@ -0,0 +1,420 @@
+use argh::FromArgs;
+use std::{
+    fmt::Write as _,
+    io::{self, Write},
+    process::ExitCode,
+    str::FromStr,
+};
+
+use rsudoku::Theme;
+
+#[derive(FromArgs, Debug)]
+/// Print a completion script for bash, zsh or fish, e.g.
+/// `sudoku completions fish > ~/.config/fish/completions/sudoku.fish`
+#[argh(subcommand, name = "completions")]
+pub struct Completions {
+    /// the shell to complete in (options: bash, zsh, fish)
+    #[argh(positional)]
+    shell: Shell,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Shell {
+    Bash,
+    Zsh,
+    Fish,
+}
+
+impl FromStr for Shell {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.to_lowercase().as_str() {
+            "bash" => Ok(Shell::Bash),
+            "zsh" => Ok(Shell::Zsh),
+            "fish" => Ok(Shell::Fish),
+            _ => Err(format!("Unsupported shell: {}", s)),
+        }
+    }
+}
+
+// what follows a flag on the command line
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Value {
+    None, // a switch
+    Any,
+    File,
+    OneOf(&'static [&'static str]),
+}
+
+struct Flag {
+    name: &'static str,
+    help: &'static str,
+    value: Value,
+}
+
+// what a command takes after its flags
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Positional {
+    None,
+    Puzzles, // a puzzle or a file of them
+    OneOf(&'static [&'static str]),
+}
+
+struct Command {
+    name: &'static str,
+    help: &'static str,
+    flags: &'static [Flag],
+    positional: Positional,
+}
+
+const fn flag(name: &'static str, help: &'static str, value: Value) -> Flag {
+    Flag { name, help, value }
+}
+
+const DIFFICULTIES: &[&str] = &["easy", "medium", "hard", "expert"];
+const FORMATS: &[&str] = &["line", "text", "svg", "tex", "html", "png"];
+const JSON: Flag = flag("json", "print a json object per puzzle", Value::None);
+
+// kept in step with the argh definitions of the game and its subcommands
+const GAME_FLAGS: &[Flag] = &[
+    flag("clues", "clues to keep", Value::Any),
+    flag("puzzle", "play this puzzle", Value::Any),
+    flag("file", "play the puzzle in this file", Value::File),
+    flag(
+        "resume",
+        "carry on with the saved game, or a named one",
+        Value::None,
+    ),
+    flag("config", "read settings from this file", Value::File),
+    flag("theme", "colors of the board", Value::OneOf(&Theme::NAMES)),
+    flag(
+        "keymap",
+        "key layout",
+        Value::OneOf(&["default", "vim", "numpad"]),
+    ),
+    flag(
+        "labels",
+        "label rows and columns",
+        Value::OneOf(&["numbers", "letters"]),
+    ),
+    flag("compact", "always draw the small board", Value::None),
+    flag("hints", "hints allowed per game", Value::Any),
+    flag("time-attack", "finish within a time limit", Value::None),
+    flag("no-bell", "keep the bell quiet", Value::None),
+    flag("hide-timer", "keep the clock off screen", Value::None),
+    flag("marathon", "play puzzles back to back", Value::None),
+    flag(
+        "splits",
+        "time each box or digit",
+        Value::OneOf(&["boxes", "digits"]),
+    ),
+    flag("ghost", "race your best solve", Value::None),
+    flag("lang", "language of the game", Value::OneOf(&["en", "de"])),
+    flag("seed", "seed for the puzzle generator", Value::Any),
+    flag("source", "random, web or a file of puzzles", Value::File),
+    flag("limited-mistakes", "lose after 3 mistakes", Value::None),
+    flag("strict", "refuse wrong numbers", Value::None),
+    flag("auto-notes", "keep every candidate noted", Value::None),
+    flag("autosave", "seconds between saves", Value::Any),
+];
+
+const COMMANDS: &[Command] = &[
+    Command {
+        name: "generate",
+        help: "Print new puzzles",
+        flags: &[
+            flag(
+                "difficulty",
+                "difficulty of the puzzles",
+                Value::OneOf(DIFFICULTIES),
+            ),
+            flag("count", "how many puzzles to print", Value::Any),
+            flag("seed", "seed for the generator", Value::Any),
+            flag("format", "how to print each puzzle", Value::OneOf(FORMATS)),
+            JSON,
+        ],
+        positional: Positional::None,
+    },
+    Command {
+        name: "solve",
+        help: "Print the solution of each puzzle",
+        flags: &[
+            flag(
+                "format",
+                "how to print each solution",
+                Value::OneOf(FORMATS),
+            ),
+            JSON,
+            flag("explain", "print the placements step by step", Value::None),
+        ],
+        positional: Positional::Puzzles,
+    },
+    Command {
+        name: "rate",
+        help: "Print the difficulty of each puzzle",
+        flags: &[JSON],
+        positional: Positional::Puzzles,
+    },
+    Command {
+        name: "verify",
+        help: "Check each puzzle has one solution",
+        flags: &[JSON],
+        positional: Positional::Puzzles,
+    },
+    Command {
+        name: "print",
+        help: "Draw each puzzle as a boxed grid",
+        flags: &[
+            flag("ascii", "draw with plain ascii", Value::None),
+            flag("blank", "what empty cells show", Value::Any),
+            flag("solution", "add the solved grid", Value::None),
+        ],
+        positional: Positional::Puzzles,
+    },
+    Command {
+        name: "export",
+        help: "Write a puzzle in another format",
+        flags: &[
+            flag("format", "the format to write", Value::OneOf(FORMATS)),
+            flag(
+                "candidates",
+                "mark the candidates of empty cells",
+                Value::None,
+            ),
+            flag("solution", "add the solution to an html page", Value::None),
+            flag("size", "width and height of a png", Value::Any),
+            flag("theme", "colors of a png", Value::OneOf(&["light", "dark"])),
+            flag("output", "the file to write", Value::File),
+        ],
+        positional: Positional::Puzzles,
+    },
+    Command {
+        name: "canonicalize",
+        help: "Print the id and canonical form of each puzzle",
+        flags: &[flag("dedupe", "drop disguised duplicates", Value::None)],
+        positional: Positional::Puzzles,
+    },
+    Command {
+        name: "bench",
+        help: "Time generating, solving and rating",
+        flags: &[
+            flag("count", "puzzles per difficulty", Value::Any),
+            flag(
+                "difficulty",
+                "only this difficulty",
+                Value::OneOf(DIFFICULTIES),
+            ),
+            flag("seed", "seed for the generator", Value::Any),
+            flag("json", "print a json object per difficulty", Value::None),
+        ],
+        positional: Positional::None,
+    },
+    Command {
+        name: "stats",
+        help: "Print the play history",
+        flags: &[flag("json", "print a json object", Value::None)],
+        positional: Positional::None,
+    },
+    Command {
+        name: "completions",
+        help: "Print a completion script",
+        flags: &[],
+        positional: Positional::OneOf(&["bash", "zsh", "fish"]),
+    },
+    Command {
+        name: "daily",
+        help: "Play the puzzle of the day",
+        flags: &[flag("print", "print the puzzle instead", Value::None)],
+        positional: Positional::OneOf(DIFFICULTIES),
+    },
+];
+
+impl Completions {
+    pub fn run(self) -> io::Result<ExitCode> {
+        let script = match self.shell {
+            Shell::Bash => bash(),
+            Shell::Zsh => zsh(),
+            Shell::Fish => fish(),
+        };
+        io::stdout().write_all(script.as_bytes())?;
+        Ok(ExitCode::SUCCESS)
+    }
+}
+
+fn flag_names(flags: &[Flag]) -> String {
+    let names: Vec<String> = flags.iter().map(|f| format!("--{}", f.name)).collect();
+    names.join(" ") + " --help"
+}
+
+fn bash() -> String {
+    let names: Vec<&str> = COMMANDS.iter().map(|command| command.name).collect();
+    let mut script = format!(
+        "_sudoku() {{\n    \
+             local cur=\"${{COMP_WORDS[COMP_CWORD]}}\" prev=\"${{COMP_WORDS[COMP_CWORD-1]}}\"\n    \
+             local command=\"\" word\n    \
+             for word in \"${{COMP_WORDS[@]:1:COMP_CWORD-1}}\"; do\n        \
+                 case \"$word\" in\n            \
+                     {}) command=\"$word\"; break ;;\n        \
+                 esac\n    \
+             done\n\n    \
+             case \"$command:$prev\" in\n",
+        names.join("|")
+    );
+
+    let all = std::iter::once(("", GAME_FLAGS)).chain(COMMANDS.iter().map(|c| (c.name, c.flags)));
+    for (command, flags) in all {
+        for flag in flags {
+            let reply = match flag.value {
+                Value::None => continue,
+                Value::Any => "return".to_string(),
+                Value::File => "COMPREPLY=($(compgen -f -- \"$cur\")); return".to_string(),
+                Value::OneOf(values) => format!(
+                    "COMPREPLY=($(compgen -W \"{}\" -- \"$cur\")); return",
+                    values.join(" ")
+                ),
+            };
+            let _ = writeln!(script, "        {}:--{}) {} ;;", command, flag.name, reply);
+        }
+    }
+    script.push_str("    esac\n\n    case \"$command\" in\n");
+
+    let _ = writeln!(
+        script,
+        "        \"\") COMPREPLY=($(compgen -W \"{} {} {}\" -- \"$cur\")) ;;",
+        flag_names(GAME_FLAGS),
+        DIFFICULTIES.join(" "),
+        names.join(" ")
+    );
+    for command in COMMANDS {
+        let mut words = flag_names(command.flags);
+        let mut files = "";
+        match command.positional {
+            Positional::None => {}
+            Positional::Puzzles => files = " $(compgen -f -- \"$cur\")",
+            Positional::OneOf(values) => words = values.join(" ") + " " + &words,
+        }
+        let _ = writeln!(
+            script,
+            "        {}) COMPREPLY=($(compgen -W \"{}\" -- \"$cur\"){}) ;;",
+            command.name, words, files
+        );
+    }
+    script.push_str("    esac\n}\n\ncomplete -F _sudoku sudoku\n");
+    script
+}
+
+fn zsh_flags(flags: &[Flag]) -> String {
+    flags
+        .iter()
+        .map(|flag| {
+            let value = match flag.value {
+                Value::None => String::new(),
+                Value::Any => format!(":{}:", flag.name),
+                Value::File => format!(":{}:_files", flag.name),
+                Value::OneOf(values) => format!(":{}:({})", flag.name, values.join(" ")),
+            };
+            format!(" \\\n        '--{}[{}]{}'", flag.name, flag.help, value)
+        })
+        .collect()
+}
+
+fn zsh() -> String {
+    let commands: Vec<String> = COMMANDS
+        .iter()
+        .map(|command| format!("'{}:{}'", command.name, command.help))
+        .collect();
+    let mut script = format!(
+        "#compdef sudoku\n\n\
+         _sudoku() {{\n    \
+             local context state state_descr line\n    \
+             typeset -A opt_args\n    \
+             local -a commands=({})\n\n    \
+             _arguments -C{} \\\n        \
+             '1: :->first' \\\n        \
+             '*:: :->args'\n\n    \
+             case $state in\n        \
+                 first)\n            \
+                     _alternative 'difficulties:difficulty:({})' \
+                     'commands:command:_describe command commands'\n            \
+                     ;;\n        \
+                 args)\n            \
+                     case $line[1] in\n",
+        commands.join(" "),
+        zsh_flags(GAME_FLAGS),
+        DIFFICULTIES.join(" ")
+    );
+    for command in COMMANDS {
+        let positional = match command.positional {
+            Positional::None => String::new(),
+            Positional::Puzzles => " \\\n        '1:puzzle or file:_files'".to_string(),
+            Positional::OneOf(values) => format!(" \\\n        '1: :({})'", values.join(" ")),
+        };
+        let _ = writeln!(
+            script,
+            "                {})\n                    _arguments{}{}\n                    ;;",
+            command.name,
+            zsh_flags(command.flags),
+            positional
+        );
+    }
+    script.push_str("            esac\n            ;;\n    esac\n}\n\n_sudoku \"$@\"\n");
+    script
+}
+
+fn fish_flags(script: &mut String, condition: &str, flags: &[Flag]) {
+    for flag in flags {
+        let value = match flag.value {
+            Value::None => String::new(),
+            Value::Any => " -x".to_string(),
+            Value::File => " -r -F".to_string(),
+            Value::OneOf(values) => format!(" -x -a '{}'", values.join(" ")),
+        };
+        let _ = writeln!(
+            script,
+            "complete -c sudoku -n '{}' -l {}{} -d '{}'",
+            condition, flag.name, value, flag.help
+        );
+    }
+}
+
+fn fish() -> String {
+    let names: Vec<&str> = COMMANDS.iter().map(|command| command.name).collect();
+    let mut script = String::from("complete -c sudoku -f\n");
+    let top = "not __fish_seen_subcommand_from ".to_string() + &names.join(" ");
+
+    let _ = writeln!(
+        script,
+        "complete -c sudoku -n '{}' -a '{}' -d difficulty",
+        top,
+        DIFFICULTIES.join(" ")
+    );
+    for command in COMMANDS {
+        let _ = writeln!(
+            script,
+            "complete -c sudoku -n '{}' -a {} -d '{}'",
+            top, command.name, command.help
+        );
+    }
+    fish_flags(&mut script, &top, GAME_FLAGS);
+
+    for command in COMMANDS {
+        let condition = format!("__fish_seen_subcommand_from {}", command.name);
+        fish_flags(&mut script, &condition, command.flags);
+        match command.positional {
+            Positional::None => {}
+            Positional::Puzzles => {
+                let _ = writeln!(script, "complete -c sudoku -n '{}' -F", condition);
+            }
+            Positional::OneOf(values) => {
+                let _ = writeln!(
+                    script,
+                    "complete -c sudoku -n '{}' -a '{}'",
+                    condition,
+                    values.join(" ")
+                );
+            }
+        }
+    }
+    script
+}
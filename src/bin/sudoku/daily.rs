@@ -0,0 +1,29 @@
+use argh::FromArgs;
+use std::{
+    io::{self, Write},
+    process::ExitCode,
+};
+
+use rsudoku::{Difficulty, ExportFormat};
+
+#[derive(FromArgs, Debug)]
+/// Play the puzzle of the day, the same for everyone at each difficulty and
+/// a new one at midnight UTC
+#[argh(subcommand, name = "daily")]
+pub struct Daily {
+    /// difficulty (options: easy, medium, hard, expert), medium when omitted
+    #[argh(positional, default = "Difficulty::Medium")]
+    pub difficulty: Difficulty,
+
+    /// print the puzzle as a line of 81 cells instead of playing it
+    #[argh(switch)]
+    pub print: bool,
+}
+
+impl Daily {
+    pub fn print(&self) -> io::Result<ExitCode> {
+        let puzzle = rsudoku::daily_puzzle(rsudoku::today(), self.difficulty);
+        io::stdout().write_all(&ExportFormat::Line.write(&puzzle, false))?;
+        Ok(ExitCode::SUCCESS)
+    }
+}
@@ -0,0 +1,94 @@
+use argh::FromArgs;
+use std::{
+    fs,
+    io::{self, Write},
+    path::PathBuf,
+    process::ExitCode,
+};
+
+use rsudoku::{ExportFormat, HtmlGrid, Puzzle, SvgGrid};
+
+use crate::input;
+
+#[derive(FromArgs, Debug)]
+/// Write a puzzle in another format, to stdout or a file
+#[argh(subcommand, name = "export")]
+pub struct Export {
+    /// the puzzle as 81 cells (0 or . for empty), or a file holding it; read
+    /// from stdin when omitted
+    #[argh(positional)]
+    puzzle: Option<String>,
+
+    /// the format to write (options: line, text, svg, tex, html, png
+    /// with the image feature), by default the one the output file's extension names,
+    /// else text
+    #[argh(option)]
+    format: Option<ExportFormat>,
+
+    /// mark every empty cell with its candidates, for svg
+    #[argh(switch)]
+    candidates: bool,
+
+    /// add the solution to an html page, hidden until clicked
+    #[argh(switch)]
+    solution: bool,
+
+    /// width and height of a png in pixels
+    #[cfg(feature = "image")]
+    #[argh(option, default = "450")]
+    size: usize,
+
+    /// colors of a png (options: light, dark)
+    #[cfg(feature = "image")]
+    #[argh(option, default = "rsudoku::ImageTheme::Light")]
+    theme: rsudoku::ImageTheme,
+
+    /// the file to write instead of stdout
+    #[argh(option, short = 'o')]
+    output: Option<PathBuf>,
+}
+
+impl Export {
+    pub fn run(self) -> io::Result<ExitCode> {
+        let boards = input::read_puzzles(self.puzzle.as_deref())?;
+        let [board] = boards.as_slice() else {
+            eprintln!("export takes one puzzle, got {}", boards.len());
+            return Ok(ExitCode::from(1));
+        };
+        let puzzle = match board.parse::<Puzzle>() {
+            Ok(puzzle) => puzzle,
+            Err(err) => {
+                eprintln!("{}", err);
+                return Ok(ExitCode::from(1));
+            }
+        };
+
+        let format = match (self.format, &self.output) {
+            (Some(format), _) => format,
+            (None, Some(path)) => ExportFormat::from_path(path),
+            (None, None) => ExportFormat::default(),
+        };
+        let out = match format {
+            ExportFormat::Svg => SvgGrid::new()
+                .candidates(self.candidates)
+                .render(&puzzle, false)
+                .into_bytes(),
+            ExportFormat::Html => HtmlGrid::new()
+                .solution(self.solution)
+                .render(&puzzle, false)
+                .into_bytes(),
+            #[cfg(feature = "image")]
+            ExportFormat::Png => rsudoku::PngGrid::new()
+                .size(self.size)
+                .theme(self.theme)
+                .render(&puzzle, false),
+            format => format.write(&puzzle, false),
+        };
+
+        match &self.output {
+            Some(path) => fs::write(path, out)?,
+            None => io::stdout().write_all(&out)?,
+        }
+        Ok(ExitCode::SUCCESS)
+    }
+}
@@ -0,0 +1,69 @@
+use argh::FromArgs;
+use rand::{rngs::StdRng, SeedableRng};
+use serde_json::json;
+use std::{
+    io::{self, Write},
+    process::ExitCode,
+    time::Instant,
+};
+
+use rsudoku::{Difficulty, ExportFormat, Puzzle};
+
+#[derive(FromArgs, Debug)]
+/// Print new puzzles without starting the game
+#[argh(subcommand, name = "generate")]
+pub struct Generate {
+    /// difficulty of the puzzles (options: easy, medium, hard, expert)
+    #[argh(option, default = "Difficulty::Medium")]
+    difficulty: Difficulty,
+
+    /// how many puzzles to print
+    #[argh(option, default = "1")]
+    count: usize,
+
+    /// seed for the generator, the same seed prints the same puzzles
+    #[argh(option)]
+    seed: Option<u64>,
+
+    /// how to print each puzzle (options: line, text)
+    #[argh(option, default = "ExportFormat::Line")]
+    format: ExportFormat,
+
+    /// print a json object per puzzle instead, one per line, with its
+    /// solution and how long it took to generate
+    #[argh(switch)]
+    json: bool,
+}
+
+impl Generate {
+    pub fn run(self) -> io::Result<ExitCode> {
+        let mut rng = match self.seed {
+            Some(seed) => StdRng::seed_from_u64(seed),
+            None => StdRng::from_entropy(),
+        };
+
+        let mut stdout = io::stdout().lock();
+        for i in 0..self.count {
+            let started = Instant::now();
+            let puzzle = Puzzle::with_rng(self.difficulty as usize, &mut rng);
+            if self.json {
+                let report = json!({
+                    "puzzle": puzzle.to_board(false),
+                    "solution": puzzle.to_solved().to_board(true),
+                    "difficulty": self.difficulty,
+                    "clues": puzzle.clues(),
+                    "millis": started.elapsed().as_secs_f64() * 1000.0,
+                });
+                writeln!(stdout, "{}", report)?;
+                continue;
+            }
+
+            // grids are kept apart by a blank line, lines need nothing
+            if i > 0 && self.format != ExportFormat::Line {
+                writeln!(stdout)?;
+            }
+            stdout.write_all(&self.format.write(&puzzle, false))?;
+        }
+        Ok(ExitCode::SUCCESS)
+    }
+}
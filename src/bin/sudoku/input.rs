@@ -0,0 +1,36 @@
+use std::{
+    fs,
+    io::{self, Read},
+    path::Path,
+};
+
+// the puzzles a subcommand works on: the argument itself when it is a puzzle,
+// the file it names, or stdin without one. A file or stdin holds one puzzle
+// per line, or a single puzzle spread over several lines when they add up to
+// 81 cells.
+pub fn read_puzzles(source: Option<&str>) -> io::Result<Vec<String>> {
+    let text = match source {
+        Some(path) if Path::new(path).is_file() => fs::read_to_string(path)?,
+        Some(puzzle) => puzzle.to_string(),
+        None => {
+            let mut text = String::new();
+            io::stdin().read_to_string(&mut text)?;
+            text
+        }
+    };
+
+    let lines: Vec<&str> = text
+        .lines()
+        .map(str::trim)
+        .filter(|line| !line.is_empty())
+        .collect();
+    let cells: usize = lines
+        .iter()
+        .map(|line| line.chars().filter(|c| !c.is_whitespace()).count())
+        .sum();
+    if cells == 81 {
+        Ok(vec![lines.join("\n")])
+    } else {
+        Ok(lines.into_iter().map(String::from).collect())
+    }
+}
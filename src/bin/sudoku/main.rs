@@ -0,0 +1,439 @@
+use argh::FromArgs;
+use std::{fs, io, path::PathBuf, process::ExitCode, str::FromStr, time::Duration};
+
+use rsudoku::{
+    App, Config, Difficulty, Keymap, KeymapPreset, Labels, Lang, Puzzle, PuzzlePack, PuzzleSource,
+    SplitKind, Theme, MAX_CLUES, MIN_CLUES,
+};
+
+mod bench;
+mod canonicalize;
+mod completions;
+mod daily;
+mod export;
+mod generate;
+mod input;
+mod print;
+mod rate;
+mod solve;
+mod stats;
+mod verify;
+
+#[derive(FromArgs, Debug)]
+/// Cli to play Sudoku
+struct Sudoku {
+    #[argh(subcommand)]
+    command: Option<Subcommand>,
+
+    /// difficulty (options: easy, medium, hard, expert), shows a menu when omitted
+    #[argh(positional)]
+    difficulty: Option<Difficulty>,
+
+    /// clues to keep instead of the difficulty's, from 24 to 81; skips the
+    /// menu when no difficulty is given
+    #[argh(option, from_str_fn(parse_clues))]
+    clues: Option<usize>,
+
+    /// play this puzzle, 81 cells with 0 or . for empty, instead of a new one
+    #[argh(option)]
+    puzzle: Option<String>,
+
+    /// play the puzzle in this file instead of a new one
+    #[argh(option)]
+    file: Option<PathBuf>,
+
+    /// carry on with the game left unfinished when last quitting, or with
+    /// the game saved under the name given
+    #[argh(option)]
+    resume: Option<String>,
+
+    /// read settings from this file instead of the one in the config directory
+    #[argh(option)]
+    config: Option<PathBuf>,
+
+    /// colors of the board, `--theme help` lists them
+    #[argh(option)]
+    theme: Option<String>,
+
+    /// key layout (options: default, vim, numpad)
+    #[argh(option)]
+    keymap: Option<KeymapPreset>,
+
+    /// label rows and columns around the grid (options: numbers, letters)
+    #[argh(option)]
+    labels: Option<Labels>,
+
+    /// always draw the small board, one character per cell
+    #[argh(switch)]
+    compact: bool,
+
+    /// hints allowed per game, 3 unless configured
+    #[argh(option)]
+    hints: Option<usize>,
+
+    /// finish within a time limit that depends on the difficulty
+    #[argh(switch)]
+    time_attack: bool,
+
+    /// don't ring the bell when a key is rejected
+    #[argh(switch)]
+    no_bell: bool,
+
+    /// keep the clock off screen, games are still timed for the stats
+    #[argh(switch)]
+    hide_timer: bool,
+
+    /// start the next puzzle as soon as one is solved and show the totals on quitting
+    #[argh(switch)]
+    marathon: bool,
+
+    /// time each finished box or digit against your best: boxes or digits
+    #[argh(option)]
+    splits: Option<SplitKind>,
+
+    /// race the pace of your best solve at this difficulty
+    #[argh(switch)]
+    ghost: bool,
+
+    /// language of the game: en or de, taken from LANG when omitted
+    #[argh(option)]
+    lang: Option<Lang>,
+
+    /// seed for the puzzle generator, the same seed deals the same games
+    #[argh(option)]
+    seed: Option<u64>,
+
+    /// where new puzzles come from: random, the default, web for the server
+    /// set as source.url in the config, or a file of puzzles, one per line,
+    /// dealt by how hard the solver rates them
+    #[argh(option)]
+    source: Option<Source>,
+
+    /// lose the game after 3 mistakes
+    #[argh(switch)]
+    limited_mistakes: bool,
+
+    /// refuse numbers that disagree with the solution, counting them as
+    /// mistakes
+    #[argh(switch)]
+    strict: bool,
+
+    /// keep the notes of every empty cell filled in with its candidates
+    #[argh(switch)]
+    auto_notes: bool,
+
+    /// save the game in play every this many seconds and after moves, 30
+    /// unless configured, 0 to only save on quitting
+    #[argh(option)]
+    autosave: Option<u64>,
+}
+
+const DEFAULT_AUTOSAVE_SECS: u64 = 30;
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+enum Source {
+    Random,
+    Web,
+    Pack(PathBuf),
+}
+
+impl FromStr for Source {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "random" => Ok(Source::Random),
+            "web" => Ok(Source::Web),
+            "" => Err("Unsupported source: expected random, web or a file".to_string()),
+            path => Ok(Source::Pack(PathBuf::from(path))),
+        }
+    }
+}
+
+// tools that print to stdout instead of starting the game
+#[derive(FromArgs, Debug)]
+#[argh(subcommand)]
+enum Subcommand {
+    Generate(generate::Generate),
+    Solve(solve::Solve),
+    Rate(rate::Rate),
+    Verify(verify::Verify),
+    Print(print::Print),
+    Export(export::Export),
+    Completions(completions::Completions),
+    Bench(bench::Bench),
+    Canonicalize(canonicalize::Canonicalize),
+    Stats(stats::Stats),
+    Daily(daily::Daily),
+}
+
+fn parse_clues(value: &str) -> Result<usize, String> {
+    match value.parse() {
+        Ok(clues) if (MIN_CLUES..=MAX_CLUES).contains(&clues) => Ok(clues),
+        _ => Err(format!(
+            "clues must be a number from {} to {}",
+            MIN_CLUES, MAX_CLUES
+        )),
+    }
+}
+
+// a puzzle given on the command line, rejected unless it has exactly one
+// solution like the generated ones
+fn playable(board: &str) -> Result<Puzzle, String> {
+    let puzzle: Puzzle = board.parse()?;
+    match puzzle.solution_count() {
+        1 => Ok(puzzle),
+        _ => Err("The puzzle has more than one solution".to_string()),
+    }
+}
+
+// the given config file, or the one in the config directory if there is one
+fn load_config(path: Option<PathBuf>) -> Result<Config, String> {
+    let (path, must_exist) = match path {
+        Some(path) => (path, true),
+        None => match rsudoku::config_path() {
+            Some(path) => (path, false),
+            None => return Ok(Config::default()),
+        },
+    };
+    match Config::load(&path) {
+        Ok(config) => Ok(config),
+        Err(err) if err.kind() == io::ErrorKind::NotFound && !must_exist => Ok(Config::default()),
+        Err(err) => Err(format!("{}: {}", path.display(), err)),
+    }
+}
+
+// like `argh::from_env`, except that a bare --resume, argh having no option
+// with an optional value, gets the empty name for the game left unfinished
+fn parse_args() -> Sudoku {
+    let strings: Vec<String> = std::env::args().collect();
+    let command = strings
+        .first()
+        .and_then(|path| std::path::Path::new(path).file_name())
+        .map_or("sudoku".into(), |name| name.to_string_lossy());
+    let mut args: Vec<&str> = Vec::new();
+    for (i, arg) in strings.iter().enumerate().skip(1) {
+        args.push(arg);
+        let has_name = strings
+            .get(i + 1)
+            .is_some_and(|next| !next.starts_with('-'));
+        if arg == "--resume" && !has_name {
+            args.push("");
+        }
+    }
+    match Sudoku::from_args(&[&command], &args) {
+        Ok(args) => args,
+        Err(exit) => match exit.status {
+            Ok(()) => {
+                println!("{}", exit.output);
+                std::process::exit(0)
+            }
+            Err(()) => {
+                eprintln!(
+                    "{}\nRun {} --help for more information.",
+                    exit.output, command
+                );
+                std::process::exit(1)
+            }
+        },
+    }
+}
+
+fn main() -> io::Result<ExitCode> {
+    let args = parse_args();
+    // every subcommand but daily runs on its own
+    let daily = match args.command {
+        None => None,
+        Some(Subcommand::Daily(daily)) if daily.print => return daily.print(),
+        Some(Subcommand::Daily(daily)) => Some(daily),
+        Some(Subcommand::Generate(generate)) => return generate.run(),
+        Some(Subcommand::Solve(solve)) => return solve.run(),
+        Some(Subcommand::Rate(rate)) => return rate.run(),
+        Some(Subcommand::Verify(verify)) => return verify.run(),
+        Some(Subcommand::Print(print)) => return print.run(),
+        Some(Subcommand::Export(export)) => return export.run(),
+        Some(Subcommand::Completions(completions)) => return completions.run(),
+        Some(Subcommand::Bench(bench)) => return bench.run(),
+        Some(Subcommand::Canonicalize(canonicalize)) => return canonicalize.run(),
+        Some(Subcommand::Stats(stats)) => return stats.run(),
+    };
+    // the daily puzzle is the same for everyone, nothing may change it
+    let is_custom = args.difficulty.is_some()
+        || args.clues.is_some()
+        || args.puzzle.is_some()
+        || args.file.is_some()
+        || args.seed.is_some()
+        || args.resume.is_some();
+    if daily.is_some() && is_custom {
+        eprintln!("daily can't be combined with a difficulty, --clues, --puzzle, --file, --seed or --resume");
+        return Ok(ExitCode::from(1));
+    }
+
+    let theme = match args.theme.as_deref() {
+        Some("help") => {
+            println!("{}", Theme::NAMES.join("\n"));
+            return Ok(ExitCode::SUCCESS);
+        }
+        Some(name) => match name.parse::<Theme>() {
+            Ok(theme) => Some(theme),
+            Err(err) => {
+                eprintln!("{}, `--theme help` lists the themes", err);
+                return Ok(ExitCode::from(1));
+            }
+        },
+        None => None,
+    };
+
+    // flags win over the config file, which wins over the defaults
+    let config = match load_config(args.config) {
+        Ok(config) => config,
+        Err(err) => {
+            eprintln!("{}", err);
+            return Ok(ExitCode::from(1));
+        }
+    };
+
+    let board = match (args.puzzle, &args.file) {
+        (Some(board), _) => Some(board),
+        (None, Some(path)) => Some(fs::read_to_string(path)?),
+        (None, None) => None,
+    };
+    let puzzle = match board.as_deref().map(playable).transpose() {
+        Ok(puzzle) => puzzle,
+        Err(err) => {
+            eprintln!("{}", err);
+            return Ok(ExitCode::from(1));
+        }
+    };
+    let source: Option<Box<dyn PuzzleSource>> = match args.source {
+        None | Some(Source::Random) => None,
+        #[cfg(feature = "net")]
+        Some(Source::Web) => {
+            let source = match (config.source_url.as_deref(), rsudoku::data_dir()) {
+                (Some(url), Some(dir)) => rsudoku::WebSource::new(url, dir.join("web")),
+                (None, _) => Err("--source web needs source.url in the config".to_string()),
+                (_, None) => Err("There is no data directory to keep the puzzles in".to_string()),
+            };
+            match source {
+                Ok(source) => Some(Box::new(source)),
+                Err(err) => {
+                    eprintln!("{}", err);
+                    return Ok(ExitCode::from(1));
+                }
+            }
+        }
+        #[cfg(not(feature = "net"))]
+        Some(Source::Web) => {
+            eprintln!("--source web needs a build with the net feature");
+            return Ok(ExitCode::from(1));
+        }
+        Some(Source::Pack(path)) => match PuzzlePack::load(&path) {
+            Ok(pack) => Some(Box::new(pack)),
+            Err(err) => {
+                eprintln!("{}: {}", path.display(), err);
+                return Ok(ExitCode::from(1));
+            }
+        },
+    };
+
+    // what another machine pushed is pulled before anything is loaded
+    #[cfg(feature = "sync")]
+    let remote = match config
+        .sync_url
+        .as_deref()
+        .map(str::parse::<rsudoku::Remote>)
+    {
+        Some(Ok(remote)) => {
+            if remote.sends_password() {
+                eprintln!(
+                    "warning: sync.url has a password, which is sent unencrypted over http://"
+                );
+            }
+            rsudoku::data_dir().map(|dir| (remote, dir))
+        }
+        Some(Err(err)) => {
+            eprintln!("{}", err);
+            return Ok(ExitCode::from(1));
+        }
+        None => None,
+    };
+    #[cfg(feature = "sync")]
+    if let Some((remote, dir)) = &remote {
+        if let Err(err) = remote.pull(dir) {
+            eprintln!("could not pull the saves and stats: {}", err);
+        }
+    }
+    #[cfg(not(feature = "sync"))]
+    if config.sync_url.is_some() {
+        eprintln!("sync.url is set, but this build has no sync feature");
+    }
+
+    let mut keymap: Keymap = args.keymap.or(config.keymap).unwrap_or_default().into();
+    for &(action, key) in &config.bindings {
+        keymap.rebind(action, key);
+    }
+
+    let mut terminal = ratatui::init();
+    rsudoku::install_hooks()?;
+    let difficulty = daily.as_ref().map(|daily| daily.difficulty);
+    let app = match (difficulty.or(args.difficulty), args.clues) {
+        // the saved game replaces whatever would be started
+        _ if args.resume.is_some() => App::new_with_menu(),
+        (Some(difficulty), _) => App::new(difficulty),
+        (None, Some(_)) => App::new(Difficulty::Medium),
+        (None, None) => App::new_with_menu()
+            .with_default_difficulty(config.difficulty.unwrap_or(Difficulty::Medium)),
+    };
+    let mut app = app
+        .with_keymap(keymap)
+        .with_limited_mistakes(args.limited_mistakes || config.limited_mistakes == Some(true))
+        .with_strict(args.strict || config.strict == Some(true))
+        .with_auto_notes(args.auto_notes || config.auto_notes == Some(true))
+        .with_time_attack(args.time_attack || config.time_attack == Some(true))
+        .with_labels(args.labels.or(config.labels))
+        .with_theme(theme.or(config.theme).unwrap_or_default())
+        .with_compact(args.compact || config.compact == Some(true))
+        .with_bell(!args.no_bell && config.bell != Some(false))
+        .with_clock_hidden(args.hide_timer || config.hide_timer == Some(true))
+        .with_marathon(args.marathon)
+        .with_splits(args.splits)
+        .with_ghost(args.ghost)
+        .with_clues(args.clues)
+        .with_seed(args.seed)
+        .with_lang(args.lang.or(config.lang).unwrap_or_else(Lang::from_env))
+        .with_autosave(match args.autosave.or(config.autosave) {
+            Some(0) => None,
+            secs => Some(Duration::from_secs(secs.unwrap_or(DEFAULT_AUTOSAVE_SECS))),
+        });
+    if let Some(source) = source {
+        app = app.with_source(source);
+    }
+    if let Some(hints) = args.hints.or(config.hints) {
+        app = app.with_hint_budget(hints);
+    }
+    // last, so the seed and clue count don't replace it with a new puzzle
+    if let Some(puzzle) = puzzle {
+        app = app.with_puzzle(puzzle);
+    }
+    if daily.is_some() {
+        app = app.with_daily(rsudoku::today());
+    }
+    if let Some(dir) = rsudoku::data_dir() {
+        app = app.with_data_dir(dir);
+    }
+    match args.resume.as_deref() {
+        Some("") => app = app.with_saved_game(),
+        Some(name) => app = app.with_slot(name),
+        None => {}
+    }
+    let app_result = app.run(&mut terminal);
+    ratatui::restore();
+    #[cfg(feature = "sync")]
+    if let Some((remote, dir)) = &remote {
+        if let Err(err) = remote.push(dir) {
+            eprintln!("could not push the saves and stats: {}", err);
+        }
+    }
+
+    app_result.map(|()| ExitCode::SUCCESS)
+}
@@ -0,0 +1,61 @@
+use argh::FromArgs;
+use std::{
+    io::{self, Write},
+    process::ExitCode,
+};
+
+use rsudoku::{Puzzle, TextGrid};
+
+use crate::input;
+
+#[derive(FromArgs, Debug)]
+/// Draw each puzzle as a boxed grid
+#[argh(subcommand, name = "print")]
+pub struct Print {
+    /// the puzzle as 81 cells (0 or . for empty), or a file of them; read from
+    /// stdin when omitted
+    #[argh(positional)]
+    puzzle: Option<String>,
+
+    /// draw with +, - and | instead of box drawing characters
+    #[argh(switch)]
+    ascii: bool,
+
+    /// what an empty cell shows, e.g. _ or a space
+    #[argh(option, default = "'.'")]
+    blank: char,
+
+    /// print the solution beneath the puzzle
+    #[argh(switch)]
+    solution: bool,
+}
+
+impl Print {
+    pub fn run(self) -> io::Result<ExitCode> {
+        let mut code = ExitCode::SUCCESS;
+        let mut stdout = io::stdout().lock();
+        let grid = TextGrid::new().unicode(!self.ascii).blank(self.blank);
+
+        for (i, board) in input::read_puzzles(self.puzzle.as_deref())?
+            .iter()
+            .enumerate()
+        {
+            let puzzle = match board.parse::<Puzzle>() {
+                Ok(puzzle) => puzzle,
+                Err(err) => {
+                    eprintln!("puzzle {}: {}", i + 1, err);
+                    code = ExitCode::from(1);
+                    continue;
+                }
+            };
+            if i > 0 {
+                writeln!(stdout)?;
+            }
+            write!(stdout, "{}", grid.render(&puzzle, false))?;
+            if self.solution {
+                write!(stdout, "\n{}", grid.render(&puzzle.to_solved(), true))?;
+            }
+        }
+        Ok(code)
+    }
+}
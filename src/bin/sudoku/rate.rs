@@ -0,0 +1,77 @@
+use argh::FromArgs;
+use serde_json::json;
+use std::{
+    io::{self, Write},
+    process::ExitCode,
+    time::Instant,
+};
+
+use rsudoku::Puzzle;
+
+use crate::input;
+
+#[derive(FromArgs, Debug)]
+/// Print the difficulty, score and board of each puzzle, tab separated; the
+/// score adds up the techniques the solver needs
+#[argh(subcommand, name = "rate")]
+pub struct Rate {
+    /// the puzzle as 81 cells (0 or . for empty), or a file of them; read from
+    /// stdin when omitted
+    #[argh(positional)]
+    puzzle: Option<String>,
+
+    /// print a json object per puzzle instead, one per line, with the
+    /// hardest technique needed and the time taken
+    #[argh(switch)]
+    json: bool,
+}
+
+impl Rate {
+    pub fn run(self) -> io::Result<ExitCode> {
+        let mut code = ExitCode::SUCCESS;
+        let mut stdout = io::stdout().lock();
+
+        for (i, board) in input::read_puzzles(self.puzzle.as_deref())?
+            .iter()
+            .enumerate()
+        {
+            let started = Instant::now();
+            let result = board
+                .parse::<Puzzle>()
+                .map(|puzzle| (rsudoku::rate(&puzzle.grid()), puzzle));
+            let millis = started.elapsed().as_secs_f64() * 1000.0;
+            if result.is_err() {
+                code = ExitCode::from(1);
+            }
+
+            match (result, self.json) {
+                (Ok((rating, puzzle)), false) => writeln!(
+                    stdout,
+                    "{}\t{}\t{}",
+                    rating.difficulty,
+                    rating.score,
+                    puzzle.to_board(false)
+                )?,
+                (Err(err), false) => eprintln!("puzzle {}: {}", i + 1, err),
+                (Ok((rating, puzzle)), true) => writeln!(
+                    stdout,
+                    "{}",
+                    json!({
+                        "puzzle": puzzle.to_board(false),
+                        "difficulty": rating.difficulty,
+                        "score": rating.score,
+                        "hardest": rating.hardest.map(|technique| technique.name()),
+                        "stuck": rating.stuck,
+                        "millis": millis,
+                    })
+                )?,
+                (Err(err), true) => writeln!(
+                    stdout,
+                    "{}",
+                    json!({ "puzzle": board, "error": err, "millis": millis })
+                )?,
+            }
+        }
+        Ok(code)
+    }
+}
@@ -0,0 +1,146 @@
+use argh::FromArgs;
+use serde_json::json;
+use std::{
+    io::{self, Write},
+    process::ExitCode,
+    time::Instant,
+};
+
+use rsudoku::{Cell, ExportFormat, Puzzle, Step};
+
+use crate::input;
+
+#[derive(FromArgs, Debug)]
+/// Print the solution of each puzzle; exits with 1 if one has none or is
+/// malformed and 2 if one has several
+#[argh(subcommand, name = "solve")]
+pub struct Solve {
+    /// the puzzle as 81 cells (0 or . for empty), or a file of them; read from
+    /// stdin when omitted
+    #[argh(positional)]
+    puzzle: Option<String>,
+
+    /// how to print each solution (options: line, text)
+    #[argh(option, default = "ExportFormat::Line")]
+    format: ExportFormat,
+
+    /// print a json object per puzzle instead, one per line, with the
+    /// solution, whether it is unique or the error, and the time taken
+    #[argh(switch)]
+    json: bool,
+
+    /// print the placements the solving techniques make instead of the
+    /// solution, one per line with the technique and why it applies; with
+    /// --json they are added to each object
+    #[argh(switch)]
+    explain: bool,
+}
+
+// the placements the techniques make in order, until the board is full or
+// none of them applies
+fn walkthrough(puzzle: &Puzzle) -> Vec<Step> {
+    let mut grid = puzzle.grid();
+    let mut steps = Vec::new();
+    while let Some(step) = rsudoku::next_step(&grid) {
+        grid[step.row][step.col] = Cell::new(step.num, false);
+        steps.push(step);
+    }
+    steps
+}
+
+fn write_walkthrough(out: &mut impl Write, puzzle: &Puzzle) -> io::Result<()> {
+    let steps = walkthrough(puzzle);
+    for (i, step) in steps.iter().enumerate() {
+        writeln!(
+            out,
+            "{:>2}. R{}C{} = {}  {}: {}",
+            i + 1,
+            step.row + 1,
+            step.col + 1,
+            step.num,
+            step.technique.name(),
+            step.reason()
+        )?;
+    }
+    let left = 81 - puzzle.clues() - steps.len();
+    if left > 0 {
+        writeln!(out, "No technique applies with {} cells left", left)?;
+    }
+    Ok(())
+}
+
+impl Solve {
+    pub fn run(self) -> io::Result<ExitCode> {
+        let mut code = ExitCode::SUCCESS;
+        let mut stdout = io::stdout().lock();
+
+        for (i, board) in input::read_puzzles(self.puzzle.as_deref())?
+            .iter()
+            .enumerate()
+        {
+            let started = Instant::now();
+            // the exit code for a puzzle without exactly one solution
+            let result = match board.parse::<Puzzle>() {
+                Ok(puzzle) if puzzle.solution_count() > 1 => {
+                    Err((2, "The puzzle has more than one solution".to_string()))
+                }
+                Ok(puzzle) => Ok(puzzle),
+                Err(err) => Err((1, err)),
+            };
+            let millis = started.elapsed().as_secs_f64() * 1000.0;
+            if let Err((failure, _)) = &result {
+                code = ExitCode::from(*failure);
+            }
+
+            match (result, self.json) {
+                (Ok(puzzle), false) if self.explain => {
+                    // walkthroughs are kept apart by a blank line
+                    if i > 0 {
+                        writeln!(stdout)?;
+                    }
+                    write_walkthrough(&mut stdout, &puzzle)?
+                }
+                (Ok(puzzle), false) => {
+                    stdout.write_all(&self.format.write(&puzzle.to_solved(), true))?
+                }
+                (Err((_, err)), false) => eprintln!("puzzle {}: {}", i + 1, err),
+                (Ok(puzzle), true) => {
+                    let mut report = json!({
+                        "puzzle": puzzle.to_board(false),
+                        "solution": puzzle.to_solved().to_board(true),
+                        "unique": true,
+                        "millis": millis,
+                    });
+                    if self.explain {
+                        let steps: Vec<_> = walkthrough(&puzzle)
+                            .iter()
+                            .map(|step| {
+                                json!({
+                                    "row": step.row + 1,
+                                    "col": step.col + 1,
+                                    "num": step.num,
+                                    "technique": step.technique.name(),
+                                    "reason": step.reason(),
+                                })
+                            })
+                            .collect();
+                        report["steps"] = steps.into();
+                    }
+                    writeln!(stdout, "{}", report)?
+                }
+                (Err((_, err)), true) => writeln!(
+                    stdout,
+                    "{}",
+                    json!({
+                        "puzzle": board,
+                        "solution": null,
+                        "unique": false,
+                        "error": err,
+                        "millis": millis,
+                    })
+                )?,
+            }
+        }
+        Ok(code)
+    }
+}
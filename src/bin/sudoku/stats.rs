@@ -0,0 +1,131 @@
+use argh::FromArgs;
+use serde_json::json;
+use std::{
+    io::{self, Write},
+    process::ExitCode,
+    time::Duration,
+};
+
+use rsudoku::Difficulty;
+
+#[derive(FromArgs, Debug)]
+/// Print the games played, completion rate and average and best times per
+/// difficulty, and the current and longest streak, as kept by the game
+#[argh(subcommand, name = "stats")]
+pub struct Stats {
+    /// print a json object instead, with the times in seconds
+    #[argh(switch)]
+    json: bool,
+}
+
+fn format_time(time: Option<Duration>) -> String {
+    match time {
+        Some(time) => {
+            let secs = time.as_secs();
+            format!(
+                "{:02}:{:02}:{:02}",
+                secs / 3600,
+                secs % 3600 / 60,
+                secs % 60
+            )
+        }
+        None => "-".to_string(),
+    }
+}
+
+impl Stats {
+    pub fn run(self) -> io::Result<ExitCode> {
+        let Some(dir) = rsudoku::data_dir() else {
+            eprintln!("There is no data directory to read the stats from");
+            return Ok(ExitCode::from(1));
+        };
+        let stats = match rsudoku::Stats::load(&dir) {
+            Ok(stats) => stats,
+            Err(err) => {
+                eprintln!("{}: {}", rsudoku::Stats::path(&dir).display(), err);
+                return Ok(ExitCode::from(1));
+            }
+        };
+        let streak = stats.current_streak(rsudoku::today());
+        let mut stdout = io::stdout().lock();
+
+        if self.json {
+            let difficulties: serde_json::Map<_, _> = Difficulty::all()
+                .iter()
+                .map(|&difficulty| {
+                    let played = stats.difficulty(difficulty);
+                    let report = json!({
+                        "played": played.played,
+                        "solved": played.solved,
+                        "completion_rate": played.completion_rate(),
+                        "average_secs": played.average_time().map(|time| time.as_secs()),
+                        "best_secs": played.best_secs,
+                        "hardcore_best_secs": played.hardcore_best_secs,
+                    });
+                    (difficulty.to_string(), report)
+                })
+                .collect();
+            let report = json!({
+                "difficulties": difficulties,
+                "solved": stats.solved(),
+                "streak": streak,
+                "longest_streak": stats.longest_streak(),
+                "achievements": stats.achievements().len(),
+                "unlocked": stats
+                    .achievements()
+                    .iter()
+                    .map(|unlocked| json!({
+                        "achievement": unlocked.achievement,
+                        "date": unlocked.day.map(rsudoku::format_day),
+                    }))
+                    .collect::<Vec<_>>(),
+                "daily": stats.daily().len(),
+            });
+            writeln!(stdout, "{}", report)?;
+            return Ok(ExitCode::SUCCESS);
+        }
+
+        writeln!(
+            stdout,
+            "{:<10} {:>6} {:>6} {:>5} {:>9} {:>9} {:>9}",
+            "", "played", "solved", "rate", "average", "best", "hardcore"
+        )?;
+        for difficulty in Difficulty::all() {
+            let played = stats.difficulty(difficulty);
+            let rate = played
+                .completion_rate()
+                .map_or("-".to_string(), |rate| format!("{:.0}%", rate * 100.0));
+            writeln!(
+                stdout,
+                "{:<10} {:>6} {:>6} {:>5} {:>9} {:>9} {:>9}",
+                difficulty.to_string(),
+                played.played,
+                played.solved,
+                rate,
+                format_time(played.average_time()),
+                format_time(played.best_time()),
+                format_time(played.hardcore_best_time())
+            )?;
+        }
+        writeln!(stdout)?;
+        let days = |days: u32| format!("{} day{}", days, if days == 1 { "" } else { "s" });
+        writeln!(stdout, "Current streak: {}", days(streak))?;
+        writeln!(stdout, "Longest streak: {}", days(stats.longest_streak()))?;
+        writeln!(
+            stdout,
+            "Achievements: {} of {}",
+            stats.achievements().len(),
+            rsudoku::Achievement::ALL.len()
+        )?;
+        for unlocked in stats.achievements() {
+            writeln!(
+                stdout,
+                "  {:<16} {}",
+                unlocked.achievement.title(),
+                unlocked.day.map(rsudoku::format_day).unwrap_or_default()
+            )?;
+        }
+        writeln!(stdout, "Daily puzzles solved: {}", stats.daily().len())?;
+        Ok(ExitCode::SUCCESS)
+    }
+}
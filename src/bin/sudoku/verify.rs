@@ -0,0 +1,66 @@
+use argh::FromArgs;
+use serde_json::json;
+use std::{
+    io::{self, Write},
+    process::ExitCode,
+    time::Instant,
+};
+
+use rsudoku::Puzzle;
+
+use crate::input;
+
+#[derive(FromArgs, Debug)]
+/// Check that each puzzle reads, its clues don't conflict and it has exactly
+/// one solution; exits with 1 if any puzzle fails
+#[argh(subcommand, name = "verify")]
+pub struct Verify {
+    /// the puzzle as 81 cells (0 or . for empty), or a file of them; read from
+    /// stdin when omitted
+    #[argh(positional)]
+    puzzle: Option<String>,
+
+    /// print a json object per puzzle instead, one per line, with whether it
+    /// passed, the error if not and the time taken
+    #[argh(switch)]
+    json: bool,
+}
+
+impl Verify {
+    pub fn run(self) -> io::Result<ExitCode> {
+        let mut code = ExitCode::SUCCESS;
+        let mut stdout = io::stdout().lock();
+
+        for (i, board) in input::read_puzzles(self.puzzle.as_deref())?
+            .iter()
+            .enumerate()
+        {
+            let started = Instant::now();
+            let result = match board.parse::<Puzzle>() {
+                Ok(puzzle) if puzzle.solution_count() == 1 => Ok(()),
+                Ok(_) => Err("The puzzle has more than one solution".to_string()),
+                Err(err) => Err(err),
+            };
+            let millis = started.elapsed().as_secs_f64() * 1000.0;
+            if result.is_err() {
+                code = ExitCode::from(1);
+            }
+
+            match (result, self.json) {
+                (Ok(()), false) => writeln!(stdout, "puzzle {}: ok", i + 1)?,
+                (Err(err), false) => writeln!(stdout, "puzzle {}: {}", i + 1, err)?,
+                (result, true) => writeln!(
+                    stdout,
+                    "{}",
+                    json!({
+                        "puzzle": board,
+                        "ok": result.is_ok(),
+                        "error": result.err(),
+                        "millis": millis,
+                    })
+                )?,
+            }
+        }
+        Ok(code)
+    }
+}
@@ -0,0 +1,556 @@
+use ratatui::{
+    buffer::Buffer,
+    layout::{Constraint, Flex, Layout, Rect},
+    style::{Color, Style, Stylize},
+    widgets::Widget,
+};
+
+use std::str::FromStr;
+
+use crate::{coords::Labels, puzzle::Grid};
+
+// the smallest cell height that still fits a digit inside its lines
+pub(crate) const MIN_CELL_SIZE: u16 = 2;
+// how many times wider than tall a cell is drawn
+pub(crate) const CELL_ASPECT: u16 = 2;
+// the compact board, two columns per cell and a line between boxes
+pub(crate) const COMPACT_SIZE: (u16, u16) = (23, 11);
+
+// the colors a board is drawn with
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Theme {
+    pub clue: Style,
+    pub entry: Style,
+    pub wrong: Style,         // entries that break a rule
+    pub note: Style,          // patched over the cell's style
+    pub lines: Style,         // between the cells
+    pub compact_lines: Style, // between the boxes of the compact board
+    pub label: Style,
+    // backgrounds for the color slots, dark enough to keep digits readable
+    pub cell_colors: [Color; 5],
+    pub target: Color, // the highlighted cell
+    pub peers: Color,  // the cells highlighted along with it
+}
+
+impl Default for Theme {
+    fn default() -> Self {
+        Theme {
+            clue: Style::default().fg(Color::Yellow).bold(),
+            entry: Style::default().fg(Color::Blue).bold(),
+            wrong: Style::default().fg(Color::Red).bold(),
+            note: Style::default().fg(Color::DarkGray),
+            lines: Style::default(),
+            compact_lines: Style::default().fg(Color::DarkGray),
+            label: Style::default().fg(Color::DarkGray),
+            cell_colors: [
+                Color::Indexed(52),
+                Color::Indexed(22),
+                Color::Indexed(58),
+                Color::Indexed(17),
+                Color::Indexed(53),
+            ],
+            target: Color::Indexed(28),
+            peers: Color::Indexed(237),
+        }
+    }
+}
+
+// solarized's accents and grays, https://ethanschoonover.com/solarized
+const BASE03: Color = Color::Rgb(0x00, 0x2b, 0x36);
+const BASE02: Color = Color::Rgb(0x07, 0x36, 0x42);
+const BASE01: Color = Color::Rgb(0x58, 0x6e, 0x75);
+const BASE1: Color = Color::Rgb(0x93, 0xa1, 0xa1);
+const BASE2: Color = Color::Rgb(0xee, 0xe8, 0xd5);
+const YELLOW: Color = Color::Rgb(0xb5, 0x89, 0x00);
+const RED: Color = Color::Rgb(0xdc, 0x32, 0x2f);
+const BLUE: Color = Color::Rgb(0x26, 0x8b, 0xd2);
+
+impl Theme {
+    // the names `Theme::from_str` knows, the first being the default
+    pub const NAMES: [&'static str; 4] = [
+        "default",
+        "solarized-dark",
+        "solarized-light",
+        "high-contrast",
+    ];
+
+    fn solarized_dark() -> Self {
+        Theme {
+            clue: Style::default().fg(YELLOW).bold(),
+            entry: Style::default().fg(BLUE).bold(),
+            wrong: Style::default().fg(RED).bold(),
+            note: Style::default().fg(BASE01),
+            lines: Style::default().fg(BASE01),
+            compact_lines: Style::default().fg(BASE01),
+            label: Style::default().fg(BASE01),
+            cell_colors: [
+                Color::Rgb(0x4a, 0x1c, 0x22),
+                Color::Rgb(0x1f, 0x3d, 0x1c),
+                Color::Rgb(0x4a, 0x3d, 0x10),
+                Color::Rgb(0x0f, 0x2a, 0x4a),
+                Color::Rgb(0x3d, 0x1c, 0x3d),
+            ],
+            target: Color::Rgb(0x1b, 0x5e, 0x5a),
+            peers: BASE02,
+        }
+    }
+
+    fn solarized_light() -> Self {
+        Theme {
+            clue: Style::default().fg(BASE03).bold(),
+            entry: Style::default().fg(BLUE).bold(),
+            wrong: Style::default().fg(RED).bold(),
+            note: Style::default().fg(BASE1),
+            lines: Style::default().fg(BASE1),
+            compact_lines: Style::default().fg(BASE1),
+            label: Style::default().fg(BASE1),
+            cell_colors: [
+                Color::Rgb(0xf5, 0xd5, 0xd0),
+                Color::Rgb(0xdf, 0xeb, 0xc8),
+                Color::Rgb(0xf3, 0xe6, 0xc0),
+                Color::Rgb(0xd3, 0xe2, 0xf0),
+                Color::Rgb(0xee, 0xd6, 0xe6),
+            ],
+            target: Color::Rgb(0xc8, 0xe0, 0xc0),
+            peers: BASE2,
+        }
+    }
+
+    // plain white lines and bright digits for dim screens and projectors
+    fn high_contrast() -> Self {
+        Theme {
+            clue: Style::default().fg(Color::White).bold(),
+            entry: Style::default().fg(Color::LightCyan).bold(),
+            wrong: Style::default().fg(Color::LightRed).bold().underlined(),
+            note: Style::default().fg(Color::Gray),
+            lines: Style::default().fg(Color::White),
+            compact_lines: Style::default().fg(Color::White),
+            label: Style::default().fg(Color::White),
+            target: Color::Blue,
+            peers: Color::Indexed(238),
+            ..Theme::default()
+        }
+    }
+}
+
+impl FromStr for Theme {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.to_lowercase().as_str() {
+            "default" => Ok(Theme::default()),
+            "solarized-dark" | "solarized" => Ok(Theme::solarized_dark()),
+            "solarized-light" => Ok(Theme::solarized_light()),
+            "high-contrast" => Ok(Theme::high_contrast()),
+            _ => Err(format!("Invalid theme: {}", s)),
+        }
+    }
+}
+
+// extra styling for a cell given its row, column and the style worked out
+// so far
+pub type CellStyler<'a> = &'a dyn Fn(usize, usize, Style) -> Style;
+
+// a cell with the cells that explain it
+type Highlight<'a> = ((usize, usize), &'a [(usize, usize)]);
+
+// a sudoku board that fills the area it is given, with cells as large as fit
+// and thick lines around the boxes, for embedding in any ratatui app.
+//
+//     let grid = Puzzle::new(Difficulty::Easy).grid();
+//     frame.render_widget(SudokuGridWidget::new(&grid).selected((4, 4)), area);
+#[derive(Clone, Copy)]
+pub struct SudokuGridWidget<'a> {
+    grid: &'a Grid,
+    selected: Option<(usize, usize)>,
+    highlight: Option<Highlight<'a>>,
+    theme: Theme,
+    labels: Option<Labels>,
+    compact: bool,
+    styler: Option<CellStyler<'a>>,
+}
+
+impl<'a> SudokuGridWidget<'a> {
+    pub fn new(grid: &'a Grid) -> Self {
+        SudokuGridWidget {
+            grid,
+            selected: None,
+            highlight: None,
+            theme: Theme::default(),
+            labels: None,
+            compact: false,
+            styler: None,
+        }
+    }
+
+    // underline the cell the cursor is on
+    pub fn selected(mut self, cell: (usize, usize)) -> Self {
+        self.selected = Some(cell);
+        self
+    }
+
+    // light up a cell and the cells that explain it, like a solver step
+    pub fn highlight(mut self, cell: (usize, usize), cells: &'a [(usize, usize)]) -> Self {
+        self.highlight = Some((cell, cells));
+        self
+    }
+
+    pub fn theme(mut self, theme: Theme) -> Self {
+        self.theme = theme;
+        self
+    }
+
+    // coordinates along the top and left edges
+    pub fn labels(mut self, labels: Option<Labels>) -> Self {
+        self.labels = labels;
+        self
+    }
+
+    // one character per cell with lines between the boxes only, it takes
+    // COMPACT_SIZE whatever the area
+    pub fn compact(mut self, compact: bool) -> Self {
+        self.compact = compact;
+        self
+    }
+
+    // layer more styling over each cell, for marks the widget doesn't know
+    pub fn cell_style(mut self, styler: CellStyler<'a>) -> Self {
+        self.styler = Some(styler);
+        self
+    }
+
+    // the smallest area the board fits in
+    pub fn min_size(&self) -> (u16, u16) {
+        if self.compact {
+            return COMPACT_SIZE;
+        }
+        let labels = self.labels.is_some() as u16;
+        (
+            9 * MIN_CELL_SIZE * CELL_ASPECT + 1 + labels,
+            9 * MIN_CELL_SIZE + 1 + labels,
+        )
+    }
+
+    // where the full board's lines go in `area`, closing lines included
+    pub fn grid_area(&self, area: Rect) -> Rect {
+        let (cell_width, cell_height) = self.cell_size(area);
+        let area = self.label_room(area);
+        let width = 9 * cell_width + 1;
+        let height = 9 * cell_height + 1;
+        Rect {
+            x: area.x + area.width.saturating_sub(width) / 2,
+            y: area.y + area.height.saturating_sub(height) / 2,
+            width: width.min(area.width),
+            height: height.min(area.height),
+        }
+    }
+
+    // the cell drawn at (x, y) when rendered in `area`, None off the board
+    // and on the compact board's lines, the full board's lines belong to the
+    // cell right or below of them
+    pub fn cell_at(&self, area: Rect, x: u16, y: u16) -> Option<(usize, usize)> {
+        if self.compact {
+            let area = compact_area(area);
+            let (dx, dy) = (x.checked_sub(area.x)?, y.checked_sub(area.y)?);
+            if dx >= area.width || dy >= area.height || dx % 8 == 7 || dy % 4 == 3 {
+                return None;
+            }
+            let col = dx / 8 * 3 + (dx % 8 / 2).min(2);
+            let row = dy / 4 * 3 + dy % 4;
+            return Some((row as usize, col as usize));
+        }
+
+        let (cell_width, cell_height) = self.cell_size(area);
+        let grid = self.grid_area(area);
+        let (dx, dy) = (x.checked_sub(grid.x)?, y.checked_sub(grid.y)?);
+        let (col, row) = (dx / cell_width.max(1), dy / cell_height.max(1));
+        (col < 9 && row < 9).then_some((row as usize, col as usize))
+    }
+
+    // keep a column on the left and a line on top free for the labels
+    fn label_room(&self, mut area: Rect) -> Rect {
+        if self.labels.is_some() {
+            area.x += 1;
+            area.y += 1;
+            area.width = area.width.saturating_sub(1);
+            area.height = area.height.saturating_sub(1);
+        }
+        area
+    }
+
+    // the closing lines on the right and bottom take one more column and
+    // row, terminal characters are about twice as tall as wide so cells
+    // are twice as wide as tall to look square
+    fn cell_size(&self, area: Rect) -> (u16, u16) {
+        let area = self.label_room(area);
+        let cell_height = std::cmp::min(
+            area.height.saturating_sub(1) / 9,
+            area.width.saturating_sub(1) / 9 / CELL_ASPECT,
+        );
+        (cell_height * CELL_ASPECT, cell_height)
+    }
+
+    // the symbol drawn for a cell and its style, layering the states that
+    // highlight it
+    fn cell_look(&self, row: usize, col: usize) -> (String, Style) {
+        let theme = &self.theme;
+        let cell = self.grid[row][col];
+        let (symbol, style) = if cell.value() == 0 {
+            (" ".into(), Style::default()) // empty cell
+        } else if cell.is_clue() {
+            (cell.value().to_string(), theme.clue)
+        } else if cell.possible_wrong() {
+            (cell.value().to_string(), theme.wrong)
+        } else {
+            (cell.value().to_string(), theme.entry)
+        };
+
+        let style = if self.selected == Some((row, col)) {
+            style.underlined()
+        } else {
+            style
+        };
+        let style = match cell.color() {
+            0 => style,
+            color => style.bg(theme.cell_colors[(color - 1) as usize]),
+        };
+        let style = match self.highlight {
+            Some((target, _)) if target == (row, col) => style.bg(theme.target),
+            Some((_, cells)) if cells.contains(&(row, col)) => style.bg(theme.peers),
+            _ => style,
+        };
+        let style = match self.styler {
+            Some(styler) => styler(row, col, style),
+            None => style,
+        };
+
+        (symbol, style)
+    }
+
+    fn render_compact(&self, area: Rect, buf: &mut Buffer) {
+        let area = compact_area(area);
+        let (width, height) = (area.width, area.height);
+
+        let line_style = self.theme.compact_lines;
+        for i in [1, 2] {
+            let x = area.x + i * 8 - 1;
+            for dy in 0..height {
+                buf.set_string(x, area.y + dy, "│", line_style);
+            }
+        }
+        for i in [1, 2] {
+            let y = area.y + i * 4 - 1;
+            for dx in 0..width {
+                let symbol = if (dx + 1) % 8 == 0 { "┼" } else { "─" };
+                buf.set_string(area.x + dx, y, symbol, line_style);
+            }
+        }
+
+        for row in 0..9 {
+            for col in 0..9 {
+                let x = area.x + 1 + col as u16 * 2 + col as u16 / 3 * 2;
+                let y = area.y + row as u16 + row as u16 / 3;
+                let (symbol, style) = self.cell_look(row, col);
+                // a dot keeps empty cells visible without lines around them
+                let symbol = if symbol == " " { "·".into() } else { symbol };
+                buf.set_string(x, y, symbol, style);
+            }
+        }
+    }
+}
+
+impl Widget for SudokuGridWidget<'_> {
+    fn render(self, area: Rect, buf: &mut Buffer) {
+        if self.compact {
+            return self.render_compact(area, buf);
+        }
+
+        let (cell_width, cell_height) = self.cell_size(area);
+        let grid_area = self.grid_area(area);
+        draw_grid_lines(grid_area, cell_width, cell_height, self.theme.lines, buf);
+
+        for row in 0..9 {
+            for col in 0..9 {
+                let x = grid_area.x + col as u16 * cell_width;
+                let y = grid_area.y + row as u16 * cell_height;
+
+                let cell = self.grid[row][col];
+                let (symbol, cell_style) = self.cell_look(row, col);
+                if cell.color() != 0 {
+                    // fill the whole cell so the color reads at any size
+                    let background = self.theme.cell_colors[(cell.color() - 1) as usize];
+                    for dy in 1..cell_height {
+                        for dx in 1..cell_width {
+                            buf.set_string(x + dx, y + dy, " ", Style::default().bg(background));
+                        }
+                    }
+                }
+
+                // notes sit in a 3x3 pattern once the cell has room for them
+                let notes = cell.notes();
+                if cell.value() == 0 && !notes.is_empty() && cell_height > 3 {
+                    // spread over the cell's width, keeping them centered
+                    let spacing = (cell_width - 1) / 3;
+                    for num in notes {
+                        let index = (num - 1) as u16;
+                        buf.set_string(
+                            x + 1 + spacing / 2 + index % 3 * spacing,
+                            y + 1 + index / 3,
+                            num.to_string(),
+                            cell_style.patch(self.theme.note),
+                        );
+                    }
+                    continue;
+                }
+
+                // center the symbol in the cell
+                buf.set_stringn(
+                    x + cell_width / 2,
+                    y + cell_height / 2,
+                    &symbol,
+                    1,
+                    cell_style,
+                );
+            }
+        }
+
+        if let Some(labels) = self.labels {
+            for i in 0..9 {
+                buf.set_string(
+                    grid_area.x - 1,
+                    grid_area.y + i as u16 * cell_height + cell_height / 2,
+                    labels.row(i).to_string(),
+                    self.theme.label,
+                );
+                buf.set_string(
+                    grid_area.x + i as u16 * cell_width + cell_width / 2,
+                    grid_area.y - 1,
+                    labels.col(i).to_string(),
+                    self.theme.label,
+                );
+            }
+        }
+    }
+}
+
+// where the compact board goes, centered in `area`
+fn compact_area(area: Rect) -> Rect {
+    let (width, height) = COMPACT_SIZE;
+    let [area] = Layout::horizontal([Constraint::Length(width)])
+        .flex(Flex::Center)
+        .areas(area);
+    let [area] = Layout::vertical([Constraint::Length(height)])
+        .flex(Flex::Center)
+        .areas(area);
+    area
+}
+
+// the lines between and around the cells, thick around the boxes, with
+// junctions picked from the lines that meet there
+fn draw_grid_lines(area: Rect, cell_width: u16, cell_height: u16, style: Style, buf: &mut Buffer) {
+    let is_thick = |line: u16| line.is_multiple_of(3);
+    let (right, bottom) = (area.x + 9 * cell_width, area.y + 9 * cell_height);
+
+    for line in 0..=9 {
+        let y = area.y + line * cell_height;
+        let symbol = if is_thick(line) { "━" } else { "─" };
+        for x in area.x..=right {
+            buf.set_string(x, y, symbol, style);
+        }
+
+        let x = area.x + line * cell_width;
+        let symbol = if is_thick(line) { "┃" } else { "│" };
+        for y in area.y..=bottom {
+            buf.set_string(x, y, symbol, style);
+        }
+    }
+
+    for row in 0..=9 {
+        for col in 0..=9 {
+            let symbol = junction(
+                [row > 0, row < 9, col > 0, col < 9],
+                is_thick(row),
+                is_thick(col),
+            );
+            buf.set_string(
+                area.x + col * cell_width,
+                area.y + row * cell_height,
+                symbol,
+                style,
+            );
+        }
+    }
+}
+
+// the box drawing character joining the arms that exist, given as up, down,
+// left and right, where the horizontal and vertical lines may each be thick
+fn junction(arms: [bool; 4], thick_horizontal: bool, thick_vertical: bool) -> &'static str {
+    // each shape as light, thick horizontal, thick vertical and all thick
+    let variants = match arms {
+        [false, true, false, true] => ["┌", "┍", "┎", "┏"],
+        [false, true, true, false] => ["┐", "┑", "┒", "┓"],
+        [true, false, false, true] => ["└", "┕", "┖", "┗"],
+        [true, false, true, false] => ["┘", "┙", "┚", "┛"],
+        [true, true, false, true] => ["├", "┝", "┠", "┣"],
+        [true, true, true, false] => ["┤", "┥", "┨", "┫"],
+        [false, true, true, true] => ["┬", "┯", "┰", "┳"],
+        [true, false, true, true] => ["┴", "┷", "┸", "┻"],
+        [true, true, true, true] => ["┼", "┿", "╂", "╋"],
+        // a straight line otherwise
+        [false, false, _, _] => ["─", "━", "─", "━"],
+        _ => ["│", "│", "┃", "┃"],
+    };
+    variants[thick_horizontal as usize | (thick_vertical as usize) << 1]
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{Difficulty, Puzzle};
+
+    #[test]
+    fn test_every_named_theme_parses() {
+        let themes: Vec<Theme> = Theme::NAMES
+            .iter()
+            .map(|name| name.parse().unwrap())
+            .collect();
+        assert_eq!(themes[0], Theme::default());
+        // no two names give the same colors
+        for (i, theme) in themes.iter().enumerate() {
+            assert!(!themes[i + 1..].contains(theme));
+        }
+        assert!("sepia".parse::<Theme>().is_err());
+    }
+
+    #[test]
+    fn test_board_fills_its_area() {
+        let grid = Puzzle::new(Difficulty::Easy).grid();
+        let widget = SudokuGridWidget::new(&grid).selected((0, 0));
+        let area = Rect::new(0, 0, 80, 40);
+        let mut buf = Buffer::empty(area);
+        widget.render(area, &mut buf);
+
+        // cells of 8x4 centered horizontally, 73 wide and 37 tall
+        let lines = widget.grid_area(area);
+        assert_eq!(lines, Rect::new(3, 1, 73, 37));
+        assert_eq!(buf[(3, 1)].symbol(), "┏");
+        assert_eq!(buf[(75, 37)].symbol(), "┛");
+        assert_eq!(buf[(27, 13)].symbol(), "╋");
+
+        assert_eq!(widget.cell_at(area, 3, 1), Some((0, 0)));
+        assert_eq!(widget.cell_at(area, 30, 14), Some((3, 3)));
+        assert_eq!(widget.cell_at(area, 76, 20), None);
+
+        let compact = widget.compact(true);
+        let mut buf = Buffer::empty(area);
+        compact.render(area, &mut buf);
+        // the compact board sits at (29, 15)
+        assert_eq!(buf[(29 + 7, 15)].symbol(), "│");
+        assert_eq!(compact.cell_at(area, 30, 15), Some((0, 0)));
+        assert_eq!(compact.cell_at(area, 29 + 9, 15 + 4), Some((3, 3)));
+        assert_eq!(compact.cell_at(area, 29 + 7, 15), None);
+
+        let (width, height) = widget.min_size();
+        assert!(width <= 2 * 9 * 2 + 1 && height <= 2 * 9 + 1);
+    }
+}
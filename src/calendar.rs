@@ -0,0 +1,167 @@
+use std::ops::Range;
+
+use crate::{
+    daily::{civil_from_days, days_from_civil},
+    Difficulty,
+};
+
+const MONTHS: [&str; 12] = [
+    "January",
+    "February",
+    "March",
+    "April",
+    "May",
+    "June",
+    "July",
+    "August",
+    "September",
+    "October",
+    "November",
+    "December",
+];
+
+// one month of daily puzzles at a difficulty, to see which were solved and
+// pick a missed one to play; days are counted like `today()`
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub(crate) struct Calendar {
+    today: u64,
+    first: u64, // the first day of the month shown
+    selected: u64,
+    difficulty: Difficulty,
+}
+
+fn first_of_month(day: u64) -> u64 {
+    let (y, m, _) = civil_from_days(day);
+    days_from_civil(y, m, 1)
+}
+
+impl Calendar {
+    pub(crate) fn new(today: u64, difficulty: Difficulty) -> Self {
+        Calendar {
+            today,
+            first: first_of_month(today),
+            selected: today,
+            difficulty,
+        }
+    }
+
+    pub(crate) fn today(&self) -> u64 {
+        self.today
+    }
+
+    pub(crate) fn selected(&self) -> u64 {
+        self.selected
+    }
+
+    pub(crate) fn difficulty(&self) -> Difficulty {
+        self.difficulty
+    }
+
+    // every day of the month shown
+    pub(crate) fn days(&self) -> Range<u64> {
+        let (y, m, _) = civil_from_days(self.first);
+        let next = match m {
+            12 => days_from_civil(y + 1, 1, 1),
+            m => days_from_civil(y, m + 1, 1),
+        };
+        self.first..next
+    }
+
+    // e.g. October 2026
+    pub(crate) fn title(&self) -> String {
+        let (y, m, _) = civil_from_days(self.first);
+        format!("{} {}", MONTHS[m as usize - 1], y)
+    }
+
+    // how many days of the first week, which starts on a Monday, come
+    // before the month does; the epoch was a Thursday
+    pub(crate) fn leading_days(&self) -> usize {
+        ((self.first + 3) % 7) as usize
+    }
+
+    // move the selection by whole days, staying in the month and never
+    // past today, whose puzzle is the last one out
+    pub(crate) fn move_by(&mut self, days: i64) {
+        let days_shown = self.days();
+        let last = (days_shown.end - 1).min(self.today);
+        self.selected = self
+            .selected
+            .saturating_add_signed(days)
+            .clamp(days_shown.start, last);
+    }
+
+    pub(crate) fn previous_month(&mut self) {
+        if self.first == 0 {
+            return;
+        }
+        self.show_month(first_of_month(self.first - 1));
+    }
+
+    pub(crate) fn next_month(&mut self) {
+        let next = self.days().end;
+        if next <= self.today {
+            self.show_month(next);
+        }
+    }
+
+    // keeps the day of the month where the new month has it
+    fn show_month(&mut self, first: u64) {
+        let offset = self.selected - self.first;
+        self.first = first;
+        self.selected = first;
+        self.move_by(offset as i64);
+    }
+
+    pub(crate) fn next_difficulty(&mut self) {
+        let all = Difficulty::all();
+        let next = all.iter().position(|&d| d == self.difficulty).unwrap_or(0) + 1;
+        self.difficulty = all[next % all.len()];
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    // 2026-10-14, a Wednesday
+    const TODAY: u64 = 20_740;
+
+    #[test]
+    fn test_the_month_of_today() {
+        let calendar = Calendar::new(TODAY, Difficulty::Medium);
+        assert_eq!(calendar.title(), "October 2026");
+        assert_eq!(calendar.days(), 20_727..20_758);
+        // the 1st was a Thursday
+        assert_eq!(calendar.leading_days(), 3);
+        assert_eq!(calendar.selected(), TODAY);
+    }
+
+    #[test]
+    fn test_selection_stays_in_the_month_and_the_past() {
+        let mut calendar = Calendar::new(TODAY, Difficulty::Medium);
+        calendar.move_by(7);
+        assert_eq!(calendar.selected(), TODAY);
+        calendar.move_by(-30);
+        assert_eq!(calendar.selected(), 20_727);
+
+        calendar.next_month();
+        assert_eq!(calendar.title(), "October 2026");
+        calendar.previous_month();
+        calendar.previous_month();
+        assert_eq!(calendar.title(), "August 2026");
+        assert_eq!(calendar.selected(), days_from_civil(2026, 8, 1));
+
+        calendar.move_by(30);
+        calendar.next_month();
+        // the 31st of August becomes the last of September
+        assert_eq!(calendar.selected(), days_from_civil(2026, 9, 30));
+        assert_eq!(calendar.days().count(), 30);
+    }
+
+    #[test]
+    fn test_december_runs_into_the_next_year() {
+        let calendar = Calendar::new(days_from_civil(2025, 12, 20), Difficulty::Easy);
+        assert_eq!(calendar.title(), "December 2025");
+        assert_eq!(calendar.days().count(), 31);
+    }
+}
@@ -0,0 +1,140 @@
+use alloc::{format, string::String, vec::Vec};
+
+use crate::puzzle::Grid;
+
+const SIZE: usize = 9;
+const PERMUTATIONS: [[usize; 3]; 6] = [
+    [0, 1, 2],
+    [0, 2, 1],
+    [1, 0, 2],
+    [1, 2, 0],
+    [2, 0, 1],
+    [2, 1, 0],
+];
+
+// the 1296 orders of rows, or columns, that keep every band together: the
+// bands swapped around and the lines within each one as well
+fn line_orders() -> Vec<[usize; SIZE]> {
+    let mut orders = Vec::with_capacity(6 * 6 * 6 * 6);
+    for bands in PERMUTATIONS {
+        for first in PERMUTATIONS {
+            for second in PERMUTATIONS {
+                for third in PERMUTATIONS {
+                    let mut order = [0; SIZE];
+                    for (band, lines) in [first, second, third].iter().enumerate() {
+                        for (i, line) in lines.iter().enumerate() {
+                            order[band * 3 + i] = bands[band] * 3 + line;
+                        }
+                    }
+                    orders.push(order);
+                }
+            }
+        }
+    }
+    orders
+}
+
+// the clues of a grid as 81 digits, 0 for an empty cell, in its canonical
+// form: the smallest of all the boards it can be turned into by transposing,
+// swapping bands, stacks and the lines within them, and renaming digits,
+// which are numbered in the order they first turn up. Two puzzles are the
+// same puzzle in disguise exactly when their forms are equal.
+pub fn canonical_form(grid: &Grid) -> String {
+    let mut clues = [[0u8; SIZE]; SIZE];
+    for (row, cells) in grid.iter().enumerate() {
+        for (col, cell) in cells.iter().enumerate() {
+            if cell.is_clue() {
+                clues[row][col] = cell.value();
+            }
+        }
+    }
+    let mut transposed = clues;
+    for (row, cells) in transposed.iter_mut().enumerate() {
+        for (col, cell) in cells.iter_mut().enumerate() {
+            *cell = clues[col][row];
+        }
+    }
+
+    let orders = line_orders();
+    let mut best = [u8::MAX; SIZE * SIZE];
+    let mut board = [0u8; SIZE * SIZE];
+    for grid in [clues, transposed] {
+        for rows in &orders {
+            'cols: for cols in &orders {
+                let mut labels = [0u8; SIZE + 1];
+                let mut next = 1;
+                let mut is_smaller = false;
+                for (i, cell) in board.iter_mut().enumerate() {
+                    let value = grid[rows[i / SIZE]][cols[i % SIZE]] as usize;
+                    if value != 0 && labels[value] == 0 {
+                        labels[value] = next;
+                        next += 1;
+                    }
+                    *cell = labels[value];
+                    if !is_smaller {
+                        if *cell > best[i] {
+                            continue 'cols;
+                        }
+                        is_smaller = *cell < best[i];
+                    }
+                }
+                if is_smaller {
+                    best = board;
+                }
+            }
+        }
+    }
+    best.iter().map(|&value| (b'0' + value) as char).collect()
+}
+
+// a short name for a canonical form, the same for every disguise of a
+// puzzle: 16 hex digits of its 64 bit FNV-1a hash
+pub fn puzzle_id(canonical_form: &str) -> String {
+    let hash = canonical_form
+        .bytes()
+        .fold(0xcbf2_9ce4_8422_2325u64, |hash, byte| {
+            (hash ^ byte as u64).wrapping_mul(0x0100_0000_01b3)
+        });
+    format!("{:016x}", hash)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::Puzzle;
+
+    const BOARD: &str =
+        "530070000600195000098000060800060003400803001700020006060000280000419005000080079";
+
+    #[test]
+    fn test_disguises_share_a_form() {
+        let puzzle: Puzzle = BOARD.parse().unwrap();
+        let form = canonical_form(&puzzle.grid());
+        assert_eq!(form.len(), 81);
+        assert!(form.as_str() <= BOARD);
+
+        // transposed, with every digit renamed and the first two rows swapped
+        let digits: Vec<char> = BOARD.chars().collect();
+        let mut disguised = String::new();
+        for row in [1, 0, 2, 3, 4, 5, 6, 7, 8] {
+            for col in 0..9 {
+                disguised.push(match digits[col * 9 + row] {
+                    '0' => '0',
+                    c => (b'1' + (b'9' - c as u8)) as char,
+                });
+            }
+        }
+        let disguised: Puzzle = disguised.parse().unwrap();
+        assert_eq!(canonical_form(&disguised.grid()), form);
+        assert_eq!(puzzle_id(&form), puzzle_id(&form));
+
+        let other = Puzzle::new(crate::Difficulty::Easy);
+        assert_ne!(canonical_form(&other.grid()), form);
+    }
+
+    #[test]
+    fn test_puzzle_id() {
+        assert_eq!(puzzle_id(""), "cbf29ce484222325");
+        assert_eq!(puzzle_id("a"), "af63dc4c8601ec8c");
+    }
+}
@@ -0,0 +1,114 @@
+use rand::Rng;
+use ratatui::{
+    buffer::Buffer,
+    layout::Rect,
+    style::{Color, Style},
+    widgets::Widget,
+};
+
+// how many ticks the win animation runs before the win screen
+const CELEBRATION_TICKS: u16 = 20;
+// how many diagonals behind the wave front stay lit
+const WAVE_LENGTH: u16 = 3;
+const CONFETTI: usize = 60;
+const SYMBOLS: [&str; 5] = ["*", "•", "+", "o", "✦"];
+const COLORS: [Color; 6] = [
+    Color::Red,
+    Color::Yellow,
+    Color::Green,
+    Color::Cyan,
+    Color::Magenta,
+    Color::Blue,
+];
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+struct Piece {
+    x: u16, // across the area in thousandths, so a resize keeps the spread
+    y: i16, // rows fallen, starting above the area
+    speed: i16,
+    symbol: &'static str,
+    color: Color,
+}
+
+// a highlight sweeping diagonally across the solved board while confetti falls
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub(crate) struct Celebration {
+    ticks: u16,
+    confetti: Vec<Piece>,
+}
+
+impl Celebration {
+    pub(crate) fn new() -> Self {
+        let mut rng = rand::thread_rng();
+        let confetti = (0..CONFETTI)
+            .map(|_| Piece {
+                x: rng.gen_range(0..1000),
+                y: -rng.gen_range(0..12),
+                speed: rng.gen_range(1..=2),
+                symbol: SYMBOLS[rng.gen_range(0..SYMBOLS.len())],
+                color: COLORS[rng.gen_range(0..COLORS.len())],
+            })
+            .collect();
+
+        Celebration { ticks: 0, confetti }
+    }
+
+    pub(crate) fn tick(&mut self) {
+        self.ticks += 1;
+        for piece in self.confetti.iter_mut() {
+            piece.y += piece.speed;
+        }
+    }
+
+    pub(crate) fn is_finished(&self) -> bool {
+        self.ticks >= CELEBRATION_TICKS
+    }
+
+    // how brightly the wave lights a cell, 0 when it is not passing over it
+    pub(crate) fn wave(&self, row: usize, col: usize) -> u8 {
+        let behind = self.ticks.checked_sub((row + col) as u16);
+        match behind {
+            Some(behind) if behind < WAVE_LENGTH => (WAVE_LENGTH - behind) as u8,
+            _ => 0,
+        }
+    }
+}
+
+impl Widget for &Celebration {
+    fn render(self, area: Rect, buf: &mut Buffer) {
+        for piece in &self.confetti {
+            let Ok(y) = u16::try_from(piece.y) else {
+                continue;
+            };
+            if y >= area.height || area.width == 0 {
+                continue;
+            }
+            let x = area.x + (piece.x as u32 * area.width as u32 / 1000) as u16;
+            buf.set_string(
+                x,
+                area.y + y,
+                piece.symbol,
+                Style::default().fg(piece.color),
+            );
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_wave_sweeps_from_the_top_left() {
+        let mut celebration = Celebration::new();
+        assert!(celebration.wave(0, 0) > 0);
+        assert_eq!(celebration.wave(8, 8), 0);
+
+        for _ in 0..16 {
+            celebration.tick();
+        }
+        assert_eq!(celebration.wave(0, 0), 0);
+        assert!(celebration.wave(8, 8) > 0);
+        assert!(!celebration.is_finished());
+    }
+}
@@ -0,0 +1,44 @@
+// the terminal escape that puts text on the system clipboard (OSC 52), it
+// travels with the output so it works over ssh too
+#[cfg(feature = "tui")]
+pub(crate) fn copy_sequence(text: &str) -> String {
+    format!("\x1b]52;c;{}\x07", encode_base64(text.as_bytes()))
+}
+
+pub(crate) fn encode_base64(bytes: &[u8]) -> String {
+    const ALPHABET: &[u8; 64] = b"ABCDEFGHIJKLMNOPQRSTUVWXYZabcdefghijklmnopqrstuvwxyz0123456789+/";
+
+    let mut out = String::with_capacity(bytes.len().div_ceil(3) * 4);
+    for chunk in bytes.chunks(3) {
+        let n = chunk
+            .iter()
+            .enumerate()
+            .fold(0u32, |n, (i, &b)| n | (b as u32) << (16 - 8 * i));
+        for i in 0..4 {
+            if i <= chunk.len() {
+                out.push(ALPHABET[(n >> (18 - 6 * i) & 63) as usize] as char);
+            } else {
+                out.push('=');
+            }
+        }
+    }
+    out
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_base64_pads_partial_chunks() {
+        assert_eq!(encode_base64(b"Man"), "TWFu");
+        assert_eq!(encode_base64(b"Ma"), "TWE=");
+        assert_eq!(encode_base64(b"M"), "TQ==");
+    }
+
+    #[cfg(feature = "tui")]
+    #[test]
+    fn test_copy_sequence_wraps_the_encoded_text() {
+        assert_eq!(copy_sequence("12"), "\x1b]52;c;MTI=\x07");
+    }
+}
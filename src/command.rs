@@ -0,0 +1,351 @@
+use std::{path::PathBuf, str::FromStr};
+
+use crate::{coords, Difficulty};
+
+// the ex-style commands accepted on the ':' prompt
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Command {
+    // start a new game, at the current settings when nothing is given
+    New(Option<NewGame>),
+    Hint,
+    Reset,
+    Undo,
+    Redo,
+    Help,
+    Reveal,
+    // compare entries with the solution, clearing the wrong ones when set
+    Check(bool),
+    // fill every cell that has a single candidate left
+    Singles,
+    // pencil mark every empty cell once, or switch keeping them up to date
+    // on or off
+    Notes(Option<bool>),
+    // put a number in a cell without moving there, e.g. r4c7=5 or d5=3
+    Place { row: usize, col: usize, num: u8 },
+    // watch the logical solver work through the board
+    Solver,
+    // type a puzzle's clues into an empty board to play it
+    Edit,
+    // start a custom game from a puzzle given as 81 cells, or carry on with
+    // the game saved under a name
+    Load(String),
+    // keep the game under a name to load later
+    Save(String),
+    // pick a named save to load
+    Saves,
+    // write the board as text to a file, the player's entries included when set
+    Export { path: PathBuf, entries: bool },
+    // write when each number went in, the mistakes and the hints of the game
+    // as json
+    ExportAnalytics(PathBuf),
+    // ask for the argument that was left out in a dialog
+    Prompt(Prompt),
+    Quit,
+}
+
+// the commands whose argument can be typed into a dialog instead
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Prompt {
+    Load,
+    Save,
+    Export,
+}
+
+impl Prompt {
+    // the command the typed text is the argument of
+    pub fn name(self) -> &'static str {
+        match self {
+            Prompt::Load => "load",
+            Prompt::Save => "save",
+            Prompt::Export => "export",
+        }
+    }
+
+    pub fn title(self) -> &'static str {
+        match self {
+            Prompt::Load => " Load Game ",
+            Prompt::Save => " Save Game ",
+            Prompt::Export => " Export Board ",
+        }
+    }
+
+    pub fn button(self) -> &'static str {
+        match self {
+            Prompt::Load => "Load",
+            Prompt::Save => "Save",
+            Prompt::Export => "Export",
+        }
+    }
+
+    // what to type, above the field
+    pub fn question(self) -> &'static str {
+        match self {
+            Prompt::Load => "A save name, or a puzzle of 81 cells:",
+            Prompt::Save => "Keep the game under the name:",
+            Prompt::Export => "Write the board to the file:",
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum NewGame {
+    Difficulty(Difficulty),
+    Clues(usize),
+}
+
+// every command name with whether it takes an argument, used for completion
+const COMMANDS: [(&str, bool); 17] = [
+    ("new", true),
+    ("hint", false),
+    ("reset", false),
+    ("undo", false),
+    ("redo", false),
+    ("help", false),
+    ("reveal", false),
+    ("check", true),
+    ("singles", false),
+    ("solver", false),
+    ("edit", false),
+    ("notes", true),
+    ("load", true),
+    ("save", true),
+    ("saves", false),
+    ("export", true),
+    ("quit", false),
+];
+
+const DIFFICULTIES: [&str; 4] = ["easy", "medium", "hard", "expert"];
+
+impl FromStr for Command {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        if let Some((cell, num)) = s.split_once('=') {
+            let (row, col) = coords::parse_cell(cell)?;
+            let num = match num.trim().parse::<u8>() {
+                Ok(num @ 0..=9) => num,
+                _ => return Err(format!("Invalid number: {}", num.trim())),
+            };
+            return Ok(Command::Place { row, col, num });
+        }
+
+        let mut words = s.split_whitespace();
+        let name = words.next().unwrap_or_default();
+        let arg = words.next();
+
+        if name == "export" {
+            return match (arg, words.next(), words.next()) {
+                (Some(path), None, _) => Ok(Command::Export {
+                    path: path.into(),
+                    entries: false,
+                }),
+                (Some("entries"), Some(path), None) => Ok(Command::Export {
+                    path: path.into(),
+                    entries: true,
+                }),
+                (Some("analytics"), Some(path), None) => Ok(Command::ExportAnalytics(path.into())),
+                (None, ..) => Ok(Command::Prompt(Prompt::Export)),
+                _ => Err("export takes [entries|analytics] <file>".into()),
+            };
+        }
+
+        if words.next().is_some() {
+            return Err(format!("Too many arguments: {}", s.trim()));
+        }
+
+        let command = match name {
+            "new" => {
+                return match arg {
+                    None => Ok(Command::New(None)),
+                    Some(arg) => match arg.parse::<usize>() {
+                        Ok(clues) => Ok(Command::New(Some(NewGame::Clues(clues)))),
+                        Err(_) => Ok(Command::New(Some(NewGame::Difficulty(arg.parse()?)))),
+                    },
+                };
+            }
+            "check" => {
+                return match arg {
+                    None => Ok(Command::Check(false)),
+                    Some("clear") => Ok(Command::Check(true)),
+                    Some(arg) => Err(format!("check takes 'clear', got: {}", arg)),
+                };
+            }
+            "notes" => {
+                return match arg {
+                    None => Ok(Command::Notes(None)),
+                    Some("auto") => Ok(Command::Notes(Some(true))),
+                    Some("off") => Ok(Command::Notes(Some(false))),
+                    Some(arg) => Err(format!("notes takes 'auto' or 'off', got: {}", arg)),
+                };
+            }
+            "load" => {
+                return match arg {
+                    Some(board) => Ok(Command::Load(board.to_string())),
+                    None => Ok(Command::Prompt(Prompt::Load)),
+                };
+            }
+            "save" => {
+                return match arg {
+                    Some(name) => Ok(Command::Save(name.to_string())),
+                    None => Ok(Command::Prompt(Prompt::Save)),
+                };
+            }
+            "hint" => Command::Hint,
+            "reset" => Command::Reset,
+            "undo" => Command::Undo,
+            "redo" => Command::Redo,
+            "help" => Command::Help,
+            "reveal" => Command::Reveal,
+            "singles" => Command::Singles,
+            "solver" => Command::Solver,
+            "edit" => Command::Edit,
+            "saves" => Command::Saves,
+            "quit" | "q" => Command::Quit,
+            "" => return Err("Empty command".into()),
+            _ => return Err(format!("Unknown command: {}", name)),
+        };
+
+        match arg {
+            Some(arg) => Err(format!("{} takes no argument, got: {}", name, arg)),
+            None => Ok(command),
+        }
+    }
+}
+
+// complete the last word of a partial command line, returning the new line
+// and every candidate that matched
+pub fn complete(input: &str) -> (String, Vec<&'static str>) {
+    let (head, word, candidates): (&str, &str, Vec<(&'static str, bool)>) =
+        match input.rsplit_once(' ') {
+            None => (
+                "",
+                input,
+                COMMANDS
+                    .iter()
+                    .filter(|(name, _)| name.starts_with(input))
+                    .copied()
+                    .collect(),
+            ),
+            Some((head, word)) if head.trim() == "new" => (
+                head,
+                word,
+                DIFFICULTIES
+                    .iter()
+                    .filter(|name| name.starts_with(word))
+                    .map(|&name| (name, false))
+                    .collect(),
+            ),
+            Some(_) => return (input.to_string(), Vec::new()),
+        };
+
+    let names: Vec<&'static str> = candidates.iter().map(|(name, _)| *name).collect();
+    let separator = if head.is_empty() { "" } else { " " };
+
+    let completed = match candidates.as_slice() {
+        [] => word.to_string(),
+        [(name, takes_arg)] => {
+            let suffix = if *takes_arg { " " } else { "" };
+            format!("{}{}", name, suffix)
+        }
+        _ => common_prefix(&names).to_string(),
+    };
+
+    (format!("{}{}{}", head, separator, completed), names)
+}
+
+fn common_prefix<'a>(names: &[&'a str]) -> &'a str {
+    let first = names[0];
+    let len = names.iter().skip(1).fold(first.len(), |len, name| {
+        first
+            .bytes()
+            .zip(name.bytes())
+            .take(len)
+            .take_while(|(a, b)| a == b)
+            .count()
+    });
+    &first[..len]
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_commands() {
+        assert_eq!("quit".parse(), Ok(Command::Quit));
+        assert_eq!("q".parse(), Ok(Command::Quit));
+        assert_eq!(" hint ".parse(), Ok(Command::Hint));
+        assert_eq!("new".parse(), Ok(Command::New(None)));
+        assert_eq!(
+            "new hard".parse(),
+            Ok(Command::New(Some(NewGame::Difficulty(Difficulty::Hard))))
+        );
+        assert_eq!("new 40".parse(), Ok(Command::New(Some(NewGame::Clues(40)))));
+        assert_eq!("check".parse(), Ok(Command::Check(false)));
+        assert_eq!("check clear".parse(), Ok(Command::Check(true)));
+        assert_eq!("notes".parse(), Ok(Command::Notes(None)));
+        assert_eq!("notes auto".parse(), Ok(Command::Notes(Some(true))));
+        assert_eq!("load 1..2".parse(), Ok(Command::Load("1..2".to_string())));
+        assert_eq!(
+            "save weekend".parse(),
+            Ok(Command::Save("weekend".to_string()))
+        );
+        assert_eq!("saves".parse(), Ok(Command::Saves));
+        assert_eq!("edit".parse(), Ok(Command::Edit));
+        assert_eq!("load".parse(), Ok(Command::Prompt(Prompt::Load)));
+        assert_eq!("save".parse(), Ok(Command::Prompt(Prompt::Save)));
+        assert_eq!("export".parse(), Ok(Command::Prompt(Prompt::Export)));
+        assert_eq!(
+            "export analytics game.json".parse(),
+            Ok(Command::ExportAnalytics("game.json".into()))
+        );
+        assert_eq!(
+            "r4c7=5".parse(),
+            Ok(Command::Place {
+                row: 3,
+                col: 6,
+                num: 5
+            })
+        );
+        assert_eq!(
+            "d5 = 0".parse(),
+            Ok(Command::Place {
+                row: 3,
+                col: 4,
+                num: 0
+            })
+        );
+    }
+
+    #[test]
+    fn test_parse_errors() {
+        assert!("".parse::<Command>().is_err());
+        assert!("fly".parse::<Command>().is_err());
+        assert!("hint 3".parse::<Command>().is_err());
+        assert!("new impossible".parse::<Command>().is_err());
+        assert!("new hard now".parse::<Command>().is_err());
+        assert!("check all".parse::<Command>().is_err());
+        assert!("export all board.txt".parse::<Command>().is_err());
+        assert!("r4c7=10".parse::<Command>().is_err());
+        assert!("z9=1".parse::<Command>().is_err());
+    }
+
+    #[test]
+    fn test_complete_command_name() {
+        assert_eq!(complete("hi"), ("hint".into(), vec!["hint"]));
+        assert_eq!(complete("ne"), ("new ".into(), vec!["new"]));
+        assert_eq!(complete("no"), ("notes ".into(), vec!["notes"]));
+        assert_eq!(
+            complete("re"),
+            ("re".into(), vec!["reset", "redo", "reveal"])
+        );
+        assert_eq!(complete("x").1, Vec::<&str>::new());
+    }
+
+    #[test]
+    fn test_complete_difficulty() {
+        assert_eq!(complete("new h"), ("new hard".into(), vec!["hard"]));
+        assert_eq!(complete("new e").1, vec!["easy", "expert"]);
+        assert_eq!(complete("hint x"), ("hint x".into(), vec![]));
+    }
+}
@@ -0,0 +1,277 @@
+use directories::ProjectDirs;
+use serde::{de, Deserialize, Deserializer};
+use std::{
+    fmt, fs, io,
+    path::{Path, PathBuf},
+    str::FromStr,
+};
+
+use crate::{Action, Difficulty, KeyBinding, KeymapPreset, Labels, Lang, Theme};
+
+const CONFIG_FILE: &str = "config.toml";
+
+// where rsudoku reads its settings from, e.g. ~/.config/rsudoku/config.toml
+pub fn config_path() -> Option<PathBuf> {
+    ProjectDirs::from("", "", "rsudoku").map(|dirs| dirs.config_dir().join(CONFIG_FILE))
+}
+
+// settings read from the config file, None for what it leaves out so the
+// command line and the built in defaults can fill in the rest
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct Config {
+    pub theme: Option<Theme>,
+    pub keymap: Option<KeymapPreset>,
+    pub bindings: Vec<(Action, KeyBinding)>, // applied over the preset, in order
+    pub difficulty: Option<Difficulty>,      // preselected on the menu
+    pub hints: Option<usize>,
+    pub limited_mistakes: Option<bool>,
+    pub time_attack: Option<bool>,
+    pub bell: Option<bool>,
+    pub hide_timer: Option<bool>,
+    pub compact: Option<bool>,
+    pub labels: Option<Labels>,
+    pub lang: Option<Lang>,
+    pub autosave: Option<u64>, // seconds, 0 for never
+    pub auto_notes: Option<bool>,
+    pub strict: Option<bool>,
+    pub sync_url: Option<String>, // used by builds with the sync feature
+    pub source_url: Option<String>, // used by builds with the net feature
+}
+
+// the file as toml, a table for each section
+#[derive(Debug, Default, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+struct File {
+    theme: ThemeSection,
+    keymap: KeymapSection,
+    game: GameSection,
+    behavior: BehaviorSection,
+    sync: UrlSection,
+    source: UrlSection,
+}
+
+#[derive(Debug, Default, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+struct ThemeSection {
+    #[serde(deserialize_with = "from_text")]
+    name: Option<Theme>,
+}
+
+// besides the preset, `action = "key"` lines rebind single actions
+#[derive(Debug, Default, Deserialize)]
+#[serde(default)]
+struct KeymapSection {
+    #[serde(deserialize_with = "from_text")]
+    preset: Option<KeymapPreset>,
+    #[serde(flatten)]
+    bindings: Bindings,
+}
+
+#[derive(Debug, Default)]
+struct Bindings(Vec<(Action, KeyBinding)>);
+
+impl<'de> Deserialize<'de> for Bindings {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        struct Visitor;
+
+        impl<'de> de::Visitor<'de> for Visitor {
+            type Value = Bindings;
+
+            fn expecting(&self, f: &mut fmt::Formatter) -> fmt::Result {
+                f.write_str("action = \"key\" lines")
+            }
+
+            fn visit_map<A: de::MapAccess<'de>>(self, mut map: A) -> Result<Bindings, A::Error> {
+                let mut bindings = Vec::new();
+                while let Some((action, key)) = map.next_entry::<String, String>()? {
+                    let action = action.parse().map_err(de::Error::custom)?;
+                    let key = key.parse().map_err(de::Error::custom)?;
+                    bindings.push((action, key));
+                }
+                Ok(Bindings(bindings))
+            }
+        }
+
+        deserializer.deserialize_map(Visitor)
+    }
+}
+
+#[derive(Debug, Default, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+struct GameSection {
+    #[serde(deserialize_with = "from_text")]
+    difficulty: Option<Difficulty>,
+}
+
+#[derive(Debug, Default, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+struct BehaviorSection {
+    hints: Option<usize>,
+    limited_mistakes: Option<bool>,
+    time_attack: Option<bool>,
+    bell: Option<bool>,
+    hide_timer: Option<bool>,
+    compact: Option<bool>,
+    #[serde(deserialize_with = "from_text")]
+    labels: Option<Labels>,
+    #[serde(deserialize_with = "from_text")]
+    lang: Option<Lang>,
+    autosave: Option<u64>,
+    auto_notes: Option<bool>,
+    strict: Option<bool>,
+}
+
+#[derive(Debug, Default, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+struct UrlSection {
+    url: Option<String>,
+}
+
+// a setting written as text, like a theme's name, read through its FromStr
+fn from_text<'de, D, T>(deserializer: D) -> Result<Option<T>, D::Error>
+where
+    D: Deserializer<'de>,
+    T: FromStr<Err = String>,
+{
+    let text = String::deserialize(deserializer)?;
+    text.parse().map(Some).map_err(de::Error::custom)
+}
+
+impl From<File> for Config {
+    fn from(file: File) -> Self {
+        let behavior = file.behavior;
+        Config {
+            theme: file.theme.name,
+            keymap: file.keymap.preset,
+            bindings: file.keymap.bindings.0,
+            difficulty: file.game.difficulty,
+            hints: behavior.hints,
+            limited_mistakes: behavior.limited_mistakes,
+            time_attack: behavior.time_attack,
+            bell: behavior.bell,
+            hide_timer: behavior.hide_timer,
+            compact: behavior.compact,
+            labels: behavior.labels,
+            lang: behavior.lang,
+            autosave: behavior.autosave,
+            auto_notes: behavior.auto_notes,
+            strict: behavior.strict,
+            sync_url: file.sync.url,
+            source_url: file.source.url,
+        }
+    }
+}
+
+impl Config {
+    // a missing file is an error here, reading the default location treats
+    // it as an empty config
+    pub fn load(path: &Path) -> io::Result<Config> {
+        let text = fs::read_to_string(path)?;
+        text.parse()
+            .map_err(|err| io::Error::new(io::ErrorKind::InvalidData, err))
+    }
+}
+
+// toml's own errors quote the line with a caret under it, here the line's
+// number is enough
+impl FromStr for Config {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match toml::from_str::<File>(s) {
+            Ok(file) => Ok(file.into()),
+            Err(err) => Err(match err.span() {
+                Some(span) => {
+                    let line = s[..span.start].matches('\n').count() + 1;
+                    format!("line {}: {}", line, err.message())
+                }
+                None => err.message().to_string(),
+            }),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_sections_and_values() {
+        let config: Config = "\
+            # played on the laptop\n\
+            [keymap]\n\
+            preset = \"vim\"\n\
+            \n\
+            [game]\n\
+            difficulty = \"hard\"\n\
+            \n\
+            [behavior]\n\
+            hints = 5 # a few more\n\
+            bell = false\n\
+            labels = \"letters\"\n"
+            .parse()
+            .unwrap();
+        assert_eq!(
+            config,
+            Config {
+                keymap: Some(KeymapPreset::Vim),
+                difficulty: Some(Difficulty::Hard),
+                hints: Some(5),
+                bell: Some(false),
+                labels: Some(Labels::Letters),
+                ..Config::default()
+            }
+        );
+        assert_eq!("".parse(), Ok(Config::default()));
+    }
+
+    #[test]
+    fn test_parse_errors_name_the_line() {
+        let err = |s: &str| s.parse::<Config>().unwrap_err();
+        assert_eq!(
+            err("[behavior]\nbell = 1"),
+            "line 2: invalid type: integer `1`, expected a boolean"
+        );
+        assert!(err("[behavior]\nbel = true").starts_with("line 2: unknown field `bel`"));
+        assert!(err("hints").starts_with("line 1: "));
+        assert_eq!(
+            err("[keymap]\npreset = \"emacs\""),
+            "line 2: Invalid keymap: emacs"
+        );
+    }
+
+    #[test]
+    fn test_keymap_overrides() {
+        let config: Config = "[keymap]\npreset = 'vim'\nundo = 'z'\nredo = 'Ctrl+y'\n"
+            .parse()
+            .unwrap();
+        assert_eq!(config.keymap, Some(KeymapPreset::Vim));
+        assert_eq!(
+            config.bindings,
+            [
+                (Action::Undo, KeyBinding::char('z')),
+                (Action::Redo, "Ctrl+y".parse().unwrap()),
+            ]
+        );
+        assert!("[keymap]\nundo_all = 'z'"
+            .parse::<Config>()
+            .unwrap_err()
+            .contains("Invalid action: undo_all"));
+    }
+
+    #[test]
+    fn test_any_toml_spelling() {
+        let config: Config = "\
+            behavior = { hints = 2, strict = true }\n\
+            [theme]\n\
+            name = 'high-contrast'\n\
+            [sync]\n\
+            url = \"http://nas.local/dav\\u0021\"\n"
+            .parse()
+            .unwrap();
+        assert_eq!(config.hints, Some(2));
+        assert_eq!(config.strict, Some(true));
+        assert_eq!(config.theme, Some("high-contrast".parse().unwrap()));
+        assert_eq!(config.sync_url.as_deref(), Some("http://nas.local/dav!"));
+    }
+}
@@ -0,0 +1,76 @@
+use std::str::FromStr;
+
+// how rows are named in the labels around the grid, columns are always 1-9
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Labels {
+    Numbers,
+    Letters,
+}
+
+impl Labels {
+    pub fn row(&self, row: usize) -> char {
+        match self {
+            Labels::Numbers => (b'1' + row as u8) as char,
+            Labels::Letters => (b'A' + row as u8) as char,
+        }
+    }
+
+    pub fn col(&self, col: usize) -> char {
+        (b'1' + col as u8) as char
+    }
+}
+
+// a cell as written by players, either r4c7 or d5 (row D, column 5), both
+// case insensitive and 1 based; returns the 0 based (row, col)
+pub fn parse_cell(s: &str) -> Result<(usize, usize), String> {
+    let lower = s.trim().to_lowercase();
+    let invalid = || format!("Invalid cell: {}", s.trim());
+    let digit = |c: char| match c {
+        '1'..='9' => Ok(c as usize - '1' as usize),
+        _ => Err(invalid()),
+    };
+
+    let chars: Vec<char> = lower.chars().collect();
+    match chars.as_slice() {
+        ['r', row, 'c', col] => Ok((digit(*row)?, digit(*col)?)),
+        [row @ 'a'..='i', col] => Ok((*row as usize - 'a' as usize, digit(*col)?)),
+        _ => Err(invalid()),
+    }
+}
+
+impl FromStr for Labels {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.to_lowercase().as_str() {
+            "numbers" | "1-9" => Ok(Labels::Numbers),
+            "letters" | "a-i" => Ok(Labels::Letters),
+            _ => Err(format!("Invalid labels: {}", s)),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_labels() {
+        assert_eq!(Labels::Numbers.row(0), '1');
+        assert_eq!(Labels::Letters.row(8), 'I');
+        assert_eq!(Labels::Letters.col(3), '4');
+        assert_eq!("A-I".parse(), Ok(Labels::Letters));
+        assert!("roman".parse::<Labels>().is_err());
+    }
+
+    #[test]
+    fn test_parse_cell() {
+        assert_eq!(parse_cell("r4c7"), Ok((3, 6)));
+        assert_eq!(parse_cell("R1C1"), Ok((0, 0)));
+        assert_eq!(parse_cell("d5"), Ok((3, 4)));
+        assert_eq!(parse_cell("I9"), Ok((8, 8)));
+        assert!(parse_cell("r0c1").is_err());
+        assert!(parse_cell("j1").is_err());
+        assert!(parse_cell("r4").is_err());
+    }
+}
@@ -0,0 +1,78 @@
+use alloc::{format, string::String};
+use rand::{rngs::StdRng, SeedableRng};
+
+use crate::{Difficulty, Puzzle};
+
+// the puzzle of a day, counted in days since the unix epoch in UTC like
+// `today()`, the same everywhere since its seed comes from nothing else
+pub fn daily_puzzle(day: u64, difficulty: Difficulty) -> Puzzle {
+    let seed = (day << 8) | difficulty as u64;
+    Puzzle::with_rng(difficulty as usize, &mut StdRng::seed_from_u64(seed))
+}
+
+// a day since the unix epoch as yyyy-mm-dd
+pub fn format_day(day: u64) -> String {
+    let (y, m, d) = civil_from_days(day);
+    format!("{:04}-{:02}-{:02}", y, m, d)
+}
+
+// a day since the unix epoch as year, month and day of the month, after
+// Howard Hinnant's civil_from_days
+pub(crate) fn civil_from_days(day: u64) -> (u64, u64, u64) {
+    let z = day + 719_468;
+    let era = z / 146_097;
+    let doe = z % 146_097;
+    let yoe = (doe - doe / 1460 + doe / 36_524 - doe / 146_096) / 365;
+    let doy = doe - (365 * yoe + yoe / 4 - yoe / 100);
+    let mp = (5 * doy + 2) / 153;
+    let d = doy - (153 * mp + 2) / 5 + 1;
+    let m = if mp < 10 { mp + 3 } else { mp - 9 };
+    let y = yoe + era * 400 + (m <= 2) as u64;
+    (y, m, d)
+}
+
+// the day since the unix epoch of a date from 1970 on, the inverse of
+// `civil_from_days`
+#[cfg(any(feature = "tui", test))]
+pub(crate) fn days_from_civil(y: u64, m: u64, d: u64) -> u64 {
+    let y = y - (m <= 2) as u64;
+    let era = y / 400;
+    let yoe = y - era * 400;
+    let doy = (153 * (if m > 2 { m - 3 } else { m + 9 }) + 2) / 5 + d - 1;
+    let doe = yoe * 365 + yoe / 4 - yoe / 100 + doy;
+    era * 146_097 + doe - 719_468
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_format_day() {
+        assert_eq!(format_day(0), "1970-01-01");
+        assert_eq!(format_day(59), "1970-03-01");
+        assert_eq!(format_day(11_016), "2000-02-29");
+        assert_eq!(format_day(20_740), "2026-10-14");
+    }
+
+    #[test]
+    fn test_days_from_civil_inverts_civil_from_days() {
+        for day in [0, 59, 11_016, 20_740, 20_757] {
+            let (y, m, d) = civil_from_days(day);
+            assert_eq!(days_from_civil(y, m, d), day);
+        }
+        assert_eq!(days_from_civil(2026, 10, 1), 20_727);
+    }
+
+    #[test]
+    fn test_the_day_and_difficulty_pick_the_puzzle() {
+        let puzzle = daily_puzzle(20_740, Difficulty::Hard);
+        assert_eq!(puzzle, daily_puzzle(20_740, Difficulty::Hard));
+        assert_ne!(puzzle, daily_puzzle(20_741, Difficulty::Hard));
+        assert_eq!(puzzle.clues(), Difficulty::Hard as usize);
+        assert_ne!(
+            daily_puzzle(20_740, Difficulty::Easy).to_solved(),
+            puzzle.to_solved()
+        );
+    }
+}
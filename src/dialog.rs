@@ -0,0 +1,163 @@
+use ratatui::{
+    buffer::Buffer,
+    layout::{Constraint, Flex, Layout, Rect},
+    style::Stylize,
+    symbols::border,
+    text::{Line, Span, Text},
+    widgets::{Block, Clear, Padding, Paragraph, Widget},
+};
+
+const MIN_WIDTH: u16 = 30;
+
+// a centered popup with a title, body, an optional input field and a button
+// row, shared by every confirmation, menu and prompt
+#[derive(Debug, Clone, Default)]
+pub(crate) struct Dialog<'a> {
+    title: Line<'a>,
+    body: Text<'a>,
+    input: Option<&'a str>, // the text typed so far, shown with a cursor
+    buttons: Vec<(&'a str, &'a str)>, // (label, key)
+}
+
+impl<'a> Dialog<'a> {
+    pub(crate) fn new(title: impl Into<Line<'a>>) -> Self {
+        Dialog {
+            title: title.into(),
+            ..Dialog::default()
+        }
+    }
+
+    pub(crate) fn body(mut self, body: impl Into<Text<'a>>) -> Self {
+        self.body = body.into();
+        self
+    }
+
+    pub(crate) fn input(mut self, text: &'a str) -> Self {
+        self.input = Some(text);
+        self
+    }
+
+    pub(crate) fn button(mut self, label: &'a str, key: &'a str) -> Self {
+        self.buttons.push((label, key));
+        self
+    }
+
+    fn button_row(&self) -> Line<'a> {
+        let mut spans: Vec<Span> = Vec::new();
+        for (i, &(label, key)) in self.buttons.iter().enumerate() {
+            if i > 0 {
+                spans.push("  ".into());
+            }
+
+            spans.push(format!("[ {} ", label).into());
+            spans.push(format!("<{}> ]", key).blue().bold());
+        }
+        Line::from(spans)
+    }
+
+    // the typed text with the cursor after it, padded to fill `width` so the
+    // field reads as a box to type in
+    fn input_line(&self, width: usize) -> Option<Line<'a>> {
+        let text = format!("{}█", self.input?);
+        Some(Line::from(format!("{:<width$}", text, width = width)).underlined())
+    }
+
+    // the size the dialog needs to show everything, before clamping to the area
+    fn size(&self) -> (u16, u16) {
+        let content_width = [
+            self.title.width(),
+            self.body.width(),
+            self.input.map_or(0, |text| text.chars().count() + 1),
+            self.button_row().width(),
+        ]
+        .into_iter()
+        .max()
+        .unwrap_or_default() as u16;
+
+        let mut content_height = self.body.height() as u16;
+        if self.input.is_some() {
+            content_height += if self.body.lines.is_empty() { 1 } else { 2 };
+        }
+        if !self.buttons.is_empty() {
+            content_height += 2;
+        }
+
+        // borders plus a column / line of padding on each side
+        ((content_width + 4).max(MIN_WIDTH), content_height + 4)
+    }
+}
+
+impl Widget for Dialog<'_> {
+    fn render(self, area: Rect, buf: &mut Buffer) {
+        let (width, height) = self.size();
+        let [area] = Layout::horizontal([Constraint::Length(width)])
+            .flex(Flex::Center)
+            .areas(area);
+        let [area] = Layout::vertical([Constraint::Length(height)])
+            .flex(Flex::Center)
+            .areas(area);
+
+        let block = Block::bordered()
+            .title(self.title.clone().bold().centered())
+            .border_set(border::THICK)
+            .padding(Padding::uniform(1));
+        let inner_area = block.inner(area);
+
+        Clear.render(area, buf);
+        block.render(area, buf);
+
+        let mut lines = self.body.lines.clone();
+        if let Some(field) = self.input_line(inner_area.width as usize) {
+            if !lines.is_empty() {
+                lines.push(Line::default());
+            }
+            lines.push(field);
+        }
+        if !self.buttons.is_empty() {
+            lines.push(Line::default());
+            lines.push(self.button_row());
+        }
+
+        Paragraph::new(lines).centered().render(inner_area, buf);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_size_fits_body_and_buttons() {
+        let dialog = Dialog::new(" Title ").body(vec![
+            Line::from("a line that is quite a bit wider than thirty"),
+            Line::from("short"),
+        ]);
+        assert_eq!(dialog.size(), (48, 6));
+
+        let dialog = dialog.button("Ok", "Enter");
+        assert_eq!(dialog.size(), (48, 8));
+    }
+
+    #[test]
+    fn test_input_field_takes_a_line_and_its_text() {
+        let dialog = Dialog::new(" Save ")
+            .body("Save the game as")
+            .input("a-name-long-enough-to-widen-the-dialog")
+            .button("Save", "Enter");
+        assert_eq!(dialog.size(), (43, 9));
+
+        let mut buf = Buffer::empty(Rect::new(0, 0, 50, 12));
+        dialog.render(buf.area, &mut buf);
+        let rows: Vec<String> = (0..12)
+            .map(|y| (0..50).map(|x| buf[(x, y)].symbol()).collect())
+            .collect();
+        assert!(rows
+            .iter()
+            .any(|row| row.contains("a-name-long-enough-to-widen-the-dialog█")));
+    }
+
+    #[test]
+    fn test_size_has_a_minimum_width() {
+        assert_eq!(Dialog::new("x").size(), (MIN_WIDTH, 4));
+    }
+}
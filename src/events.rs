@@ -0,0 +1,346 @@
+use std::{collections::VecDeque, io, time::Duration};
+
+use crossterm::event::{self, Event, KeyCode, KeyEvent, KeyModifiers};
+#[cfg(feature = "async")]
+use futures_util::{Stream, StreamExt};
+
+// where the game reads its input from, the terminal or a script
+pub trait EventSource {
+    // wait up to `timeout` for an event, true when one is ready to read
+    fn poll(&mut self, timeout: Duration) -> io::Result<bool>;
+    fn read(&mut self) -> io::Result<Event>;
+}
+
+// the real terminal's events through crossterm
+#[derive(Debug, Clone, Copy, Default)]
+pub struct TerminalEvents;
+
+impl EventSource for TerminalEvents {
+    fn poll(&mut self, timeout: Duration) -> io::Result<bool> {
+        event::poll(timeout)
+    }
+
+    fn read(&mut self) -> io::Result<Event> {
+        event::read()
+    }
+}
+
+// a fixed list of events played back in order, never waiting for more
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct ScriptedEvents {
+    events: VecDeque<Event>,
+}
+
+impl ScriptedEvents {
+    // one key press per character, with '\n' for Enter and '\x1b' for Esc
+    pub fn keys(keys: &str) -> Self {
+        keys.chars()
+            .map(|c| {
+                let code = match c {
+                    '\n' => KeyCode::Enter,
+                    '\x1b' => KeyCode::Esc,
+                    '\t' => KeyCode::Tab,
+                    '\x08' => KeyCode::Backspace,
+                    c => KeyCode::Char(c),
+                };
+                // crossterm reports capitals with shift held
+                let modifiers = if c.is_ascii_uppercase() {
+                    KeyModifiers::SHIFT
+                } else {
+                    KeyModifiers::NONE
+                };
+                Event::Key(KeyEvent::new(code, modifiers))
+            })
+            .collect()
+    }
+
+    pub fn push(&mut self, event: Event) {
+        self.events.push_back(event);
+    }
+}
+
+impl FromIterator<Event> for ScriptedEvents {
+    fn from_iter<I: IntoIterator<Item = Event>>(events: I) -> Self {
+        ScriptedEvents {
+            events: events.into_iter().collect(),
+        }
+    }
+}
+
+impl EventSource for ScriptedEvents {
+    fn poll(&mut self, _timeout: Duration) -> io::Result<bool> {
+        Ok(!self.events.is_empty())
+    }
+
+    fn read(&mut self) -> io::Result<Event> {
+        self.events
+            .pop_front()
+            .ok_or_else(|| io::Error::new(io::ErrorKind::UnexpectedEof, "no events left"))
+    }
+}
+
+// events from an async stream, crossterm's EventStream for the terminal,
+// read on a tokio runtime of its own. Tasks spawned through `handle` run on
+// that runtime whenever the game loop waits for input, so background work
+// doesn't hold the loop up.
+#[cfg(feature = "async")]
+#[derive(Debug)]
+pub struct StreamEvents<S> {
+    runtime: tokio::runtime::Runtime,
+    stream: S,
+    next: Option<Event>, // taken off the stream by poll, for read
+}
+
+#[cfg(feature = "async")]
+impl StreamEvents<event::EventStream> {
+    pub fn terminal() -> io::Result<Self> {
+        Self::new(event::EventStream::new())
+    }
+}
+
+#[cfg(feature = "async")]
+impl<S: Stream<Item = io::Result<Event>> + Unpin> StreamEvents<S> {
+    pub fn new(stream: S) -> io::Result<Self> {
+        let runtime = tokio::runtime::Builder::new_current_thread()
+            .enable_time()
+            .build()?;
+        Ok(StreamEvents {
+            runtime,
+            stream,
+            next: None,
+        })
+    }
+
+    // where to spawn work that runs alongside the game
+    pub fn handle(&self) -> &tokio::runtime::Handle {
+        self.runtime.handle()
+    }
+}
+
+#[cfg(feature = "async")]
+impl<S: Stream<Item = io::Result<Event>> + Unpin> EventSource for StreamEvents<S> {
+    fn poll(&mut self, timeout: Duration) -> io::Result<bool> {
+        if self.next.is_some() {
+            return Ok(true);
+        }
+        let next = self.stream.next();
+        // the stream is polled once before the timeout is checked, so a zero
+        // timeout still picks up an event that is ready
+        match self
+            .runtime
+            .block_on(async { tokio::time::timeout(timeout, next).await })
+        {
+            Ok(Some(event)) => {
+                self.next = Some(event?);
+                Ok(true)
+            }
+            Ok(None) | Err(_) => Ok(false),
+        }
+    }
+
+    fn read(&mut self) -> io::Result<Event> {
+        if let Some(event) = self.next.take() {
+            return Ok(event);
+        }
+        self.runtime
+            .block_on(self.stream.next())
+            .unwrap_or_else(|| {
+                Err(io::Error::new(
+                    io::ErrorKind::UnexpectedEof,
+                    "no events left",
+                ))
+            })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{App, Difficulty, Puzzle};
+
+    #[test]
+    fn test_headless_run_plays_the_script() {
+        let mut app = App::new(Difficulty::Easy);
+        let screen = app
+            .run_headless(&mut ScriptedEvents::keys(""), 80, 30)
+            .unwrap();
+        assert_eq!(screen.split('\n').count(), 30);
+        assert!(!screen.contains("Paused"));
+
+        let screen = app
+            .run_headless(&mut ScriptedEvents::keys("p"), 80, 30)
+            .unwrap();
+        assert!(screen.contains("Paused"));
+
+        let mut events = ScriptedEvents::keys("pq5");
+        app.run_headless(&mut events, 80, 30).unwrap();
+        // quitting stops the run before the last key
+        assert!(events.poll(Duration::ZERO).unwrap());
+    }
+
+    #[test]
+    fn test_commands_without_their_argument_ask_for_it() {
+        let mut app = App::new(Difficulty::Easy);
+        let screen = app
+            .run_headless(&mut ScriptedEvents::keys(":save\nweekend"), 80, 30)
+            .unwrap();
+        assert!(screen.contains("Save Game"));
+        assert!(screen.contains("weekend█"));
+
+        // Esc drops the prompt without saving
+        let screen = app
+            .run_headless(&mut ScriptedEvents::keys("\x1b"), 80, 30)
+            .unwrap();
+        assert!(!screen.contains("Save Game"));
+    }
+
+    #[test]
+    fn test_a_rejected_note_keeps_auto_notes_on() {
+        let puzzle: Puzzle =
+            "530070000600195000098000060800060003400803001700020006060000280000419005000080079"
+                .parse()
+                .unwrap();
+        let mut app = App::new(Difficulty::Easy)
+            .with_auto_notes(true)
+            .with_puzzle(puzzle);
+        // the top left cell, where the game starts, is the clue 5
+        let mut events = ScriptedEvents::default();
+        events.push(Event::Key(KeyEvent::new(
+            KeyCode::Char('1'),
+            KeyModifiers::ALT,
+        )));
+        let screen = app.run_headless(&mut events, 80, 30).unwrap();
+        assert!(!screen.contains("Notes are no longer updated"));
+    }
+
+    #[test]
+    fn test_the_editor_plays_only_a_proper_puzzle() {
+        let mut app = App::new(Difficulty::Easy);
+        let screen = app
+            .run_headless(&mut ScriptedEvents::keys(":edit\n5"), 80, 30)
+            .unwrap();
+        assert!(screen.contains("Editor"));
+        assert!(screen.contains(" 1 clue "));
+
+        let screen = app
+            .run_headless(&mut ScriptedEvents::keys("\n"), 80, 30)
+            .unwrap();
+        assert!(screen.contains("more than one solution"));
+        assert!(screen.contains("Editor"));
+
+        let screen = app
+            .run_headless(&mut ScriptedEvents::keys("\x1b"), 80, 30)
+            .unwrap();
+        assert!(!screen.contains("Editor"));
+    }
+
+    #[cfg(feature = "async")]
+    #[test]
+    fn test_stream_events_run_spawned_tasks_while_waiting() {
+        use std::sync::{
+            atomic::{AtomicBool, Ordering},
+            Arc,
+        };
+
+        let keys = ScriptedEvents::keys("p").events.into_iter().map(Ok);
+        let mut events = StreamEvents::new(futures_util::stream::iter(keys)).unwrap();
+        let screen = App::new(Difficulty::Easy)
+            .run_headless(&mut events, 80, 30)
+            .unwrap();
+        assert!(screen.contains("Paused"));
+
+        // an empty stream that never ends leaves the wait to the spawned task
+        let mut events = StreamEvents::new(futures_util::stream::pending()).unwrap();
+        let done = Arc::new(AtomicBool::new(false));
+        let flag = Arc::clone(&done);
+        events
+            .handle()
+            .spawn(async move { flag.store(true, Ordering::SeqCst) });
+        assert!(!events.poll(Duration::from_millis(10)).unwrap());
+        assert!(done.load(Ordering::SeqCst));
+    }
+
+    #[test]
+    fn test_load_takes_81_cells_as_a_board() {
+        let board =
+            "530070000600195000098000060800060003400803001700020006060000280000419005000080079";
+        let mut app = App::new(Difficulty::Easy);
+        let screen = app
+            .run_headless(
+                &mut ScriptedEvents::keys(&format!(":load {}\n", board)),
+                80,
+                30,
+            )
+            .unwrap();
+        assert!(screen.contains("Loaded a puzzle with 30 clues"));
+
+        // its first row alone has many answers
+        let screen = app
+            .run_headless(
+                &mut ScriptedEvents::keys(&format!(":load {:0<81}\n", &board[..9])),
+                80,
+                30,
+            )
+            .unwrap();
+        assert!(screen.contains("The puzzle has more than one solution"));
+    }
+
+    #[test]
+    fn test_load_takes_an_all_digit_name_as_a_save() {
+        let dir = std::env::temp_dir().join(format!("rsudoku-digit-save-{}", std::process::id()));
+        let mut app = App::new(Difficulty::Easy).with_data_dir(dir.clone());
+        let screen = app
+            .run_headless(&mut ScriptedEvents::keys(":save 2024\n"), 80, 30)
+            .unwrap();
+        assert!(screen.contains("saved the game as 2024"));
+        let screen = app
+            .run_headless(&mut ScriptedEvents::keys(":load 2024\n"), 80, 30)
+            .unwrap();
+        assert!(screen.contains("Loaded 2024"));
+        std::fs::remove_dir_all(&dir).unwrap();
+    }
+
+    #[test]
+    fn test_quitting_keeps_the_games_in_every_tab() {
+        let dir = std::env::temp_dir().join(format!("rsudoku-tabs-{}", std::process::id()));
+        let mut app = App::new(Difficulty::Easy).with_data_dir(dir.clone());
+        let mut events = ScriptedEvents::default();
+        events.push(Event::Key(KeyEvent::new(
+            KeyCode::Char('t'),
+            KeyModifiers::CONTROL,
+        )));
+        events.push(Event::Key(KeyEvent::new(
+            KeyCode::Char('q'),
+            KeyModifiers::NONE,
+        )));
+        app.run_headless(&mut events, 80, 30).unwrap();
+
+        let screen = App::new(Difficulty::Easy)
+            .with_data_dir(dir.clone())
+            .with_saved_game()
+            .run_headless(&mut ScriptedEvents::default(), 80, 30)
+            .unwrap();
+        assert!(screen.contains("Tab 2/2"));
+        std::fs::remove_dir_all(&dir).unwrap();
+    }
+
+    #[test]
+    fn test_w_in_either_case_replays_the_solve() {
+        let board =
+            "530070000600195000098000060800060003400803001700020006060000280000419005000080079";
+        let solved = board.parse::<Puzzle>().unwrap().to_solved().to_board(true);
+        // everything but the last cell given, which is a 9
+        let puzzle: Puzzle = format!("{}0", &solved[..80]).parse().unwrap();
+        for key in ["w", "W"] {
+            let mut app = App::new(Difficulty::Easy).with_puzzle(puzzle.clone());
+            let screen = app
+                .run_headless(
+                    &mut ScriptedEvents::keys(&format!(":i9=9\n {}", key)),
+                    80,
+                    30,
+                )
+                .unwrap();
+            assert!(screen.contains("replay"), "{}", key);
+        }
+    }
+}
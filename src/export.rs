@@ -0,0 +1,574 @@
+use std::{fmt::Write, path::Path, str::FromStr};
+
+use crate::{puzzle::candidates, Cell, Puzzle};
+
+const BORDER: &str = "+-------+-------+-------+";
+// svg sizes in pixels
+const SVG_CELL: usize = 50;
+const SVG_MARGIN: usize = 10;
+
+// the ways a board can be written out
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum ExportFormat {
+    // 81 digits on one line, 0 for an empty cell
+    Line,
+    #[default]
+    Text,
+    Svg,
+    // a tikz picture for latex documents
+    Tex,
+    Html,
+    #[cfg(feature = "image")]
+    Png,
+}
+
+impl ExportFormat {
+    // the format a file name asks for, text unless its extension says otherwise
+    pub fn from_path(path: &Path) -> ExportFormat {
+        path.extension()
+            .and_then(|extension| extension.to_str()?.parse().ok())
+            .unwrap_or_default()
+    }
+
+    // the file's bytes, text for every format but png
+    pub fn write(self, puzzle: &Puzzle, entries: bool) -> Vec<u8> {
+        match self {
+            ExportFormat::Line => (puzzle.to_board(entries) + "\n").into_bytes(),
+            ExportFormat::Text => TextGrid::new().render(puzzle, entries).into_bytes(),
+            ExportFormat::Svg => SvgGrid::new().render(puzzle, entries).into_bytes(),
+            ExportFormat::Tex => tikz(puzzle, entries).into_bytes(),
+            ExportFormat::Html => HtmlGrid::new().render(puzzle, entries).into_bytes(),
+            #[cfg(feature = "image")]
+            ExportFormat::Png => PngGrid::new().render(puzzle, entries),
+        }
+    }
+}
+
+impl FromStr for ExportFormat {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.to_lowercase().as_str() {
+            "line" => Ok(ExportFormat::Line),
+            "text" | "txt" => Ok(ExportFormat::Text),
+            "svg" => Ok(ExportFormat::Svg),
+            "tex" | "latex" | "tikz" => Ok(ExportFormat::Tex),
+            "html" | "htm" => Ok(ExportFormat::Html),
+            #[cfg(feature = "image")]
+            "png" => Ok(ExportFormat::Png),
+            _ => Err(format!("Invalid format: {}", s)),
+        }
+    }
+}
+
+// the lines a text grid is drawn with
+struct Frame {
+    top: &'static str,
+    middle: &'static str, // between bands of boxes
+    bottom: &'static str,
+    outer: &'static str,
+    inner: &'static str,
+}
+
+const ASCII: Frame = Frame {
+    top: BORDER,
+    middle: BORDER,
+    bottom: BORDER,
+    outer: "|",
+    inner: "|",
+};
+
+const UNICODE: Frame = Frame {
+    top: "╔═══════╤═══════╤═══════╗",
+    middle: "╟───────┼───────┼───────╢",
+    bottom: "╚═══════╧═══════╧═══════╝",
+    outer: "║",
+    inner: "│",
+};
+
+// the board boxed in text, plain ascii by default so it survives chats and
+// printers, the player's entries shown alongside the clues if asked for
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct TextGrid {
+    unicode: bool,
+    blank: char, // drawn for an empty cell
+}
+
+impl Default for TextGrid {
+    fn default() -> Self {
+        TextGrid {
+            unicode: false,
+            blank: '.',
+        }
+    }
+}
+
+impl TextGrid {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    // box drawing characters instead of +, - and |
+    pub fn unicode(mut self, unicode: bool) -> Self {
+        self.unicode = unicode;
+        self
+    }
+
+    pub fn blank(mut self, blank: char) -> Self {
+        self.blank = blank;
+        self
+    }
+
+    pub fn render(&self, puzzle: &Puzzle, entries: bool) -> String {
+        let frame = if self.unicode { &UNICODE } else { &ASCII };
+        let mut out = String::new();
+        for (row, cells) in puzzle.grid().iter().enumerate() {
+            match row {
+                0 => out.push_str(frame.top),
+                _ if row.is_multiple_of(3) => out.push_str(frame.middle),
+                _ => {}
+            }
+            if row.is_multiple_of(3) {
+                out.push('\n');
+            }
+            for (col, cell) in cells.iter().enumerate() {
+                match col {
+                    0 => out.push_str(frame.outer),
+                    _ if col.is_multiple_of(3) => out.push_str(frame.inner),
+                    _ => {}
+                }
+                if col.is_multiple_of(3) {
+                    out.push(' ');
+                }
+                match cell.value() {
+                    num if num != 0 && (cell.is_clue() || entries) => {
+                        out.push((b'0' + num) as char)
+                    }
+                    _ => out.push(self.blank),
+                }
+                out.push(' ');
+            }
+            out.push_str(frame.outer);
+            out.push('\n');
+        }
+        out.push_str(frame.bottom);
+        out.push('\n');
+        out
+    }
+}
+
+// the board as an svg image for web pages and documents: clues in bold,
+// entries in blue if asked for and optionally every empty cell's candidates
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct SvgGrid {
+    candidates: bool,
+}
+
+impl SvgGrid {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn candidates(mut self, candidates: bool) -> Self {
+        self.candidates = candidates;
+        self
+    }
+
+    pub fn render(&self, puzzle: &Puzzle, entries: bool) -> String {
+        let size = SVG_CELL * 9 + SVG_MARGIN * 2;
+        let mut grid = puzzle.grid();
+        if !entries {
+            for cell in grid.iter_mut().flatten().filter(|cell| !cell.is_clue()) {
+                *cell = Cell::new(0, false);
+            }
+        }
+
+        let mut svg = format!(
+            "<svg xmlns=\"http://www.w3.org/2000/svg\" width=\"{size}\" height=\"{size}\" \
+             viewBox=\"0 0 {size} {size}\" font-family=\"sans-serif\">\n\
+             <rect width=\"{size}\" height=\"{size}\" fill=\"white\"/>\n"
+        );
+        for (row, cells) in grid.iter().enumerate() {
+            for (col, cell) in cells.iter().enumerate() {
+                let (x, y) = (SVG_MARGIN + col * SVG_CELL, SVG_MARGIN + row * SVG_CELL);
+                let (center_x, center_y) = (x + SVG_CELL / 2, y + SVG_CELL / 2);
+                match cell.value() {
+                    0 if self.candidates => {
+                        for num in candidates(&grid, row, col) {
+                            let spot = num as usize - 1;
+                            let _ = writeln!(
+                                svg,
+                                "<text x=\"{}\" y=\"{}\" font-size=\"12\" fill=\"#888\" \
+                                 text-anchor=\"middle\" dominant-baseline=\"central\">{}</text>",
+                                x + SVG_CELL * (spot % 3 * 2 + 1) / 6,
+                                y + SVG_CELL * (spot / 3 * 2 + 1) / 6,
+                                num
+                            );
+                        }
+                    }
+                    0 => {}
+                    num => {
+                        let style = if cell.is_clue() {
+                            "font-weight=\"bold\" fill=\"black\""
+                        } else {
+                            "fill=\"#1a4fa0\""
+                        };
+                        let _ = writeln!(
+                            svg,
+                            "<text x=\"{center_x}\" y=\"{center_y}\" font-size=\"30\" {style} \
+                             text-anchor=\"middle\" dominant-baseline=\"central\">{num}</text>"
+                        );
+                    }
+                }
+            }
+        }
+
+        // thin lines between cells, thick ones around boxes and the board
+        for i in 0..=9 {
+            let at = SVG_MARGIN + i * SVG_CELL;
+            let (width, color) = if i % 3 == 0 {
+                (3, "black")
+            } else {
+                (1, "#999")
+            };
+            let (start, end) = (SVG_MARGIN, SVG_MARGIN + 9 * SVG_CELL);
+            let _ = writeln!(
+                svg,
+                "<line x1=\"{at}\" y1=\"{start}\" x2=\"{at}\" y2=\"{end}\" stroke=\"{color}\" \
+                 stroke-width=\"{width}\" stroke-linecap=\"square\"/>\n\
+                 <line x1=\"{start}\" y1=\"{at}\" x2=\"{end}\" y2=\"{at}\" stroke=\"{color}\" \
+                 stroke-width=\"{width}\" stroke-linecap=\"square\"/>"
+            );
+        }
+        svg.push_str("</svg>\n");
+        svg
+    }
+}
+
+// the board as a tikzpicture to drop into a document with \usepackage{tikz},
+// row 1 at the top like on screen and entries in blue if asked for
+fn tikz(puzzle: &Puzzle, entries: bool) -> String {
+    let mut tex = String::from(
+        "% needs \\usepackage{tikz}\n\
+         \\begin{tikzpicture}[scale=0.6]\n\
+         \\draw[step=1, gray!60, very thin] (0,0) grid (9,9);\n\
+         \\draw[step=3, very thick] (0,0) grid (9,9);\n",
+    );
+    for (row, cells) in puzzle.grid().iter().enumerate() {
+        for (col, cell) in cells.iter().enumerate() {
+            let (x, y) = (col as f32 + 0.5, 8.5 - row as f32);
+            match cell.value() {
+                0 => {}
+                num if cell.is_clue() => {
+                    let _ = writeln!(tex, "\\node at ({x},{y}) {{\\Large {num}}};");
+                }
+                num if entries => {
+                    let _ = writeln!(
+                        tex,
+                        "\\node[text=blue!60!black] at ({x},{y}) {{\\Large {num}}};"
+                    );
+                }
+                _ => {}
+            }
+        }
+    }
+    tex.push_str("\\end{tikzpicture}\n");
+    tex
+}
+
+const HTML_STYLE: &str = "\
+body { font-family: sans-serif; display: flex; flex-direction: column; align-items: center; }
+table { border-collapse: collapse; border: 3px solid black; margin: 1em; }
+td { width: 2em; height: 2em; border: 1px solid #999; text-align: center; font-size: 1.5em; }
+td:nth-child(3n) { border-right: 3px solid black; }
+tr:nth-child(3n) td { border-bottom: 3px solid black; }
+.clue { font-weight: bold; }
+.entry { color: #1a4fa0; }
+summary { cursor: pointer; }
+";
+
+// the board as a standalone web page, clues in bold and entries in blue if
+// asked for, with the solution optionally hidden behind a click
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct HtmlGrid {
+    solution: bool,
+}
+
+impl HtmlGrid {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn solution(mut self, solution: bool) -> Self {
+        self.solution = solution;
+        self
+    }
+
+    pub fn render(&self, puzzle: &Puzzle, entries: bool) -> String {
+        let mut html = format!(
+            "<!DOCTYPE html>\n<html>\n<head>\n<meta charset=\"utf-8\">\n\
+             <title>Sudoku</title>\n<style>\n{HTML_STYLE}</style>\n</head>\n<body>\n"
+        );
+        html_table(&mut html, &puzzle.grid(), entries);
+        if self.solution {
+            html.push_str("<details>\n<summary>Show the solution</summary>\n");
+            // the solved cells told apart from the clues like entries are
+            let mut solved = puzzle.grid();
+            for (cell, solution) in solved
+                .iter_mut()
+                .flatten()
+                .zip(puzzle.to_solved().grid().iter().flatten())
+            {
+                if !cell.is_clue() {
+                    *cell = Cell::new(solution.value(), false);
+                }
+            }
+            html_table(&mut html, &solved, true);
+            html.push_str("</details>\n");
+        }
+        html.push_str("</body>\n</html>\n");
+        html
+    }
+}
+
+fn html_table(html: &mut String, grid: &[[Cell; 9]; 9], entries: bool) {
+    html.push_str("<table>\n");
+    for cells in grid {
+        html.push_str("<tr>");
+        for cell in cells {
+            match cell.value() {
+                num if num != 0 && cell.is_clue() => {
+                    let _ = write!(html, "<td class=\"clue\">{num}</td>");
+                }
+                num if num != 0 && entries => {
+                    let _ = write!(html, "<td class=\"entry\">{num}</td>");
+                }
+                _ => html.push_str("<td></td>"),
+            }
+        }
+        html.push_str("</tr>\n");
+    }
+    html.push_str("</table>\n");
+}
+
+// the colors of a png board
+#[cfg(feature = "image")]
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum ImageTheme {
+    // the image shown in kitty graphics terminals
+    Dark,
+    #[default]
+    Light,
+}
+
+#[cfg(feature = "image")]
+impl FromStr for ImageTheme {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.to_lowercase().as_str() {
+            "dark" => Ok(ImageTheme::Dark),
+            "light" => Ok(ImageTheme::Light),
+            _ => Err(format!("Invalid theme: {}", s)),
+        }
+    }
+}
+
+#[cfg(feature = "image")]
+const DEFAULT_PNG_SIZE: usize = 450;
+
+// the board as a png image, for chats that don't keep text grids aligned
+#[cfg(feature = "image")]
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct PngGrid {
+    size: usize, // width and height in pixels
+    theme: ImageTheme,
+}
+
+#[cfg(feature = "image")]
+impl Default for PngGrid {
+    fn default() -> Self {
+        PngGrid {
+            size: DEFAULT_PNG_SIZE,
+            theme: ImageTheme::default(),
+        }
+    }
+}
+
+#[cfg(feature = "image")]
+impl PngGrid {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    // rounded down to a multiple of 9 so every cell is as wide, 9 at least
+    pub fn size(mut self, size: usize) -> Self {
+        self.size = size;
+        self
+    }
+
+    pub fn theme(mut self, theme: ImageTheme) -> Self {
+        self.theme = theme;
+        self
+    }
+
+    pub fn render(&self, puzzle: &Puzzle, entries: bool) -> Vec<u8> {
+        use crate::raster::{rasterize, DARK, LIGHT};
+
+        let mut grid = puzzle.grid();
+        if !entries {
+            for cell in grid.iter_mut().flatten().filter(|cell| !cell.is_clue()) {
+                *cell = Cell::new(0, false);
+            }
+        }
+        let palette = match self.theme {
+            ImageTheme::Dark => DARK,
+            ImageTheme::Light => LIGHT,
+        };
+        let cell_pixels = (self.size / 9).max(1);
+        let pixels = rasterize(&grid, None, cell_pixels, &palette);
+        crate::png::encode(9 * cell_pixels, 9 * cell_pixels, &pixels)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_svg_draws_clues_entries_and_candidates() {
+        let mut puzzle = Puzzle::new(crate::Difficulty::Easy);
+        let (row, col) = puzzle.next_empty_cell(8, 8).unwrap();
+        puzzle.insert_number(row, col, 1).unwrap();
+
+        let texts = |svg: &str| svg.matches("<text").count();
+        let clues = SvgGrid::new().render(&puzzle, false);
+        assert!(clues.starts_with("<svg") && clues.ends_with("</svg>\n"));
+        assert_eq!(texts(&clues), puzzle.clues());
+        assert_eq!(
+            clues.matches("font-weight=\"bold\"").count(),
+            puzzle.clues()
+        );
+        assert_eq!(clues.matches("<line").count(), 20);
+        assert_eq!(
+            texts(&SvgGrid::new().render(&puzzle, true)),
+            puzzle.clues() + 1
+        );
+
+        let with_candidates = SvgGrid::new().candidates(true).render(&puzzle, false);
+        assert!(texts(&with_candidates) > texts(&clues));
+    }
+
+    #[test]
+    fn test_tex_draws_the_grid_and_numbers() {
+        let mut puzzle = Puzzle::new(crate::Difficulty::Easy);
+        let (row, col) = puzzle.next_empty_cell(8, 8).unwrap();
+        puzzle.insert_number(row, col, 1).unwrap();
+
+        let clues = tikz(&puzzle, false);
+        assert!(clues.contains("\\begin{tikzpicture}"));
+        assert!(clues.ends_with("\\end{tikzpicture}\n"));
+        assert_eq!(clues.matches("\\node").count(), puzzle.clues());
+        assert_eq!(
+            tikz(&puzzle, true).matches("\\node").count(),
+            puzzle.clues() + 1
+        );
+        // the top left cell sits at the top of the picture
+        let top_left = puzzle.grid()[0][0];
+        if top_left.is_clue() {
+            let top_left = top_left.value();
+            assert!(clues.contains(&format!("(0.5,8.5) {{\\Large {top_left}}}")));
+        }
+        assert_eq!(
+            ExportFormat::from_path(Path::new("a.tex")),
+            ExportFormat::Tex
+        );
+    }
+
+    #[test]
+    fn test_html_hides_the_solution_until_asked() {
+        let puzzle = Puzzle::new(crate::Difficulty::Easy);
+        let page = HtmlGrid::new().render(&puzzle, false);
+        assert!(page.starts_with("<!DOCTYPE html>") && page.ends_with("</html>\n"));
+        assert_eq!(page.matches("<td").count(), 81);
+        assert_eq!(page.matches("class=\"clue\"").count(), puzzle.clues());
+        assert!(!page.contains("<details>"));
+
+        let page = HtmlGrid::new().solution(true).render(&puzzle, false);
+        assert_eq!(page.matches("<td").count(), 162);
+        assert_eq!(page.matches("class=\"entry\"").count(), 81 - puzzle.clues());
+        assert_eq!(
+            ExportFormat::from_path(Path::new("a.html")),
+            ExportFormat::Html
+        );
+    }
+
+    #[cfg(feature = "image")]
+    #[test]
+    fn test_png_has_the_asked_size() {
+        let puzzle = Puzzle::new(crate::Difficulty::Easy);
+        let png = PngGrid::new().size(95).render(&puzzle, false);
+        // the width and height in the header, 95 rounded down to 90
+        assert_eq!(png[16..24], [0, 0, 0, 90, 0, 0, 0, 90]);
+        assert_ne!(
+            png,
+            PngGrid::new()
+                .theme(ImageTheme::Dark)
+                .size(95)
+                .render(&puzzle, false)
+        );
+        assert_eq!(
+            ExportFormat::from_path(Path::new("a.png")),
+            ExportFormat::Png
+        );
+    }
+
+    #[test]
+    fn test_format_from_path() {
+        assert_eq!(
+            ExportFormat::from_path(Path::new("a.svg")),
+            ExportFormat::Svg
+        );
+        assert_eq!(
+            ExportFormat::from_path(Path::new("a.txt")),
+            ExportFormat::Text
+        );
+        assert_eq!(
+            ExportFormat::from_path(Path::new("board")),
+            ExportFormat::Text
+        );
+    }
+
+    #[test]
+    fn test_text_lays_out_boxes() {
+        let mut puzzle = Puzzle::new(crate::Difficulty::Easy);
+        let (row, col) = puzzle.next_empty_cell(8, 8).unwrap();
+        puzzle.insert_number(row, col, 1).unwrap();
+
+        let text = |puzzle: &Puzzle, entries| TextGrid::new().render(puzzle, entries);
+        let clues = text(&puzzle, false);
+        let lines: Vec<&str> = clues.lines().collect();
+        assert_eq!(lines.len(), 13);
+        assert!(lines.iter().all(|line| line.len() == BORDER.len()));
+        assert_eq!(lines[4], BORDER);
+
+        let board = puzzle.to_board(false).replace('0', ".");
+        let cells: String = clues
+            .chars()
+            .filter(|c| *c == '.' || c.is_ascii_digit())
+            .collect();
+        assert_eq!(cells, board);
+        assert_ne!(text(&puzzle, true), clues);
+
+        let unicode = TextGrid::new()
+            .unicode(true)
+            .blank(' ')
+            .render(&puzzle, false);
+        let lines: Vec<&str> = unicode.lines().collect();
+        assert_eq!(lines.len(), 13);
+        assert!(lines
+            .iter()
+            .all(|line| line.chars().count() == UNICODE.top.chars().count()));
+        assert_eq!(lines[4], UNICODE.middle);
+    }
+}
@@ -0,0 +1,244 @@
+use std::{ffi::c_int, slice};
+
+use rand::{rngs::StdRng, SeedableRng};
+
+use crate::{
+    puzzle::{validate_sudoku, Grid},
+    rate, Cell, Difficulty, Puzzle,
+};
+
+// the engine for C, as declared in include/rsudoku.h: a board is 81 bytes in
+// reading order, 0 for an empty cell, and each function returns one of these
+pub const RSUDOKU_OK: c_int = 0;
+// a null pointer or a cell above 9
+pub const RSUDOKU_INVALID: c_int = -1;
+pub const RSUDOKU_CONFLICT: c_int = -2;
+pub const RSUDOKU_UNSOLVABLE: c_int = -3;
+pub const RSUDOKU_NOT_UNIQUE: c_int = -4;
+
+const CELLS: usize = 81;
+
+// how hard a board is, see `Rating`; difficulty counts from 0 for easy to 3
+// for expert
+#[repr(C)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct RsudokuRating {
+    pub difficulty: c_int,
+    pub score: u32,
+    pub stuck: u32,
+}
+
+unsafe fn read_board<'a>(cells: *const u8) -> Result<&'a [u8], c_int> {
+    if cells.is_null() {
+        return Err(RSUDOKU_INVALID);
+    }
+    let cells = slice::from_raw_parts(cells, CELLS);
+    match cells.iter().all(|&value| value <= 9) {
+        true => Ok(cells),
+        false => Err(RSUDOKU_INVALID),
+    }
+}
+
+fn to_grid(cells: &[u8]) -> Grid {
+    let mut grid = [[Cell::new(0, false); 9]; 9];
+    for (i, &value) in cells.iter().enumerate() {
+        grid[i / 9][i % 9] = Cell::new(value, value != 0);
+    }
+    grid
+}
+
+fn to_puzzle(cells: &[u8]) -> Result<Puzzle, c_int> {
+    let board: String = cells.iter().map(|&value| (b'0' + value) as char).collect();
+    match Puzzle::from_board(&board) {
+        Some(puzzle) => Ok(puzzle),
+        None if validate_sudoku(&to_grid(cells)) => Err(RSUDOKU_UNSOLVABLE),
+        None => Err(RSUDOKU_CONFLICT),
+    }
+}
+
+unsafe fn write_board(grid: &Grid, out: *mut u8) {
+    let out = slice::from_raw_parts_mut(out, CELLS);
+    for (out, cell) in out.iter_mut().zip(grid.iter().flatten()) {
+        *out = cell.value();
+    }
+}
+
+/// The clues a puzzle of a difficulty keeps, from 0 for easy to 3 for
+/// expert, or 0 for any other number.
+#[no_mangle]
+pub extern "C" fn rsudoku_clues(difficulty: c_int) -> u32 {
+    usize::try_from(difficulty)
+        .ok()
+        .and_then(|i| Difficulty::all().get(i).copied())
+        .map_or(0, |difficulty| difficulty as u32)
+}
+
+/// Writes a new puzzle with `clues` clues, clamped to 24..=81, to `cells`.
+/// A low count can keep a few more, when removing another clue would give
+/// the puzzle a second solution. The same seed always gives the same puzzle.
+///
+/// # Safety
+///
+/// `cells` must be null or point to 81 writable bytes.
+#[no_mangle]
+pub unsafe extern "C" fn rsudoku_generate(clues: u32, seed: u64, cells: *mut u8) -> c_int {
+    if cells.is_null() {
+        return RSUDOKU_INVALID;
+    }
+    let puzzle = Puzzle::with_rng(clues as usize, &mut StdRng::seed_from_u64(seed));
+    write_board(&puzzle.grid(), cells);
+    RSUDOKU_OK
+}
+
+/// Writes the solution of the puzzle in `cells` to `solution`, which may be
+/// the same buffer. A puzzle with several solutions gets one of them.
+///
+/// # Safety
+///
+/// `cells` must be null or point to 81 readable bytes, `solution` to 81
+/// writable ones.
+#[no_mangle]
+pub unsafe extern "C" fn rsudoku_solve(cells: *const u8, solution: *mut u8) -> c_int {
+    if solution.is_null() {
+        return RSUDOKU_INVALID;
+    }
+    match read_board(cells).and_then(to_puzzle) {
+        Ok(puzzle) => {
+            write_board(&puzzle.to_solved().grid(), solution);
+            RSUDOKU_OK
+        }
+        Err(code) => code,
+    }
+}
+
+/// Rates the board in `cells` by the techniques it takes to finish.
+///
+/// # Safety
+///
+/// `cells` must be null or point to 81 readable bytes, `rating` must be
+/// null or point to an `RsudokuRating`.
+#[no_mangle]
+pub unsafe extern "C" fn rsudoku_rate(cells: *const u8, rating: *mut RsudokuRating) -> c_int {
+    if rating.is_null() {
+        return RSUDOKU_INVALID;
+    }
+    let cells = match read_board(cells) {
+        Ok(cells) => cells,
+        Err(code) => return code,
+    };
+    let rated = rate(&to_grid(cells));
+    *rating = RsudokuRating {
+        difficulty: Difficulty::all()
+            .iter()
+            .position(|&difficulty| difficulty == rated.difficulty)
+            .unwrap_or_default() as c_int,
+        score: rated.score,
+        stuck: rated.stuck as u32,
+    };
+    RSUDOKU_OK
+}
+
+/// Checks that the clues in `cells` don't conflict and have exactly one
+/// solution, returning `RSUDOKU_OK` or what is wrong.
+///
+/// # Safety
+///
+/// `cells` must be null or point to 81 readable bytes.
+#[no_mangle]
+pub unsafe extern "C" fn rsudoku_validate(cells: *const u8) -> c_int {
+    match read_board(cells).and_then(to_puzzle) {
+        Ok(puzzle) if puzzle.solution_count() == 1 => RSUDOKU_OK,
+        Ok(_) => RSUDOKU_NOT_UNIQUE,
+        Err(code) => code,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::ptr;
+
+    const HEADER: &str = include_str!("../include/rsudoku.h");
+
+    #[test]
+    fn test_generate_solve_rate_and_validate() {
+        let mut cells = [0u8; CELLS];
+        unsafe {
+            assert_eq!(rsudoku_generate(36, 7, cells.as_mut_ptr()), RSUDOKU_OK);
+            assert_eq!(cells.iter().filter(|&&value| value != 0).count(), 36);
+            assert_eq!(rsudoku_validate(cells.as_ptr()), RSUDOKU_OK);
+
+            let mut rating = RsudokuRating {
+                difficulty: -1,
+                score: 0,
+                stuck: 0,
+            };
+            assert_eq!(rsudoku_rate(cells.as_ptr(), &mut rating), RSUDOKU_OK);
+            assert!((0..=3).contains(&rating.difficulty));
+
+            let mut solution = [0u8; CELLS];
+            assert_eq!(
+                rsudoku_solve(cells.as_ptr(), solution.as_mut_ptr()),
+                RSUDOKU_OK
+            );
+            assert!(solution.iter().all(|&value| (1..=9).contains(&value)));
+            for (&clue, &value) in cells.iter().zip(&solution) {
+                assert!(clue == 0 || clue == value);
+            }
+        }
+    }
+
+    #[test]
+    fn test_clues_by_difficulty() {
+        assert_eq!(rsudoku_clues(0), Difficulty::Easy as u32);
+        assert_eq!(rsudoku_clues(3), Difficulty::Expert as u32);
+        assert_eq!(rsudoku_clues(4), 0);
+        assert_eq!(rsudoku_clues(-1), 0);
+    }
+
+    #[test]
+    fn test_bad_boards_are_told_apart() {
+        let mut cells = [0u8; CELLS];
+        unsafe {
+            assert_eq!(rsudoku_validate(ptr::null()), RSUDOKU_INVALID);
+            assert_eq!(rsudoku_validate(cells.as_ptr()), RSUDOKU_NOT_UNIQUE);
+            cells[0] = 10;
+            assert_eq!(rsudoku_validate(cells.as_ptr()), RSUDOKU_INVALID);
+            cells[0] = 1;
+            cells[1] = 1;
+            assert_eq!(rsudoku_validate(cells.as_ptr()), RSUDOKU_CONFLICT);
+            // nothing conflicts yet the top left cell has no number left
+            cells = [0; CELLS];
+            cells[1..9].copy_from_slice(&[2, 3, 4, 5, 6, 7, 8, 9]);
+            cells[9 * 4] = 1;
+            assert_eq!(rsudoku_validate(cells.as_ptr()), RSUDOKU_UNSOLVABLE);
+        }
+    }
+
+    // the header is kept by hand, this keeps it in step
+    #[test]
+    fn test_the_header_matches() {
+        for function in [
+            "rsudoku_clues",
+            "rsudoku_generate",
+            "rsudoku_solve",
+            "rsudoku_rate",
+            "rsudoku_validate",
+        ] {
+            assert!(HEADER.contains(&format!("{}(", function)), "{}", function);
+        }
+        for (name, value) in [
+            ("RSUDOKU_OK", RSUDOKU_OK),
+            ("RSUDOKU_INVALID", RSUDOKU_INVALID),
+            ("RSUDOKU_CONFLICT", RSUDOKU_CONFLICT),
+            ("RSUDOKU_UNSOLVABLE", RSUDOKU_UNSOLVABLE),
+            ("RSUDOKU_NOT_UNIQUE", RSUDOKU_NOT_UNIQUE),
+        ] {
+            assert!(
+                HEADER.contains(&format!("#define {} {}\n", name, value)),
+                "{}",
+                name
+            );
+        }
+    }
+}
@@ -0,0 +1,108 @@
+use std::io::{self, Write};
+
+use crossterm::{cursor::MoveTo, queue};
+use ratatui::layout::Rect;
+
+use crate::{
+    clipboard::encode_base64,
+    puzzle::Grid,
+    raster::{rasterize, DARK},
+};
+
+// pixels per cell in the uploaded image
+const CELL_PIXELS: usize = 48;
+// the kitty protocol takes its payload in chunks of at most this many bytes
+const CHUNK_SIZE: usize = 4096;
+const IMAGE_ID: u32 = 1;
+
+// true when the terminal is known to speak the kitty graphics protocol,
+// multiplexers swallow the escapes so they are left out
+pub(crate) fn is_supported() -> bool {
+    let var = |name| std::env::var(name).unwrap_or_default();
+    if !var("TMUX").is_empty() {
+        return false;
+    }
+    !var("KITTY_WINDOW_ID").is_empty()
+        || var("TERM").contains("kitty")
+        || matches!(var("TERM_PROGRAM").as_str(), "WezTerm" | "ghostty")
+}
+
+// the board image shown over the cell rendered one, uploaded again only when
+// what it shows changes
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub(crate) struct BoardImage {
+    shown: Option<(Grid, (usize, usize), Rect)>,
+}
+
+impl BoardImage {
+    pub(crate) fn show(
+        &mut self,
+        out: &mut impl Write,
+        grid: &Grid,
+        selected: (usize, usize),
+        area: Rect,
+    ) -> io::Result<()> {
+        let state = (*grid, selected, area);
+        if self.shown == Some(state) {
+            return Ok(());
+        }
+
+        let size = 9 * CELL_PIXELS;
+        let data = encode_base64(&rasterize(grid, Some(selected), CELL_PIXELS, &DARK));
+        queue!(out, MoveTo(area.x, area.y))?;
+        let chunks: Vec<&[u8]> = data.as_bytes().chunks(CHUNK_SIZE).collect();
+        for (i, chunk) in chunks.iter().enumerate() {
+            let more = (i + 1 < chunks.len()) as u8;
+            // the first chunk carries the header: rgb pixels, scaled to the
+            // board's cells, replacing the previous image and quiet so no
+            // replies end up in the input
+            if i == 0 {
+                write!(
+                    out,
+                    "\x1b_Ga=T,f=24,s={size},v={size},i={IMAGE_ID},p=1,c={},r={},C=1,q=2,m={more};",
+                    area.width, area.height
+                )?;
+            } else {
+                write!(out, "\x1b_Gm={more};")?;
+            }
+            out.write_all(chunk)?;
+            write!(out, "\x1b\\")?;
+        }
+        out.flush()?;
+
+        self.shown = Some(state);
+        Ok(())
+    }
+
+    pub(crate) fn hide(&mut self, out: &mut impl Write) -> io::Result<()> {
+        if self.shown.take().is_some() {
+            write!(out, "\x1b_Ga=d,d=I,i={IMAGE_ID},q=2\x1b\\")?;
+            out.flush()?;
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::Puzzle;
+
+    #[test]
+    fn test_image_is_redrawn_only_on_change() {
+        let grid = Puzzle::new(crate::Difficulty::Easy).grid();
+        let area = Rect::new(2, 1, 37, 19);
+        let mut image = BoardImage::default();
+
+        let mut out = Vec::new();
+        image.show(&mut out, &grid, (0, 0), area).unwrap();
+        assert!(!out.is_empty());
+
+        let mut out = Vec::new();
+        image.show(&mut out, &grid, (0, 0), area).unwrap();
+        assert!(out.is_empty());
+
+        image.hide(&mut out).unwrap();
+        assert!(!out.is_empty());
+    }
+}
@@ -0,0 +1,115 @@
+use std::{collections::HashMap, str::FromStr, sync::OnceLock};
+
+use crate::Difficulty;
+
+// the language the game talks in, its strings read from src/lang
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Hash)]
+pub enum Lang {
+    #[default]
+    English,
+    German,
+}
+
+type Strings = HashMap<&'static str, &'static str>;
+
+// `key = text` lines, blank lines and # comments skipped
+fn parse(file: &'static str) -> Strings {
+    file.lines()
+        .map(str::trim)
+        .filter(|line| !line.is_empty() && !line.starts_with('#'))
+        .filter_map(|line| line.split_once('='))
+        .map(|(key, text)| (key.trim(), text.trim()))
+        .collect()
+}
+
+impl Lang {
+    // the language asked for by LANG, e.g. de_DE.UTF-8, English otherwise
+    pub fn from_env() -> Lang {
+        std::env::var("LANG")
+            .ok()
+            .and_then(|lang| lang.get(..2)?.parse().ok())
+            .unwrap_or_default()
+    }
+
+    fn strings(self) -> &'static Strings {
+        static ENGLISH: OnceLock<Strings> = OnceLock::new();
+        static GERMAN: OnceLock<Strings> = OnceLock::new();
+        match self {
+            Lang::English => ENGLISH.get_or_init(|| parse(include_str!("lang/en.txt"))),
+            Lang::German => GERMAN.get_or_init(|| parse(include_str!("lang/de.txt"))),
+        }
+    }
+
+    // the text for `key`, falling back to English and then to the key itself
+    pub(crate) fn tr(self, key: &'static str) -> &'static str {
+        self.strings()
+            .get(key)
+            .or_else(|| Lang::English.strings().get(key))
+            .copied()
+            .unwrap_or(key)
+    }
+
+    // the text for `key` with each {} filled in by the next of `args`
+    pub(crate) fn format(self, key: &'static str, args: &[&dyn std::fmt::Display]) -> String {
+        let mut parts = self.tr(key).split("{}");
+        let mut text = parts.next().unwrap_or_default().to_string();
+        for (i, part) in parts.enumerate() {
+            if let Some(arg) = args.get(i) {
+                text.push_str(&arg.to_string());
+            }
+            text.push_str(part);
+        }
+        text
+    }
+
+    pub(crate) fn difficulty(self, difficulty: Difficulty) -> &'static str {
+        self.tr(match difficulty {
+            Difficulty::Easy => "easy",
+            Difficulty::Medium => "medium",
+            Difficulty::Hard => "hard",
+            Difficulty::Expert => "expert",
+        })
+    }
+}
+
+impl FromStr for Lang {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.to_lowercase().as_str() {
+            "en" | "english" => Ok(Lang::English),
+            "de" | "german" | "deutsch" => Ok(Lang::German),
+            _ => Err(format!("Unsupported language: {}", s)),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_translations_cover_english() {
+        for key in Lang::English.strings().keys() {
+            assert!(
+                Lang::German.strings().contains_key(key),
+                "de misses {}",
+                key
+            );
+        }
+        assert_eq!(Lang::German.tr("quit"), "Beenden");
+        assert_eq!(Lang::German.tr("no such key"), "no such key");
+    }
+
+    #[test]
+    fn test_format_fills_placeholders_in_order() {
+        assert_eq!(
+            Lang::English.format("mistakes_hints", &[&2, &1]),
+            "Mistakes: 2  Hints: 1"
+        );
+        assert_eq!(
+            Lang::German.format("elapsed", &[&"00:01:00"]),
+            "Verstrichen: 00:01:00"
+        );
+    }
+}
@@ -0,0 +1,734 @@
+use crossterm::event::{KeyCode, KeyEvent, KeyModifiers};
+use std::{fmt, str::FromStr};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Action {
+    Quit,
+    NewGame,
+    Reset,
+    Hint,
+    MoveLeft,
+    MoveRight,
+    MoveUp,
+    MoveDown,
+    NextEmpty,
+    PrevEmpty,
+    // waits for a digit and then jumps to that box
+    BoxPrefix,
+    JumpToBox(u8),
+    Insert(u8),
+    Clear,
+    Undo,
+    Redo,
+    Pause,
+    Help,
+    Command,
+    Reveal,
+    Check,
+    // check and also clear every wrong entry
+    CheckAndClear,
+    // pencil mark the number in the selected cell, or take the mark away
+    Note(u8),
+    // while on, digits pencil mark instead of inserting
+    NotesMode,
+    // paint the selected cell with one of the color slots, 1-5
+    Color(u8),
+    ClearColor,
+    // start or stop selecting a block of cells from the cursor
+    Select,
+    // put the puzzle on the clipboard, with the player's entries if true
+    Copy(bool),
+    // park the game in a tab and start another, or move on to the next tab
+    NewTab,
+    NextTab,
+    // show or hide the clock, it keeps running either way
+    ToggleClock,
+}
+
+// the actions a config's [keymap] table can rebind by name; digits keep
+// inserting, coloring and jumping to boxes
+const NAMES: [(&str, Action); 28] = [
+    ("quit", Action::Quit),
+    ("new_game", Action::NewGame),
+    ("reset", Action::Reset),
+    ("hint", Action::Hint),
+    ("move_left", Action::MoveLeft),
+    ("move_right", Action::MoveRight),
+    ("move_up", Action::MoveUp),
+    ("move_down", Action::MoveDown),
+    ("next_empty", Action::NextEmpty),
+    ("prev_empty", Action::PrevEmpty),
+    ("box_prefix", Action::BoxPrefix),
+    ("clear", Action::Clear),
+    ("notes_mode", Action::NotesMode),
+    ("undo", Action::Undo),
+    ("redo", Action::Redo),
+    ("pause", Action::Pause),
+    ("help", Action::Help),
+    ("command", Action::Command),
+    ("reveal", Action::Reveal),
+    ("check", Action::Check),
+    ("check_and_clear", Action::CheckAndClear),
+    ("clear_color", Action::ClearColor),
+    ("select", Action::Select),
+    ("copy", Action::Copy(false)),
+    ("copy_entries", Action::Copy(true)),
+    ("new_tab", Action::NewTab),
+    ("next_tab", Action::NextTab),
+    ("toggle_clock", Action::ToggleClock),
+];
+
+impl FromStr for Action {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        NAMES
+            .iter()
+            .find(|(name, _)| *name == s)
+            .map(|&(_, action)| action)
+            .ok_or_else(|| format!("Invalid action: {}", s))
+    }
+}
+
+impl Action {
+    pub fn description(&self) -> &'static str {
+        match self {
+            Action::Quit => "Quit",
+            Action::NewGame => "New game",
+            Action::Reset => "Reset the puzzle",
+            Action::Hint => "Hint the selected or next unsolved cell",
+            Action::MoveLeft => "Move left",
+            Action::MoveRight => "Move right",
+            Action::MoveUp => "Move up",
+            Action::MoveDown => "Move down",
+            Action::NextEmpty => "Next empty cell",
+            Action::PrevEmpty => "Previous empty cell",
+            Action::BoxPrefix => "Jump to box (then 1-9)",
+            Action::JumpToBox(_) => "Jump to box",
+            Action::Insert(_) => "Insert number",
+            Action::Clear => "Clear the selected cell",
+            Action::Undo => "Undo",
+            Action::Redo => "Redo",
+            Action::Pause => "Pause (hides the board)",
+            Action::Help => "Show this help",
+            Action::Command => "Command prompt (:new hard, :hint, :quit, Tab completes)",
+            Action::Reveal => "Reveal the solution (asks first)",
+            Action::Check => "Check your entries against the solution",
+            Action::CheckAndClear => "Check and clear the wrong entries",
+            Action::Note(_) => "Pencil mark a number, or take the mark away",
+            Action::NotesMode => "Notes mode (digits pencil mark)",
+            Action::Color(_) => "Color the selected cell",
+            Action::ClearColor => "Remove the selected cell's color",
+            Action::Select => "Select several cells (move to extend, Esc cancels)",
+            Action::Copy(false) => "Copy the puzzle to the clipboard",
+            Action::Copy(true) => "Copy the puzzle with your entries",
+            Action::NewTab => "Open a new game in another tab",
+            Action::NextTab => "Switch to the next tab",
+            Action::ToggleClock => "Show or hide the clock",
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum KeymapPreset {
+    #[default]
+    Default,
+    Vim,
+    Numpad,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct KeyBinding {
+    code: KeyCode,
+    modifiers: KeyModifiers,
+}
+
+impl KeyBinding {
+    pub const fn new(code: KeyCode, modifiers: KeyModifiers) -> Self {
+        Self { code, modifiers }
+    }
+
+    pub const fn plain(code: KeyCode) -> Self {
+        Self::new(code, KeyModifiers::NONE)
+    }
+
+    pub const fn char(c: char) -> Self {
+        Self::plain(KeyCode::Char(c))
+    }
+
+    pub fn code(&self) -> KeyCode {
+        self.code
+    }
+
+    pub fn modifiers(&self) -> KeyModifiers {
+        self.modifiers
+    }
+
+    // the lowercase binding for uppercase letters, if there is one
+    fn lowercase(&self) -> Option<KeyBinding> {
+        match self.code {
+            KeyCode::Char(c) if c.is_ascii_uppercase() => Some(KeyBinding::new(
+                KeyCode::Char(c.to_ascii_lowercase()),
+                self.modifiers,
+            )),
+            _ => None,
+        }
+    }
+
+    // shift is already reflected in the character itself (and in BackTab), so it
+    // is ignored for those keys to behave the same across terminals
+    fn matches(&self, event: &KeyEvent) -> bool {
+        let modifiers = match event.code {
+            KeyCode::Char(_) | KeyCode::BackTab => event.modifiers - KeyModifiers::SHIFT,
+            _ => event.modifiers,
+        };
+        self.code == event.code && self.modifiers == modifiers
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Keymap {
+    bindings: Vec<(KeyBinding, Action)>,
+}
+
+impl Keymap {
+    pub fn new(preset: KeymapPreset) -> Self {
+        let mut keymap = Keymap {
+            bindings: Vec::new(),
+        };
+
+        keymap.bind_common();
+
+        match preset {
+            KeymapPreset::Default => {
+                keymap.bind_chars(&['H', 'h'], Action::Hint);
+                keymap.bind_arrows();
+            }
+            KeymapPreset::Vim => {
+                keymap.bind(KeyBinding::char('H'), Action::Hint);
+                keymap.bind(KeyBinding::char('h'), Action::MoveLeft);
+                keymap.bind(KeyBinding::char('l'), Action::MoveRight);
+                keymap.bind(KeyBinding::char('k'), Action::MoveUp);
+                keymap.bind(KeyBinding::char('j'), Action::MoveDown);
+                keymap.bind(KeyBinding::char('x'), Action::Clear);
+                keymap.bind_arrows();
+            }
+            KeymapPreset::Numpad => {
+                keymap.bind_chars(&['H', 'h'], Action::Hint);
+                keymap.bind_chars(&['A', 'a'], Action::MoveLeft);
+                keymap.bind_chars(&['D', 'd'], Action::MoveRight);
+                keymap.bind_chars(&['W', 'w'], Action::MoveUp);
+                keymap.bind_chars(&['S', 's'], Action::MoveDown);
+                keymap.bind_chars(&['X', 'x'], Action::Clear);
+                keymap.bind_arrows();
+            }
+        }
+
+        keymap
+    }
+
+    pub fn bind(&mut self, key: KeyBinding, action: Action) {
+        // a key maps to a single action, rebinding replaces the old one
+        self.bindings.retain(|(k, _)| *k != key);
+        self.bindings.push((key, action));
+    }
+
+    // the key becomes the action's only one, whatever the preset gave it
+    pub fn rebind(&mut self, action: Action, key: KeyBinding) {
+        self.bindings.retain(|(_, a)| *a != action);
+        self.bind(key, action);
+    }
+
+    // the shortest way to write a key for the action, for the hints on
+    // screen, e.g. Del over Backspace
+    pub fn label(&self, action: Action) -> Option<String> {
+        self.bindings
+            .iter()
+            .filter(|(_, a)| *a == action)
+            .map(|(key, _)| key.to_string())
+            .min_by_key(|key| key.chars().count())
+    }
+
+    pub fn bindings(&self) -> &[(KeyBinding, Action)] {
+        &self.bindings
+    }
+
+    pub fn action(&self, event: &KeyEvent) -> Option<Action> {
+        if let Some((_, action)) = self.bindings.iter().find(|(key, _)| key.matches(event)) {
+            return Some(*action);
+        }
+
+        // digits are never rebound, they always insert into the selected cell
+        // (0 clears it), with Ctrl held jump to the box with that number and
+        // with Alt held pencil mark it
+        match event.code {
+            KeyCode::Char(c @ '1'..='9') if event.modifiers.contains(KeyModifiers::CONTROL) => {
+                Some(Action::JumpToBox(c as u8 - b'0'))
+            }
+            KeyCode::Char(c @ '1'..='9') if event.modifiers.contains(KeyModifiers::ALT) => {
+                Some(Action::Note(c as u8 - b'0'))
+            }
+            // shifted 1-5 pick a color, most terminals only report the
+            // symbol on a US layout
+            KeyCode::Char(c @ '1'..='5') if event.modifiers.contains(KeyModifiers::SHIFT) => {
+                Some(Action::Color(c as u8 - b'0'))
+            }
+            KeyCode::Char(c @ ('!' | '@' | '#' | '$' | '%')) => {
+                let slot = "!@#$%".find(c).unwrap_or_default() as u8 + 1;
+                Some(Action::Color(slot))
+            }
+            KeyCode::Char(c @ '1'..='9') => Some(Action::Insert(c as u8 - b'0')),
+            KeyCode::Char('0') => Some(Action::Clear),
+            _ => None,
+        }
+    }
+
+    // (keys, description) rows for every bound action, in binding order
+    pub fn help(&self) -> Vec<(String, &'static str)> {
+        let mut rows: Vec<(Action, Vec<String>)> = Vec::new();
+        for (key, action) in &self.bindings {
+            // "Q" next to "q" for the same action is just noise
+            if key
+                .lowercase()
+                .is_some_and(|lower| self.bindings.contains(&(lower, *action)))
+            {
+                continue;
+            }
+
+            match rows.iter_mut().find(|(a, _)| a == action) {
+                Some((_, keys)) => keys.push(key.to_string()),
+                None => rows.push((*action, vec![key.to_string()])),
+            }
+        }
+
+        let mut help: Vec<(String, &'static str)> = rows
+            .into_iter()
+            .map(|(action, keys)| (keys.join(", "), action.description()))
+            .collect();
+        help.push(("1-9".into(), Action::Insert(0).description()));
+        help.push(("0".into(), Action::Clear.description()));
+        help.push(("Ctrl+1-9".into(), Action::JumpToBox(0).description()));
+        help.push(("Alt+1-9".into(), Action::Note(0).description()));
+        help.push(("Shift+1-5".into(), Action::Color(0).description()));
+        help
+    }
+
+    fn bind_common(&mut self) {
+        self.bind_chars(&['Q', 'q'], Action::Quit);
+        self.bind_chars(&['N', 'n'], Action::NewGame);
+        self.bind_chars(&['R', 'r'], Action::Reset);
+        self.bind(KeyBinding::plain(KeyCode::Backspace), Action::Clear);
+        self.bind(KeyBinding::plain(KeyCode::Delete), Action::Clear);
+        self.bind(KeyBinding::plain(KeyCode::Tab), Action::NextEmpty);
+        self.bind(KeyBinding::plain(KeyCode::BackTab), Action::PrevEmpty);
+        self.bind_chars(&['B', 'b'], Action::BoxPrefix);
+        self.bind_chars(&['U', 'u'], Action::Undo);
+        self.bind(
+            KeyBinding::new(KeyCode::Char('r'), KeyModifiers::CONTROL),
+            Action::Redo,
+        );
+        self.bind_chars(&['P', 'p'], Action::Pause);
+        self.bind(KeyBinding::char('?'), Action::Help);
+        self.bind(KeyBinding::char(':'), Action::Command);
+        // shifted only, so it is hard to hit by accident
+        self.bind(KeyBinding::char('V'), Action::Reveal);
+        self.bind(KeyBinding::char('c'), Action::Check);
+        self.bind(KeyBinding::char('C'), Action::CheckAndClear);
+        self.bind(KeyBinding::char(')'), Action::ClearColor);
+        self.bind(KeyBinding::char('v'), Action::Select);
+        self.bind(KeyBinding::char('m'), Action::NotesMode);
+        self.bind(KeyBinding::char('y'), Action::Copy(false));
+        self.bind(KeyBinding::char('Y'), Action::Copy(true));
+        self.bind(KeyBinding::char('t'), Action::ToggleClock);
+        self.bind(
+            KeyBinding::new(KeyCode::Char('t'), KeyModifiers::CONTROL),
+            Action::NewTab,
+        );
+        // few terminals report Ctrl+Tab, Ctrl+PageDown works in the rest
+        self.bind(
+            KeyBinding::new(KeyCode::Tab, KeyModifiers::CONTROL),
+            Action::NextTab,
+        );
+        self.bind(
+            KeyBinding::new(KeyCode::PageDown, KeyModifiers::CONTROL),
+            Action::NextTab,
+        );
+    }
+
+    fn bind_chars(&mut self, chars: &[char], action: Action) {
+        for &c in chars {
+            self.bind(KeyBinding::char(c), action);
+        }
+    }
+
+    fn bind_arrows(&mut self) {
+        self.bind(KeyBinding::plain(KeyCode::Left), Action::MoveLeft);
+        self.bind(KeyBinding::plain(KeyCode::Right), Action::MoveRight);
+        self.bind(KeyBinding::plain(KeyCode::Up), Action::MoveUp);
+        self.bind(KeyBinding::plain(KeyCode::Down), Action::MoveDown);
+    }
+}
+
+impl fmt::Display for KeyBinding {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        if self.modifiers.contains(KeyModifiers::CONTROL) {
+            write!(f, "Ctrl+")?;
+        }
+        if self.modifiers.contains(KeyModifiers::ALT) {
+            write!(f, "Alt+")?;
+        }
+
+        match self.code {
+            KeyCode::Char(' ') => write!(f, "Space"),
+            KeyCode::Char(c) => write!(f, "{}", c),
+            KeyCode::Left => write!(f, "←"),
+            KeyCode::Right => write!(f, "→"),
+            KeyCode::Up => write!(f, "↑"),
+            KeyCode::Down => write!(f, "↓"),
+            KeyCode::BackTab => write!(f, "Shift-Tab"),
+            KeyCode::Backspace => write!(f, "Backspace"),
+            KeyCode::Delete => write!(f, "Del"),
+            KeyCode::F(n) => write!(f, "F{}", n),
+            code => write!(f, "{:?}", code),
+        }
+    }
+}
+
+// the way `Display` writes a key, as in u, Ctrl+r or PageDown, in any case
+impl FromStr for KeyBinding {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let mut modifiers = KeyModifiers::NONE;
+        let mut rest = s;
+        loop {
+            let lower = rest.to_lowercase();
+            if lower.starts_with("ctrl+") && rest.len() > 5 {
+                modifiers |= KeyModifiers::CONTROL;
+                rest = &rest[5..];
+            } else if lower.starts_with("alt+") && rest.len() > 4 {
+                modifiers |= KeyModifiers::ALT;
+                rest = &rest[4..];
+            } else {
+                break;
+            }
+        }
+
+        let mut chars = rest.chars();
+        let code = match (chars.next(), chars.next()) {
+            (Some(c), None) => KeyCode::Char(c),
+            _ => match rest.to_lowercase().as_str() {
+                "space" => KeyCode::Char(' '),
+                "left" | "←" => KeyCode::Left,
+                "right" | "→" => KeyCode::Right,
+                "up" | "↑" => KeyCode::Up,
+                "down" | "↓" => KeyCode::Down,
+                "tab" => KeyCode::Tab,
+                "shift-tab" | "backtab" => KeyCode::BackTab,
+                "backspace" => KeyCode::Backspace,
+                "del" | "delete" => KeyCode::Delete,
+                "enter" => KeyCode::Enter,
+                "esc" => KeyCode::Esc,
+                "home" => KeyCode::Home,
+                "end" => KeyCode::End,
+                "pageup" => KeyCode::PageUp,
+                "pagedown" => KeyCode::PageDown,
+                "insert" => KeyCode::Insert,
+                name => match name.strip_prefix('f').and_then(|n| n.parse().ok()) {
+                    Some(n @ 1..=12) => KeyCode::F(n),
+                    _ => return Err(format!("Invalid key: {}", s)),
+                },
+            },
+        };
+        Ok(KeyBinding::new(code, modifiers))
+    }
+}
+
+impl Default for Keymap {
+    fn default() -> Self {
+        Keymap::new(KeymapPreset::Default)
+    }
+}
+
+impl From<KeymapPreset> for Keymap {
+    fn from(preset: KeymapPreset) -> Self {
+        Keymap::new(preset)
+    }
+}
+
+impl FromStr for KeymapPreset {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.to_lowercase().as_str() {
+            "default" | "arrows" => Ok(KeymapPreset::Default),
+            "vim" => Ok(KeymapPreset::Vim),
+            "numpad" | "numpad-left-hand" => Ok(KeymapPreset::Numpad),
+            _ => Err(format!("Invalid keymap: {}", s)),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn press(code: KeyCode) -> KeyEvent {
+        KeyEvent::new(code, KeyModifiers::NONE)
+    }
+
+    #[test]
+    fn test_default_preset_uses_arrows() {
+        let keymap = Keymap::new(KeymapPreset::Default);
+        assert_eq!(keymap.action(&press(KeyCode::Left)), Some(Action::MoveLeft));
+        assert_eq!(
+            keymap.action(&press(KeyCode::Char('h'))),
+            Some(Action::Hint)
+        );
+    }
+
+    #[test]
+    fn test_vim_preset_moves_with_hjkl() {
+        let keymap = Keymap::new(KeymapPreset::Vim);
+        assert_eq!(
+            keymap.action(&press(KeyCode::Char('h'))),
+            Some(Action::MoveLeft)
+        );
+        assert_eq!(
+            keymap.action(&press(KeyCode::Char('j'))),
+            Some(Action::MoveDown)
+        );
+        assert_eq!(
+            keymap.action(&KeyEvent::new(KeyCode::Char('H'), KeyModifiers::SHIFT)),
+            Some(Action::Hint)
+        );
+    }
+
+    #[test]
+    fn test_numpad_preset_moves_with_wasd() {
+        let keymap = Keymap::new(KeymapPreset::Numpad);
+        assert_eq!(
+            keymap.action(&press(KeyCode::Char('w'))),
+            Some(Action::MoveUp)
+        );
+        assert_eq!(
+            keymap.action(&press(KeyCode::Char('x'))),
+            Some(Action::Clear)
+        );
+    }
+
+    #[test]
+    fn test_shifted_digits_pick_a_color() {
+        let keymap = Keymap::default();
+        assert_eq!(
+            keymap.action(&press(KeyCode::Char('#'))),
+            Some(Action::Color(3))
+        );
+        assert_eq!(
+            keymap.action(&KeyEvent::new(KeyCode::Char('5'), KeyModifiers::SHIFT)),
+            Some(Action::Color(5))
+        );
+        assert_eq!(
+            keymap.action(&KeyEvent::new(KeyCode::Char('6'), KeyModifiers::SHIFT)),
+            Some(Action::Insert(6))
+        );
+    }
+
+    #[test]
+    fn test_digits_insert_in_every_preset() {
+        for preset in [
+            KeymapPreset::Default,
+            KeymapPreset::Vim,
+            KeymapPreset::Numpad,
+        ] {
+            let keymap = Keymap::new(preset);
+            assert_eq!(
+                keymap.action(&press(KeyCode::Char('7'))),
+                Some(Action::Insert(7))
+            );
+        }
+    }
+
+    #[test]
+    fn test_shift_tab_jumps_to_previous_empty() {
+        let keymap = Keymap::default();
+        assert_eq!(keymap.action(&press(KeyCode::Tab)), Some(Action::NextEmpty));
+        assert_eq!(
+            keymap.action(&KeyEvent::new(KeyCode::BackTab, KeyModifiers::SHIFT)),
+            Some(Action::PrevEmpty)
+        );
+    }
+
+    #[test]
+    fn test_ctrl_r_redoes_while_r_resets() {
+        let keymap = Keymap::default();
+        assert_eq!(
+            keymap.action(&KeyEvent::new(KeyCode::Char('r'), KeyModifiers::CONTROL)),
+            Some(Action::Redo)
+        );
+        assert_eq!(
+            keymap.action(&press(KeyCode::Char('r'))),
+            Some(Action::Reset)
+        );
+        assert_eq!(
+            keymap.action(&press(KeyCode::Char('u'))),
+            Some(Action::Undo)
+        );
+    }
+
+    #[test]
+    fn test_ctrl_tab_switches_tabs_while_tab_moves() {
+        let keymap = Keymap::new(KeymapPreset::Vim);
+        assert_eq!(
+            keymap.action(&KeyEvent::new(KeyCode::Tab, KeyModifiers::CONTROL)),
+            Some(Action::NextTab)
+        );
+        assert_eq!(keymap.action(&press(KeyCode::Tab)), Some(Action::NextEmpty));
+        assert_eq!(
+            keymap.action(&KeyEvent::new(KeyCode::Char('t'), KeyModifiers::CONTROL)),
+            Some(Action::NewTab)
+        );
+    }
+
+    #[test]
+    fn test_rebinding_replaces_previous_action() {
+        let mut keymap = Keymap::default();
+        keymap.bind(KeyBinding::char('z'), Action::Undo);
+        keymap.bind(KeyBinding::char('u'), Action::Redo);
+        assert_eq!(
+            keymap.action(&press(KeyCode::Char('z'))),
+            Some(Action::Undo)
+        );
+        assert_eq!(
+            keymap.action(&press(KeyCode::Char('u'))),
+            Some(Action::Redo)
+        );
+    }
+
+    #[test]
+    fn test_override_replaces_the_preset_binding() {
+        let mut keymap = Keymap::new(KeymapPreset::Vim);
+        keymap.rebind("undo".parse().unwrap(), "z".parse().unwrap());
+        keymap.rebind(Action::Redo, "Ctrl+y".parse().unwrap());
+        assert_eq!(
+            keymap.action(&press(KeyCode::Char('z'))),
+            Some(Action::Undo)
+        );
+        assert_eq!(keymap.action(&press(KeyCode::Char('u'))), None);
+        assert_eq!(
+            keymap.action(&KeyEvent::new(KeyCode::Char('y'), KeyModifiers::CONTROL)),
+            Some(Action::Redo)
+        );
+        assert_eq!(
+            keymap.action(&KeyEvent::new(KeyCode::Char('r'), KeyModifiers::CONTROL)),
+            None
+        );
+        assert!("undo_all".parse::<Action>().is_err());
+    }
+
+    #[test]
+    fn test_keys_parse_the_way_they_print() {
+        for key in [
+            "q",
+            "Ctrl+r",
+            "Del",
+            "Shift-Tab",
+            "←",
+            "Space",
+            "Alt+x",
+            "F5",
+        ] {
+            assert_eq!(key.parse::<KeyBinding>().unwrap().to_string(), key);
+        }
+        assert_eq!(
+            "ctrl+PageDown".parse(),
+            Ok(KeyBinding::new(KeyCode::PageDown, KeyModifiers::CONTROL))
+        );
+        assert_eq!("F5".parse(), Ok(KeyBinding::plain(KeyCode::F(5))));
+        assert!("F13".parse::<KeyBinding>().is_err());
+        assert!("ctrl+".parse::<KeyBinding>().is_err());
+    }
+
+    #[test]
+    fn test_labels_follow_the_preset() {
+        let keymap = Keymap::default();
+        assert_eq!(keymap.label(Action::Quit).as_deref(), Some("Q"));
+        assert_eq!(keymap.label(Action::Clear).as_deref(), Some("Del"));
+        assert_eq!(
+            Keymap::new(KeymapPreset::Vim)
+                .label(Action::Hint)
+                .as_deref(),
+            Some("H")
+        );
+        let mut keymap = Keymap::new(KeymapPreset::Numpad);
+        keymap.rebind(Action::Quit, KeyBinding::plain(KeyCode::F(10)));
+        assert_eq!(keymap.label(Action::Quit).as_deref(), Some("F10"));
+        assert_eq!(keymap.label(Action::Clear).as_deref(), Some("X"));
+    }
+
+    #[test]
+    fn test_zero_clears() {
+        let keymap = Keymap::new(KeymapPreset::Vim);
+        assert_eq!(
+            keymap.action(&press(KeyCode::Char('0'))),
+            Some(Action::Clear)
+        );
+    }
+
+    #[test]
+    fn test_alt_digit_and_m_take_notes() {
+        let keymap = Keymap::new(KeymapPreset::Numpad);
+        assert_eq!(
+            keymap.action(&KeyEvent::new(KeyCode::Char('4'), KeyModifiers::ALT)),
+            Some(Action::Note(4))
+        );
+        assert_eq!(
+            keymap.action(&press(KeyCode::Char('m'))),
+            Some(Action::NotesMode)
+        );
+    }
+
+    #[test]
+    fn test_ctrl_digit_jumps_to_box() {
+        let keymap = Keymap::default();
+        assert_eq!(
+            keymap.action(&KeyEvent::new(KeyCode::Char('5'), KeyModifiers::CONTROL)),
+            Some(Action::JumpToBox(5))
+        );
+        assert_eq!(
+            keymap.action(&press(KeyCode::Char('b'))),
+            Some(Action::BoxPrefix)
+        );
+    }
+
+    #[test]
+    fn test_help_lists_every_bound_action_once() {
+        let help = Keymap::default().help();
+        let quit = help.iter().find(|(_, d)| *d == "Quit").unwrap();
+        assert_eq!(quit.0, "q");
+
+        let redo = help.iter().find(|(_, d)| *d == "Redo").unwrap();
+        assert_eq!(redo.0, "Ctrl+r");
+
+        let clear = help
+            .iter()
+            .find(|(_, d)| *d == "Clear the selected cell")
+            .unwrap();
+        assert_eq!(clear.0, "Backspace, Del");
+    }
+
+    #[test]
+    fn test_help_follows_preset() {
+        let help = Keymap::new(KeymapPreset::Vim).help();
+        assert!(help.contains(&("h, ←".into(), "Move left")));
+        assert!(help.contains(&("H".into(), "Hint the selected or next unsolved cell")));
+    }
+
+    #[test]
+    fn test_preset_from_str() {
+        assert_eq!("vim".parse(), Ok(KeymapPreset::Vim));
+        assert_eq!("Arrows".parse(), Ok(KeymapPreset::Default));
+        assert_eq!("numpad-left-hand".parse(), Ok(KeymapPreset::Numpad));
+        assert!("emacs".parse::<KeymapPreset>().is_err());
+    }
+}
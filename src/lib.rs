@@ -1,5 +1,127 @@
+// without the std feature only the grid, its validation, the solver and
+// canonical forms are built, on `alloc`, for embedded and other small targets
+#![cfg_attr(not(any(feature = "std", test)), no_std)]
+
+extern crate alloc;
+
+#[cfg(feature = "tui")]
+mod achievements;
+#[cfg(feature = "tui")]
+mod analytics;
+#[cfg(feature = "tui")]
 mod app;
+#[cfg(feature = "tui")]
+mod board;
+#[cfg(feature = "tui")]
+mod calendar;
+mod canonical;
+#[cfg(feature = "tui")]
+mod celebration;
+#[cfg(any(feature = "tui", feature = "sync", feature = "net"))]
+mod clipboard;
+#[cfg(feature = "tui")]
+mod command;
+#[cfg(feature = "tui")]
+mod config;
+#[cfg(feature = "tui")]
+mod coords;
+mod daily;
+#[cfg(feature = "tui")]
+mod dialog;
+#[cfg(feature = "tui")]
+mod events;
+#[cfg(feature = "std")]
+mod export;
+#[cfg(feature = "ffi")]
+mod ffi;
+#[cfg(feature = "graphics")]
+mod graphics;
+#[cfg(any(feature = "sync", feature = "net"))]
+mod http;
+#[cfg(feature = "tui")]
+mod i18n;
+#[cfg(feature = "tui")]
+mod keymap;
+#[cfg(feature = "tui")]
+mod marathon;
+#[cfg(feature = "tui")]
+mod menu;
+#[cfg(feature = "tui")]
+mod msg;
+mod observer;
+#[cfg(feature = "image")]
+mod png;
 mod puzzle;
+#[cfg(feature = "python")]
+mod python;
+#[cfg(any(feature = "graphics", feature = "image"))]
+mod raster;
+#[cfg(feature = "tui")]
+mod replay;
+#[cfg(feature = "tui")]
+mod save;
+mod solver;
+#[cfg(feature = "std")]
+mod source;
+#[cfg(feature = "tui")]
+mod splits;
+#[cfg(feature = "tui")]
+mod stats;
+#[cfg(feature = "tui")]
+mod stopwatch;
+#[cfg(feature = "sync")]
+mod sync;
+#[cfg(feature = "tui")]
+mod toast;
+#[cfg(feature = "tui")]
+mod tty;
+#[cfg(feature = "tui")]
+mod tutorial;
+#[cfg(feature = "net")]
+mod web;
 
+#[cfg(feature = "tui")]
+pub use achievements::Achievement;
+#[cfg(feature = "tui")]
 pub use app::App;
-pub use puzzle::{Difficulty, Puzzle};
+#[cfg(feature = "tui")]
+pub use board::{CellStyler, SudokuGridWidget, Theme};
+pub use canonical::{canonical_form, puzzle_id};
+#[cfg(feature = "tui")]
+pub use command::{Command, NewGame, Prompt};
+#[cfg(feature = "tui")]
+pub use config::{config_path, Config};
+#[cfg(feature = "tui")]
+pub use coords::Labels;
+pub use daily::{daily_puzzle, format_day};
+#[cfg(feature = "async")]
+pub use events::StreamEvents;
+#[cfg(feature = "tui")]
+pub use events::{EventSource, ScriptedEvents, TerminalEvents};
+#[cfg(feature = "std")]
+pub use export::{ExportFormat, HtmlGrid, SvgGrid, TextGrid};
+#[cfg(feature = "image")]
+pub use export::{ImageTheme, PngGrid};
+#[cfg(feature = "tui")]
+pub use i18n::Lang;
+#[cfg(feature = "tui")]
+pub use keymap::{Action, KeyBinding, Keymap, KeymapPreset};
+#[cfg(feature = "tui")]
+pub use msg::Msg;
+pub use observer::{PuzzleEvent, PuzzleObserver};
+pub use puzzle::{
+    Cell, CellChange, Difficulty, Grid, Move, MoveError, Puzzle, MAX_CLUES, MIN_CLUES,
+};
+pub use solver::{next_step, rate, Rating, Step, Technique};
+#[cfg(feature = "std")]
+pub use source::{Generator, PuzzlePack, PuzzleSource};
+#[cfg(feature = "tui")]
+pub use splits::SplitKind;
+#[cfg(feature = "tui")]
+pub use stats::{data_dir, today, DailySolve, Stats, UnlockedAchievement};
+#[cfg(feature = "sync")]
+pub use sync::Remote;
+#[cfg(feature = "tui")]
+pub use tty::install_hooks;
+#[cfg(feature = "net")]
+pub use web::WebSource;
@@ -0,0 +1,52 @@
+use std::time::Duration;
+
+// puzzles solved back to back in one session, a new one starting as soon as
+// the last is done
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub(crate) struct Marathon {
+    solved: usize,
+    total: Duration,
+    fastest: Option<Duration>,
+}
+
+impl Marathon {
+    pub(crate) fn record(&mut self, time: Duration) {
+        self.solved += 1;
+        self.total += time;
+        self.fastest = Some(self.fastest.map_or(time, |fastest| fastest.min(time)));
+    }
+
+    pub(crate) fn solved(&self) -> usize {
+        self.solved
+    }
+
+    pub(crate) fn total(&self) -> Duration {
+        self.total
+    }
+
+    pub(crate) fn fastest(&self) -> Option<Duration> {
+        self.fastest
+    }
+
+    pub(crate) fn average(&self) -> Option<Duration> {
+        (self.solved > 0).then(|| self.total / self.solved as u32)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_marathon_adds_up_solves() {
+        let mut marathon = Marathon::default();
+        assert_eq!(marathon.average(), None);
+
+        marathon.record(Duration::from_secs(300));
+        marathon.record(Duration::from_secs(100));
+        assert_eq!(marathon.solved(), 2);
+        assert_eq!(marathon.total(), Duration::from_secs(400));
+        assert_eq!(marathon.fastest(), Some(Duration::from_secs(100)));
+        assert_eq!(marathon.average(), Some(Duration::from_secs(200)));
+    }
+}
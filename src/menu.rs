@@ -0,0 +1,332 @@
+use ratatui::{
+    buffer::Buffer,
+    layout::{Alignment, Constraint, Flex, Layout, Rect},
+    style::Stylize,
+    symbols::border,
+    text::Line,
+    widgets::{
+        block::{Position, Title},
+        Block, Paragraph, Widget,
+    },
+};
+
+use crate::{
+    dialog::Dialog,
+    i18n::Lang,
+    puzzle::{MAX_CLUES, MIN_CLUES},
+    Difficulty,
+};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub(crate) enum MenuItem {
+    Difficulty(Difficulty),
+    Custom,
+    Resume,
+    Tutorial,
+    Calendar,
+    Statistics,
+    Achievements,
+}
+
+const NEW_GAME_ITEMS: [MenuItem; 5] = [
+    MenuItem::Difficulty(Difficulty::Easy),
+    MenuItem::Difficulty(Difficulty::Medium),
+    MenuItem::Difficulty(Difficulty::Hard),
+    MenuItem::Difficulty(Difficulty::Expert),
+    MenuItem::Custom,
+];
+
+const DEFAULT_CUSTOM_CLUES: usize = 30;
+// the main menu's letters for the screens it opens, in either case, with
+// the label naming each
+pub(crate) const SHORTCUTS: [(char, &str); 3] = [
+    ('s', "statistics"),
+    ('a', "achievements"),
+    ('l', "saved_games"),
+];
+
+// picks what kind of game to start, either at startup or from a running game
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub(crate) struct Menu {
+    items: Vec<MenuItem>,
+    selected: usize,
+    custom_clues: usize,
+    can_resume: bool,
+    is_dialog: bool, // shown over a running game, which Esc returns to
+    lang: Lang,
+    quit_key: String,     // as the keymap writes it
+    shortcuts: Vec<char>, // the letter shortcuts the keymap leaves free
+}
+
+impl Menu {
+    pub(crate) fn new(can_resume: bool) -> Self {
+        let mut items = NEW_GAME_ITEMS.to_vec();
+        items.push(MenuItem::Resume);
+        items.push(MenuItem::Tutorial);
+        items.push(MenuItem::Calendar);
+        items.push(MenuItem::Statistics);
+        items.push(MenuItem::Achievements);
+
+        // a game left unfinished is what is most likely wanted, then medium
+        let resume = items.iter().position(|&item| item == MenuItem::Resume);
+        Menu {
+            selected: resume.filter(|_| can_resume).unwrap_or(1),
+            items,
+            custom_clues: DEFAULT_CUSTOM_CLUES,
+            can_resume,
+            is_dialog: false,
+            lang: Lang::default(),
+            quit_key: "Q".into(),
+            shortcuts: SHORTCUTS.iter().map(|&(c, _)| c).collect(),
+        }
+    }
+
+    // preselect a difficulty other than medium, unless there is a game to
+    // resume
+    pub(crate) fn with_difficulty(mut self, level: Difficulty) -> Self {
+        let item = MenuItem::Difficulty(level);
+        if !self.can_resume {
+            self.selected = self.items.iter().position(|&i| i == item).unwrap_or(1);
+        }
+        self
+    }
+
+    pub(crate) fn with_lang(mut self, lang: Lang) -> Self {
+        self.lang = lang;
+        self
+    }
+
+    pub(crate) fn with_quit_key(mut self, key: String) -> Self {
+        self.quit_key = key;
+        self
+    }
+
+    // only these of `SHORTCUTS` are named at the bottom
+    pub(crate) fn with_shortcuts(mut self, shortcuts: Vec<char>) -> Self {
+        self.shortcuts = shortcuts;
+        self
+    }
+
+    // the in-game new game dialog, preselecting what is currently played
+    pub(crate) fn new_game(level: Difficulty, custom_clues: Option<usize>) -> Self {
+        let current = match custom_clues {
+            Some(_) => MenuItem::Custom,
+            None => MenuItem::Difficulty(level),
+        };
+
+        Menu {
+            items: NEW_GAME_ITEMS.to_vec(),
+            selected: NEW_GAME_ITEMS
+                .iter()
+                .position(|&item| item == current)
+                .unwrap_or_default(),
+            custom_clues: custom_clues.unwrap_or(DEFAULT_CUSTOM_CLUES),
+            can_resume: false,
+            is_dialog: true,
+            lang: Lang::default(),
+            quit_key: "Q".into(),
+            shortcuts: Vec::new(),
+        }
+    }
+
+    pub(crate) fn selected(&self) -> MenuItem {
+        self.items[self.selected]
+    }
+
+    pub(crate) fn custom_clues(&self) -> usize {
+        self.custom_clues
+    }
+
+    pub(crate) fn is_enabled(&self, item: MenuItem) -> bool {
+        item != MenuItem::Resume || self.can_resume
+    }
+
+    pub(crate) fn up(&mut self) {
+        let len = self.items.len();
+        self.selected = (self.selected + len - 1) % len;
+        if !self.is_enabled(self.selected()) {
+            self.up();
+        }
+    }
+
+    pub(crate) fn down(&mut self) {
+        self.selected = (self.selected + 1) % self.items.len();
+        if !self.is_enabled(self.selected()) {
+            self.down();
+        }
+    }
+
+    // left/right tune the clue count while custom is selected
+    pub(crate) fn less(&mut self) {
+        if self.selected() == MenuItem::Custom {
+            self.custom_clues = self.custom_clues.saturating_sub(1).max(MIN_CLUES);
+        }
+    }
+
+    pub(crate) fn more(&mut self) {
+        if self.selected() == MenuItem::Custom {
+            self.custom_clues = (self.custom_clues + 1).min(MAX_CLUES);
+        }
+    }
+
+    fn label(&self, item: MenuItem) -> String {
+        let lang = self.lang;
+        match item {
+            MenuItem::Difficulty(difficulty) => lang.difficulty(difficulty).into(),
+            MenuItem::Custom => lang.format("custom", &[&self.custom_clues]),
+            MenuItem::Resume if self.can_resume => lang.tr("resume").into(),
+            MenuItem::Resume => lang.tr("resume_none").into(),
+            MenuItem::Tutorial => lang.tr("tutorial").into(),
+            MenuItem::Calendar => lang.tr("daily_calendar").into(),
+            MenuItem::Statistics => lang.tr("statistics").into(),
+            MenuItem::Achievements => lang.tr("achievements").into(),
+        }
+    }
+}
+
+impl Widget for &Menu {
+    fn render(self, area: Rect, buf: &mut Buffer) {
+        let lines: Vec<Line> = self
+            .items
+            .iter()
+            .enumerate()
+            .map(|(i, &item)| {
+                let label = self.label(item);
+                if i == self.selected {
+                    Line::from(format!("> {} <", label).blue().bold())
+                } else if self.is_enabled(item) {
+                    Line::from(label)
+                } else {
+                    Line::from(label.dark_gray())
+                }
+            })
+            .collect();
+
+        if self.is_dialog {
+            return Dialog::new(format!(" {} ", self.lang.tr("new_game")))
+                .body(lines)
+                .button(self.lang.tr("cancel"), "Esc")
+                .button(self.lang.tr("select"), "Enter")
+                .render(area, buf);
+        }
+
+        let label = |key| format!(" {} ", self.lang.tr(key)).into();
+        let mut hints = vec![label("quit"), format!("<{}> ", self.quit_key).blue().bold()];
+        for (c, key) in SHORTCUTS {
+            if self.shortcuts.contains(&c) {
+                hints.push(label(key));
+                hints.push(format!("<{}> ", c.to_ascii_uppercase()).blue().bold());
+            }
+        }
+        hints.extend([label("select"), "<Enter> ".blue().bold()]);
+        let instructions = Title::from(Line::from(hints));
+        let block = Block::bordered()
+            .title(Title::from(" Sudoku ".bold()).alignment(Alignment::Center))
+            .title(instructions.position(Position::Bottom))
+            .border_set(border::THICK);
+
+        let inner_area = block.inner(area);
+        block.render(area, buf);
+
+        let [menu_area] = Layout::vertical([Constraint::Length(self.items.len() as u16)])
+            .flex(Flex::Center)
+            .areas(inner_area);
+        Paragraph::new(lines).centered().render(menu_area, buf);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_navigation_skips_resume_without_save() {
+        let mut menu = Menu::new(false);
+        menu.down(); // hard
+        menu.down(); // expert
+        menu.down(); // custom
+        assert_eq!(menu.selected(), MenuItem::Custom);
+        menu.down();
+        assert_eq!(menu.selected(), MenuItem::Tutorial);
+        menu.up();
+        assert_eq!(menu.selected(), MenuItem::Custom);
+    }
+
+    #[test]
+    fn test_saved_game_is_preselected() {
+        let mut menu = Menu::new(true);
+        assert_eq!(menu.selected(), MenuItem::Resume);
+        menu.up();
+        assert_eq!(menu.selected(), MenuItem::Custom);
+        menu.down();
+        assert_eq!(menu.selected(), MenuItem::Resume);
+    }
+
+    #[test]
+    fn test_default_difficulty_is_preselected() {
+        let menu = Menu::new(false).with_difficulty(Difficulty::Expert);
+        assert_eq!(menu.selected(), MenuItem::Difficulty(Difficulty::Expert));
+        let menu = Menu::new(true).with_difficulty(Difficulty::Expert);
+        assert_eq!(menu.selected(), MenuItem::Resume);
+    }
+
+    #[test]
+    fn test_new_game_dialog_preselects_current_game() {
+        let menu = Menu::new_game(Difficulty::Hard, None);
+        assert!(menu.is_dialog);
+        assert_eq!(menu.selected(), MenuItem::Difficulty(Difficulty::Hard));
+
+        let menu = Menu::new_game(Difficulty::Easy, Some(45));
+        assert_eq!(menu.selected(), MenuItem::Custom);
+        assert_eq!(menu.custom_clues(), 45);
+        assert!(!menu.items.contains(&MenuItem::Resume));
+    }
+
+    #[test]
+    fn test_custom_clues_stay_in_range() {
+        let mut menu = Menu::new(false);
+        menu.more(); // not on custom, ignored
+        assert_eq!(menu.custom_clues(), DEFAULT_CUSTOM_CLUES);
+
+        menu.up();
+        menu.up();
+        menu.up();
+        menu.up();
+        menu.up();
+        menu.up(); // wraps past achievements, statistics, the calendar and the tutorial to custom
+        for _ in 0..100 {
+            menu.less();
+        }
+        assert_eq!(menu.custom_clues(), MIN_CLUES);
+    }
+
+    #[test]
+    fn test_vim_letters_move_and_shortcuts_take_the_other_case() {
+        use crate::{events::ScriptedEvents, App, Keymap, KeymapPreset};
+
+        let mut app = App::new_with_menu().with_keymap(Keymap::new(KeymapPreset::Vim));
+        // medium, then down to custom and l raises its clue count
+        let screen = app
+            .run_headless(&mut ScriptedEvents::keys("jjjl"), 100, 30)
+            .unwrap();
+        assert!(screen.contains("Custom  ◀ 31 clues ▶"));
+        assert!(screen.contains("<L>"));
+
+        let screen = app
+            .run_headless(&mut ScriptedEvents::keys("L"), 100, 30)
+            .unwrap();
+        assert!(screen.contains("No saved games yet"));
+    }
+
+    #[test]
+    fn test_shortcuts_the_keymap_moves_with_are_not_named() {
+        use crate::{events::ScriptedEvents, App, Keymap, KeymapPreset};
+
+        let mut app = App::new_with_menu().with_keymap(Keymap::new(KeymapPreset::Numpad));
+        let screen = app
+            .run_headless(&mut ScriptedEvents::keys(""), 100, 30)
+            .unwrap();
+        assert!(!screen.contains("<S>"));
+        assert!(screen.contains("<L>"));
+    }
+}
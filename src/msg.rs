@@ -0,0 +1,57 @@
+use crossterm::event::{Event, KeyEvent, KeyEventKind, MouseEvent};
+
+// everything that changes the game, input and the passing of time alike, all
+// handled by `App::update`
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Msg {
+    Key(KeyEvent),
+    Paste(String),
+    Mouse(MouseEvent),
+    // the layout is worked out from the frame on every draw, so this only
+    // asks for a redraw
+    Resize(u16, u16),
+    // TICK_RATE has passed
+    Tick,
+}
+
+impl Msg {
+    // the message for a terminal event, None for the ones the game ignores
+    // like key releases
+    pub fn from_event(event: Event) -> Option<Msg> {
+        match event {
+            Event::Key(key_event) if key_event.kind == KeyEventKind::Press => {
+                Some(Msg::Key(key_event))
+            }
+            Event::Paste(text) => Some(Msg::Paste(text)),
+            Event::Mouse(mouse_event) => Some(Msg::Mouse(mouse_event)),
+            Event::Resize(width, height) => Some(Msg::Resize(width, height)),
+            _ => None,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{App, Difficulty, ScriptedEvents};
+    use crossterm::event::{KeyCode, KeyEventState, KeyModifiers};
+
+    #[test]
+    fn test_messages_drive_the_app() {
+        let release = KeyEvent {
+            kind: KeyEventKind::Release,
+            state: KeyEventState::NONE,
+            ..KeyEvent::new(KeyCode::Char('p'), KeyModifiers::NONE)
+        };
+        assert_eq!(Msg::from_event(Event::Key(release)), None);
+
+        let mut app = App::new(Difficulty::Easy);
+        let press = Event::Key(KeyEvent::new(KeyCode::Char('p'), KeyModifiers::NONE));
+        app.update(Msg::from_event(press).unwrap());
+        app.update(Msg::Tick);
+        let screen = app
+            .run_headless(&mut ScriptedEvents::default(), 80, 30)
+            .unwrap();
+        assert!(screen.contains("Paused"));
+    }
+}
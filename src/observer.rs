@@ -0,0 +1,196 @@
+use alloc::{boxed::Box, vec::Vec};
+use core::fmt;
+
+// a change to a puzzle's numbers, rows and columns counting from 0
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum PuzzleEvent {
+    // a number went in, over the one there before if any
+    Insert {
+        row: usize,
+        col: usize,
+        num: u8,
+    },
+    Clear {
+        row: usize,
+        col: usize,
+    },
+    // the number just put in at row, col is also in each of the `with` cells
+    Conflict {
+        row: usize,
+        col: usize,
+        with: Vec<(usize, usize)>,
+    },
+    Solved,
+}
+
+// told about every change to a puzzle it watches, see `Puzzle::observe`, so
+// it can react without comparing the whole grid after each move; each
+// method does nothing unless overridden
+pub trait PuzzleObserver {
+    fn on_insert(&mut self, _row: usize, _col: usize, _num: u8) {}
+
+    fn on_clear(&mut self, _row: usize, _col: usize) {}
+
+    fn on_conflict(&mut self, _row: usize, _col: usize, _with: &[(usize, usize)]) {}
+
+    fn on_solved(&mut self) {}
+}
+
+// an observer that passes each event on, for another thread to take them
+#[cfg(feature = "std")]
+impl PuzzleObserver for std::sync::mpsc::Sender<PuzzleEvent> {
+    fn on_insert(&mut self, row: usize, col: usize, num: u8) {
+        let _ = self.send(PuzzleEvent::Insert { row, col, num });
+    }
+
+    fn on_clear(&mut self, row: usize, col: usize) {
+        let _ = self.send(PuzzleEvent::Clear { row, col });
+    }
+
+    fn on_conflict(&mut self, row: usize, col: usize, with: &[(usize, usize)]) {
+        let with = with.to_vec();
+        let _ = self.send(PuzzleEvent::Conflict { row, col, with });
+    }
+
+    fn on_solved(&mut self) {
+        let _ = self.send(PuzzleEvent::Solved);
+    }
+}
+
+// the observers of one puzzle; a copy of the puzzle starts out unwatched and
+// two puzzles compare equal whoever watches them
+#[derive(Default)]
+pub(crate) struct Observers(Vec<Box<dyn PuzzleObserver + Send>>);
+
+impl Observers {
+    pub(crate) fn push(&mut self, observer: Box<dyn PuzzleObserver + Send>) {
+        self.0.push(observer);
+    }
+
+    pub(crate) fn is_empty(&self) -> bool {
+        self.0.is_empty()
+    }
+
+    pub(crate) fn notify(&mut self, event: &PuzzleEvent) {
+        for observer in &mut self.0 {
+            match event {
+                PuzzleEvent::Insert { row, col, num } => observer.on_insert(*row, *col, *num),
+                PuzzleEvent::Clear { row, col } => observer.on_clear(*row, *col),
+                PuzzleEvent::Conflict { row, col, with } => observer.on_conflict(*row, *col, with),
+                PuzzleEvent::Solved => observer.on_solved(),
+            }
+        }
+    }
+}
+
+impl Clone for Observers {
+    fn clone(&self) -> Self {
+        Observers::default()
+    }
+}
+
+impl PartialEq for Observers {
+    fn eq(&self, _: &Self) -> bool {
+        true
+    }
+}
+
+impl Eq for Observers {}
+
+impl fmt::Debug for Observers {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{} observers", self.0.len())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{Difficulty, Puzzle};
+    use std::sync::{Arc, Mutex};
+
+    #[derive(Default, Clone)]
+    struct Recorder(Arc<Mutex<Vec<PuzzleEvent>>>);
+
+    impl Recorder {
+        fn take(&self) -> Vec<PuzzleEvent> {
+            std::mem::take(&mut self.0.lock().unwrap())
+        }
+    }
+
+    impl PuzzleObserver for Recorder {
+        fn on_insert(&mut self, row: usize, col: usize, num: u8) {
+            self.0
+                .lock()
+                .unwrap()
+                .push(PuzzleEvent::Insert { row, col, num });
+        }
+
+        fn on_clear(&mut self, row: usize, col: usize) {
+            self.0.lock().unwrap().push(PuzzleEvent::Clear { row, col });
+        }
+
+        fn on_conflict(&mut self, row: usize, col: usize, with: &[(usize, usize)]) {
+            let with = with.to_vec();
+            self.0
+                .lock()
+                .unwrap()
+                .push(PuzzleEvent::Conflict { row, col, with });
+        }
+
+        fn on_solved(&mut self) {
+            self.0.lock().unwrap().push(PuzzleEvent::Solved);
+        }
+    }
+
+    #[test]
+    fn test_observers_hear_each_change() {
+        let mut puzzle = Puzzle::new(Difficulty::Easy);
+        let recorder = Recorder::default();
+        puzzle.observe(recorder.clone());
+
+        // a number already in the row
+        let (row, col) = puzzle.next_empty_cell(0, 0).unwrap();
+        let (other, num) = (0..9)
+            .map(|c| (c, puzzle.grid()[row][c].value()))
+            .find(|&(_, num)| num != 0)
+            .unwrap();
+        puzzle.insert_number(row, col, num).unwrap();
+        let with = puzzle.conflicts(row, col);
+        assert!(with.contains(&(row, other)));
+        puzzle.clear_cell(row, col).unwrap();
+        assert_eq!(
+            recorder.take(),
+            [
+                PuzzleEvent::Insert { row, col, num },
+                PuzzleEvent::Conflict { row, col, with },
+                PuzzleEvent::Clear { row, col },
+            ]
+        );
+
+        while let Some((row, col)) = puzzle.next_empty_cell(0, 0) {
+            puzzle.hint(row, col).unwrap();
+        }
+        let events = recorder.take();
+        assert_eq!(events.last(), Some(&PuzzleEvent::Solved));
+        assert_eq!(events.len(), 81 - puzzle.clues() + 1);
+
+        let last = match events[events.len() - 2] {
+            PuzzleEvent::Insert { row, col, .. } => (row, col),
+            ref event => panic!("{:?}", event),
+        };
+        puzzle.undo();
+        assert_eq!(
+            recorder.take(),
+            [PuzzleEvent::Clear {
+                row: last.0,
+                col: last.1
+            }]
+        );
+
+        let mut copy = puzzle.clone();
+        copy.redo();
+        assert!(copy.is_solved());
+        assert!(recorder.take().is_empty());
+    }
+}
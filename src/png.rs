@@ -0,0 +1,97 @@
+// a minimal png encoder: 8-bit rgb, no filtering and deflate's stored blocks,
+// larger than a compressing encoder's output but needing no dependencies
+
+const SIGNATURE: [u8; 8] = [0x89, b'P', b'N', b'G', b'\r', b'\n', 0x1a, b'\n'];
+// the most a stored deflate block can hold
+const MAX_BLOCK: usize = 65535;
+
+fn crc32(data: &[u8]) -> u32 {
+    let mut crc = !0u32;
+    for &byte in data {
+        crc ^= byte as u32;
+        for _ in 0..8 {
+            crc = if crc & 1 == 1 {
+                (crc >> 1) ^ 0xedb8_8320
+            } else {
+                crc >> 1
+            };
+        }
+    }
+    !crc
+}
+
+fn adler32(data: &[u8]) -> u32 {
+    let (mut a, mut b) = (1u32, 0u32);
+    for &byte in data {
+        a = (a + byte as u32) % 65521;
+        b = (b + a) % 65521;
+    }
+    (b << 16) | a
+}
+
+fn chunk(out: &mut Vec<u8>, kind: &[u8; 4], data: &[u8]) {
+    out.extend_from_slice(&(data.len() as u32).to_be_bytes());
+    let start = out.len();
+    out.extend_from_slice(kind);
+    out.extend_from_slice(data);
+    let crc = crc32(&out[start..]);
+    out.extend_from_slice(&crc.to_be_bytes());
+}
+
+// a zlib stream holding `data` uncompressed
+fn zlib_stored(data: &[u8]) -> Vec<u8> {
+    let mut out = vec![0x78, 0x01];
+    let blocks: Vec<&[u8]> = data.chunks(MAX_BLOCK).collect();
+    for (i, block) in blocks.iter().enumerate() {
+        let is_last = (i + 1 == blocks.len()) as u8;
+        let len = block.len() as u16;
+        out.push(is_last);
+        out.extend_from_slice(&len.to_le_bytes());
+        out.extend_from_slice(&(!len).to_le_bytes());
+        out.extend_from_slice(block);
+    }
+    out.extend_from_slice(&adler32(data).to_be_bytes());
+    out
+}
+
+// rgb pixels, `width` by `height` in reading order, as a png file
+pub(crate) fn encode(width: usize, height: usize, rgb: &[u8]) -> Vec<u8> {
+    let mut header = Vec::with_capacity(13);
+    header.extend_from_slice(&(width as u32).to_be_bytes());
+    header.extend_from_slice(&(height as u32).to_be_bytes());
+    // 8 bits per channel, rgb, deflate, the only filter method, no interlace
+    header.extend_from_slice(&[8, 2, 0, 0, 0]);
+
+    // every scanline starts with its filter type, 0 for none
+    let mut scanlines = Vec::with_capacity(height * (width * 3 + 1));
+    for row in rgb.chunks(width * 3) {
+        scanlines.push(0);
+        scanlines.extend_from_slice(row);
+    }
+
+    let mut out = SIGNATURE.to_vec();
+    chunk(&mut out, b"IHDR", &header);
+    chunk(&mut out, b"IDAT", &zlib_stored(&scanlines));
+    chunk(&mut out, b"IEND", &[]);
+    out
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_checksums_and_layout() {
+        assert_eq!(crc32(b"123456789"), 0xcbf4_3926);
+        assert_eq!(adler32(b"Wikipedia"), 0x11e6_0398);
+
+        let png = encode(2, 1, &[255, 0, 0, 0, 0, 255]);
+        assert_eq!(png[..8], SIGNATURE);
+        assert_eq!(&png[12..16], b"IHDR");
+        assert_eq!(&png[png.len() - 8..png.len() - 4], b"IEND");
+
+        // big enough to need several stored blocks
+        let big = encode(200, 200, &vec![7; 200 * 200 * 3]);
+        assert!(big.len() > 200 * 200 * 3);
+    }
+}
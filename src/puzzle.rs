@@ -1,5 +1,15 @@
-use rand::seq::SliceRandom;
-use std::{collections::HashSet, str::FromStr};
+use alloc::{
+    boxed::Box,
+    format,
+    string::{String, ToString},
+    vec,
+    vec::Vec,
+};
+use core::{fmt, str::FromStr};
+
+use crate::observer::{Observers, PuzzleEvent, PuzzleObserver};
+use rand::{rngs::StdRng, seq::SliceRandom, Rng, SeedableRng};
+use serde::{Deserialize, Serialize};
 
 const SIZE: usize = 9;
 const SUBGRID_SIZE: usize = 3;
@@ -7,11 +17,15 @@ const EASY_CLUES: usize = 36;
 const MEDIUM_CLUES: usize = 34;
 const HARD_CLUES: usize = 32;
 const EXPERT_CLUES: usize = 30;
+pub const MIN_CLUES: usize = 24;
+pub const MAX_CLUES: usize = SIZE * SIZE;
 
 pub type Grid = [[Cell; SIZE]; SIZE];
 
+// each level is worth its clue count as a number, `as usize`, while the
+// levels order from easy to expert
 #[repr(usize)]
-#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
 pub enum Difficulty {
     Easy = EASY_CLUES,
     Medium = MEDIUM_CLUES,
@@ -19,11 +33,58 @@ pub enum Difficulty {
     Expert = EXPERT_CLUES,
 }
 
-#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+impl Difficulty {
+    // every level, easiest first
+    pub const fn all() -> [Difficulty; 4] {
+        [
+            Difficulty::Easy,
+            Difficulty::Medium,
+            Difficulty::Hard,
+            Difficulty::Expert,
+        ]
+    }
+
+    fn rank(self) -> usize {
+        match self {
+            Difficulty::Easy => 0,
+            Difficulty::Medium => 1,
+            Difficulty::Hard => 2,
+            Difficulty::Expert => 3,
+        }
+    }
+}
+
+impl PartialOrd for Difficulty {
+    fn partial_cmp(&self, other: &Self) -> Option<core::cmp::Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+impl Ord for Difficulty {
+    fn cmp(&self, other: &Self) -> core::cmp::Ordering {
+        self.rank().cmp(&other.rank())
+    }
+}
+
+// e.g. Medium, as `FromStr` reads it back
+impl fmt::Display for Difficulty {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(match self {
+            Difficulty::Easy => "Easy",
+            Difficulty::Medium => "Medium",
+            Difficulty::Hard => "Hard",
+            Difficulty::Expert => "Expert",
+        })
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
 pub struct Cell {
     value: u8,
     is_clue: bool,
     possible_wrong: bool,
+    notes: u16, // pencil marks, bit n set for candidate n
+    color: u8,  // player chosen color slot, 0 for none
 }
 
 impl Cell {
@@ -32,6 +93,8 @@ impl Cell {
             value,
             is_clue,
             possible_wrong: false,
+            notes: 0,
+            color: 0,
         }
     }
 
@@ -46,27 +109,161 @@ impl Cell {
     pub fn possible_wrong(&self) -> bool {
         self.possible_wrong
     }
+
+    pub fn color(&self) -> u8 {
+        self.color
+    }
+
+    pub fn notes(&self) -> Vec<u8> {
+        (1..=SIZE as u8)
+            .filter(|num| self.notes & (1 << num) != 0)
+            .collect()
+    }
 }
 
+// why a move was rejected, the grid is left untouched
 #[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum MoveError {
+    Clue,
+    Occupied,
+    InvalidNumber(u8),
+    // the number is not the solution's, only refused with strict checking
+    Wrong(u8),
+    // the row and column, one of them 9 or more
+    OutOfBounds(usize, usize),
+}
+
+impl fmt::Display for MoveError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            MoveError::Clue => write!(f, "cell is a clue"),
+            MoveError::Occupied => write!(f, "cell is already filled"),
+            MoveError::InvalidNumber(num) => write!(f, "invalid number: {}", num),
+            MoveError::Wrong(num) => write!(f, "{} is not the right number there", num),
+            MoveError::OutOfBounds(row, col) => write!(f, "there is no cell at {}, {}", row, col),
+        }
+    }
+}
+
+impl core::error::Error for MoveError {}
+
+// a single cell before and after a change, enough to undo or redo it
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub struct CellChange {
+    pub row: usize,
+    pub col: usize,
+    pub before: Cell,
+    pub after: Cell,
+}
+
+// every change made by one user action, undone and redone together
+pub type Move = Vec<CellChange>;
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
 pub struct Puzzle {
     grid: Grid,
     clues: usize, // number of clues to keep in the puzzle
     is_solved: bool,
     solution: Grid,
+    history: Vec<Move>,
+    future: Vec<Move>, // undone moves, available for redo
+    mistakes: usize,   // entries that disagree with the solution
+    hints: usize,
+    auto_notes: bool, // keep every empty cell's notes equal to its candidates
+    #[serde(skip)]
+    observers: Observers,
 }
 
 impl Puzzle {
+    #[cfg(any(feature = "entropy", test))]
     pub fn new(difficulty: Difficulty) -> Self {
-        let mut puzzle = Puzzle {
+        Self::with_clues(difficulty as usize)
+    }
+
+    // generate a puzzle keeping 'clues' numbers, clamped to MIN_CLUES..=MAX_CLUES
+    #[cfg(any(feature = "entropy", test))]
+    pub fn with_clues(clues: usize) -> Self {
+        Self::with_rng(clues, &mut rand::thread_rng())
+    }
+
+    // like `with_clues` drawing from the given generator, so a seeded one
+    // gives the same puzzle every time
+    pub fn with_rng(clues: usize, rng: &mut impl Rng) -> Self {
+        let mut puzzle = Self::empty();
+        puzzle.clues = clues.clamp(MIN_CLUES, MAX_CLUES);
+        puzzle.generate_full_solution(rng);
+        puzzle.remove_numbers(rng);
+        puzzle
+    }
+
+    // a hand made puzzle from 81 digits in reading order, 0 for an empty cell,
+    // None if it is malformed or has no solution
+    pub(crate) fn from_board(board: &str) -> Option<Self> {
+        let digits = board
+            .chars()
+            .map(|c| c.to_digit(10).map(|d| d as u8))
+            .collect::<Option<Vec<u8>>>()?;
+        if digits.len() != SIZE * SIZE {
+            return None;
+        }
+
+        let mut puzzle = Self::empty();
+        for (i, &value) in digits.iter().enumerate() {
+            puzzle.grid[i / SIZE][i % SIZE] = Cell::new(value, value != 0);
+        }
+        puzzle.clues = digits.iter().filter(|&&value| value != 0).count();
+
+        // a fixed seed, so a puzzle with several solutions always gets the
+        // same one and no entropy is needed
+        let mut solution = puzzle.grid;
+        if !validate_sudoku(&solution) || !fill_grid(&mut solution, &mut StdRng::seed_from_u64(0)) {
+            return None;
+        }
+        for cell in solution.iter_mut().flatten() {
+            cell.is_clue = true;
+        }
+        puzzle.solution = solution;
+        Some(puzzle)
+    }
+
+    // the clues as 81 digits in reading order with 0 for an empty cell, the
+    // player's entries included if asked for; the inverse of `from_board`
+    pub fn to_board(&self, entries: bool) -> String {
+        self.grid
+            .iter()
+            .flatten()
+            .map(|cell| match cell.value {
+                value if cell.is_clue || entries => (b'0' + value) as char,
+                _ => '0',
+            })
+            .collect()
+    }
+
+    pub(crate) fn empty() -> Self {
+        Puzzle {
             grid: [[Cell::new(0, true); SIZE]; SIZE],
-            clues: difficulty as usize,
+            clues: 0,
             is_solved: false,
             solution: [[Cell::new(0, true); SIZE]; SIZE],
-        };
-        puzzle.generate_full_solution();
-        puzzle.remove_numbers();
-        puzzle
+            history: Vec::new(),
+            future: Vec::new(),
+            mistakes: 0,
+            hints: 0,
+            auto_notes: false,
+            observers: Observers::default(),
+        }
+    }
+
+    pub fn clues(&self) -> usize {
+        self.clues
+    }
+
+    pub fn mistakes(&self) -> usize {
+        self.mistakes
+    }
+
+    pub fn hints(&self) -> usize {
+        self.hints
     }
 
     pub fn grid(&self) -> Grid {
@@ -77,44 +274,468 @@ impl Puzzle {
         self.is_solved
     }
 
-    pub(crate) fn insert_number(&mut self, row: usize, col: usize, num: u8) {
+    /// Puts `num` in the empty cell at `row` and `col`, both counting from 0.
+    /// 0 clears the cell instead. A number that disagrees with the solution
+    /// still goes in and counts as a mistake.
+    ///
+    /// Fails, leaving the grid as it was, on a clue, a filled cell, a number
+    /// above 9 or a cell off the grid.
+    pub fn insert_number(&mut self, row: usize, col: usize, num: u8) -> Result<(), MoveError> {
+        check_bounds(row, col)?;
         if self.grid[row][col].is_clue {
-            return;
+            return Err(MoveError::Clue);
         }
 
-        if self.grid[row][col].value == 0 {
-            if !is_safe(&self.grid(), row, col, num) {
-                self.grid[row][col].possible_wrong = true;
-            }
+        // 0 is the empty marker, inserting it means clearing the cell
+        if num == 0 {
+            return self.clear_cell(row, col);
+        }
+
+        if num as usize > SIZE {
+            return Err(MoveError::InvalidNumber(num));
+        }
 
-            self.grid[row][col].value = num;
-            self.is_solved = self.check_if_solved();
+        if self.grid[row][col].value != 0 {
+            return Err(MoveError::Occupied);
         }
+
+        let mut cell = self.grid[row][col];
+        cell.possible_wrong = !is_safe(&self.grid(), row, col, num);
+        cell.value = num;
+
+        if num != self.solution[row][col].value {
+            self.mistakes += 1;
+        }
+
+        self.apply(vec![(row, col, cell)]);
+        Ok(())
+    }
+
+    // like `insert_number`, but a number that disagrees with the solution is
+    // refused, though still counted as a mistake
+    #[cfg(any(feature = "tui", test))]
+    pub(crate) fn insert_if_right(
+        &mut self,
+        row: usize,
+        col: usize,
+        num: u8,
+    ) -> Result<(), MoveError> {
+        let cell = self.grid[row][col];
+        let is_wrong = (1..=SIZE as u8).contains(&num)
+            && !cell.is_clue
+            && cell.value == 0
+            && num != self.solution[row][col].value;
+        if is_wrong {
+            self.mistakes += 1;
+            return Err(MoveError::Wrong(num));
+        }
+        self.insert_number(row, col, num)
+    }
+
+    // pencil mark `num` in an empty cell, or take the mark away
+    #[cfg(any(feature = "tui", test))]
+    pub(crate) fn set_note(
+        &mut self,
+        row: usize,
+        col: usize,
+        num: u8,
+        on: bool,
+    ) -> Result<(), MoveError> {
+        check_bounds(row, col)?;
+        let cell = self.grid[row][col];
+        if cell.is_clue {
+            return Err(MoveError::Clue);
+        }
+        if !(1..=SIZE as u8).contains(&num) {
+            return Err(MoveError::InvalidNumber(num));
+        }
+        if cell.value != 0 {
+            return Err(MoveError::Occupied);
+        }
+
+        let notes = if on {
+            cell.notes | (1 << num)
+        } else {
+            cell.notes & !(1 << num)
+        };
+        self.apply(vec![(row, col, Cell { notes, ..cell })]);
+        Ok(())
     }
 
-    pub(crate) fn clear_cell(&mut self, row: usize, col: usize) {
+    // colors are a solving aid and may go on clues too, 0 removes the color
+    #[cfg(feature = "tui")]
+    pub(crate) fn set_color(&mut self, row: usize, col: usize, color: u8) {
+        let cell = Cell {
+            color,
+            ..self.grid[row][col]
+        };
+        self.apply(vec![(row, col, cell)]);
+    }
+
+    /// Empties the cell at `row` and `col`, or fails on a clue or a cell off
+    /// the grid. Clearing an empty cell is no error.
+    pub fn clear_cell(&mut self, row: usize, col: usize) -> Result<(), MoveError> {
+        check_bounds(row, col)?;
         if self.grid[row][col].is_clue {
-            return;
+            return Err(MoveError::Clue);
         }
 
-        self.grid[row][col].value = 0;
-        self.grid[row][col].possible_wrong = false;
+        let mut cell = self.grid[row][col];
+        cell.value = 0;
+        cell.possible_wrong = false;
 
-        self.is_solved = false;
+        self.apply(vec![(row, col, cell)]);
+        Ok(())
     }
 
-    pub(crate) fn reset(&mut self) {
-        for row in self.grid.iter_mut() {
-            for cell in row.iter_mut() {
+    /// Empties every cell but the clues, notes and colors too.
+    pub fn reset(&mut self) {
+        let mut cells = Vec::new();
+        for (r, row) in self.grid.iter().enumerate() {
+            for (c, cell) in row.iter().enumerate() {
                 if !cell.is_clue {
-                    cell.value = 0;
+                    cells.push((r, c, Cell::new(0, false)));
+                }
+            }
+        }
+
+        self.apply(cells);
+    }
+
+    // numbers that could still go in an empty cell, nothing for a filled one
+    pub fn candidates(&self, row: usize, col: usize) -> Vec<u8> {
+        candidates(&self.grid, row, col)
+    }
+
+    // pencil mark every empty cell with all of its candidates, as one move
+    #[cfg(any(feature = "tui", test))]
+    pub(crate) fn fill_notes(&mut self) {
+        let cells = self.note_updates(&self.grid);
+        self.apply(cells);
+    }
+
+    // with auto notes on, every move also updates the notes it affects;
+    // switching them on fills the notes in outside of the history
+    #[cfg(any(feature = "tui", test))]
+    pub(crate) fn set_auto_notes(&mut self, auto_notes: bool) {
+        self.auto_notes = auto_notes;
+        if auto_notes {
+            self.refresh_notes();
+        }
+    }
+
+    #[cfg(any(feature = "tui", test))]
+    fn refresh_notes(&mut self) {
+        for (row, col, cell) in self.note_updates(&self.grid) {
+            self.grid[row][col] = cell;
+        }
+    }
+
+    pub fn auto_notes(&self) -> bool {
+        self.auto_notes
+    }
+
+    fn note_updates(&self, grid: &Grid) -> Vec<(usize, usize, Cell)> {
+        let mut cells = Vec::new();
+        for (r, row) in grid.iter().enumerate() {
+            for (c, cell) in row.iter().enumerate() {
+                if cell.value == 0 && !cell.is_clue {
+                    let notes = candidates(grid, r, c)
+                        .iter()
+                        .fold(0, |notes, num| notes | (1 << num));
+                    cells.push((r, c, Cell { notes, ..*cell }));
+                }
+            }
+        }
+        cells
+    }
+
+    // place every naked single, i.e. every empty cell with just one
+    // candidate, until none are left; all of it is one move, returning how
+    // many cells were filled
+    #[cfg(any(feature = "tui", test))]
+    pub(crate) fn fill_singles(&mut self) -> usize {
+        let mut grid = self.grid;
+        let mut cells = Vec::new();
+        loop {
+            let single = (0..SIZE)
+                .flat_map(|r| (0..SIZE).map(move |c| (r, c)))
+                .find_map(|(r, c)| match candidates(&grid, r, c).as_slice() {
+                    [num] => Some((r, c, *num)),
+                    _ => None,
+                });
+            let Some((row, col, num)) = single else {
+                break;
+            };
+
+            grid[row][col].value = num;
+            cells.push((row, col, grid[row][col]));
+        }
+
+        let filled = cells.len();
+        self.apply(cells);
+        filled
+    }
+
+    // filled in cells that disagree with the solution, whether or not they
+    // break a rule yet
+    pub fn wrong_entries(&self) -> Vec<(usize, usize)> {
+        (0..SIZE)
+            .flat_map(|r| (0..SIZE).map(move |c| (r, c)))
+            .filter(|&(r, c)| {
+                let cell = self.grid[r][c];
+                cell.value != 0 && cell.value != self.solution[r][c].value
+            })
+            .collect()
+    }
+
+    // empty every wrong entry as a single move
+    #[cfg(any(feature = "tui", test))]
+    pub(crate) fn clear_wrong_entries(&mut self) {
+        let cells = self
+            .wrong_entries()
+            .into_iter()
+            .map(|(r, c)| (r, c, Cell::new(0, false)))
+            .collect();
+        self.apply(cells);
+    }
+
+    // fill every cell from the solution as a single move
+    pub(crate) fn reveal(&mut self) {
+        let mut cells = Vec::new();
+        for (r, row) in self.solution.iter().enumerate() {
+            for (c, cell) in row.iter().enumerate() {
+                if !self.grid[r][c].is_clue {
+                    cells.push((r, c, *cell));
+                }
+            }
+        }
+
+        self.apply(cells);
+    }
+
+    // a copy with every cell filled in from the solution, for printing it
+    pub fn to_solved(&self) -> Puzzle {
+        let mut solved = self.clone();
+        solved.reveal();
+        solved
+    }
+
+    // how many ways the clues can be completed, counted up to 2 since that
+    // is already one too many for a proper puzzle
+    pub fn solution_count(&self) -> usize {
+        let mut grid = self.grid;
+        for cell in grid.iter_mut().flatten() {
+            if !cell.is_clue {
+                cell.value = 0;
+            }
+        }
+        let mut count = 0;
+        count_solutions(&mut grid, &mut count);
+        count
+    }
+
+    // the same puzzle from scratch, as if it was just dealt
+    #[cfg(any(feature = "tui", test))]
+    pub(crate) fn restart(&mut self) {
+        for cell in self.grid.iter_mut().flatten() {
+            if !cell.is_clue {
+                *cell = Cell::new(0, false);
+            }
+        }
+        if self.auto_notes {
+            self.refresh_notes();
+        }
+        self.history.clear();
+        self.future.clear();
+        self.mistakes = 0;
+        self.hints = 0;
+        self.is_solved = self.check_if_solved();
+    }
+
+    #[cfg(any(feature = "tui", test))]
+    pub(crate) fn hint(&mut self, row: usize, col: usize) -> Result<(), MoveError> {
+        if self.grid[row][col].is_clue {
+            return Err(MoveError::Clue);
+        }
+
+        // the solution's cells are all clues, the hinted one stays an entry
+        let cell = Cell {
+            value: self.solution[row][col].value,
+            possible_wrong: false,
+            ..self.grid[row][col]
+        };
+        self.hints += 1;
+        self.apply(vec![(row, col, cell)]);
+        Ok(())
+    }
+
+    // other cells in the same row, column or box holding the same number
+    pub fn conflicts(&self, row: usize, col: usize) -> Vec<(usize, usize)> {
+        let num = self.grid[row][col].value;
+        if num == 0 {
+            return Vec::new();
+        }
+
+        let box_row = row - row % SUBGRID_SIZE;
+        let box_col = col - col % SUBGRID_SIZE;
+        (0..SIZE)
+            .flat_map(|r| (0..SIZE).map(move |c| (r, c)))
+            .filter(|&(r, c)| {
+                r == row
+                    || c == col
+                    || (r - r % SUBGRID_SIZE == box_row && c - c % SUBGRID_SIZE == box_col)
+            })
+            .filter(|&(r, c)| (r, c) != (row, col) && self.grid[r][c].value == num)
+            .collect()
+    }
+
+    /// Has `observer` told about every number put in or taken out from now
+    /// on, whatever changed it, and when the puzzle is solved. A clone of the
+    /// puzzle starts with no observers.
+    pub fn observe(&mut self, observer: impl PuzzleObserver + Send + 'static) {
+        self.observers.push(Box::new(observer));
+    }
+
+    // revert the most recent move, returning it so callers can describe it
+    #[cfg(any(feature = "tui", test))]
+    pub(crate) fn undo(&mut self) -> Option<Move> {
+        let changes = self.history.pop()?;
+        for change in changes.iter().rev() {
+            self.grid[change.row][change.col] = change.before;
+        }
+        let was_solved = self.is_solved;
+        self.is_solved = self.check_if_solved();
+        let undone: Move = changes
+            .iter()
+            .map(|change| CellChange {
+                before: change.after,
+                after: change.before,
+                ..*change
+            })
+            .collect();
+        self.notify(&undone, was_solved);
+        self.future.push(changes.clone());
+
+        Some(changes)
+    }
+
+    // reapply the most recently undone move
+    #[cfg(any(feature = "tui", test))]
+    pub(crate) fn redo(&mut self) -> Option<Move> {
+        let changes = self.future.pop()?;
+        for change in changes.iter() {
+            self.grid[change.row][change.col] = change.after;
+        }
+        let was_solved = self.is_solved;
+        self.is_solved = self.check_if_solved();
+        self.notify(&changes, was_solved);
+        self.history.push(changes.clone());
+
+        Some(changes)
+    }
+
+    // run several moves and keep them in the history as a single one
+    #[cfg(any(feature = "tui", test))]
+    pub(crate) fn grouped<R>(&mut self, f: impl FnOnce(&mut Self) -> R) -> R {
+        let start = self.history.len();
+        let result = f(self);
+
+        let changes: Move = self.history.drain(start..).flatten().collect();
+        if !changes.is_empty() {
+            self.history.push(changes);
+        }
+        result
+    }
+
+    // write the cells and record the ones that actually changed as one move
+    fn apply(&mut self, mut cells: Vec<(usize, usize, Cell)>) {
+        if self.auto_notes {
+            let mut grid = self.grid;
+            for &(row, col, cell) in &cells {
+                grid[row][col] = cell;
+            }
+            cells.extend(self.note_updates(&grid));
+        }
+
+        let mut changes = Move::new();
+        for (row, col, after) in cells {
+            let before = self.grid[row][col];
+            if before != after {
+                self.grid[row][col] = after;
+                changes.push(CellChange {
+                    row,
+                    col,
+                    before,
+                    after,
+                });
+            }
+        }
+
+        let was_solved = self.is_solved;
+        self.is_solved = self.check_if_solved();
+        self.notify(&changes, was_solved);
+        if !changes.is_empty() {
+            self.history.push(changes);
+            self.future.clear();
+        }
+    }
+
+    // tell the observers what the changes just written did to the numbers
+    fn notify(&mut self, changes: &[CellChange], was_solved: bool) {
+        if self.observers.is_empty() {
+            return;
+        }
+        let mut events = Vec::new();
+        for change in changes {
+            let (row, col) = (change.row, change.col);
+            match (change.before.value, change.after.value) {
+                (before, after) if before == after => {}
+                (_, 0) => events.push(PuzzleEvent::Clear { row, col }),
+                (_, num) => {
+                    events.push(PuzzleEvent::Insert { row, col, num });
+                    let with = self.conflicts(row, col);
+                    if !with.is_empty() {
+                        events.push(PuzzleEvent::Conflict { row, col, with });
+                    }
                 }
             }
         }
+        if self.is_solved && !was_solved {
+            events.push(PuzzleEvent::Solved);
+        }
+        for event in &events {
+            self.observers.notify(event);
+        }
     }
 
-    pub(crate) fn hint(&mut self, row: usize, col: usize) {
-        self.grid[row][col] = self.solution[row][col]
+    // next empty cell after (row, col) in reading order, wrapping around the grid
+    pub fn next_empty_cell(&self, row: usize, col: usize) -> Option<(usize, usize)> {
+        let start = row * SIZE + col;
+        (1..=SIZE * SIZE)
+            .map(|offset| (start + offset) % (SIZE * SIZE))
+            .map(|index| (index / SIZE, index % SIZE))
+            .find(|&(r, c)| self.grid[r][c].value == 0)
+    }
+
+    // where a hint should go: the given cell unless it is a clue or already
+    // right, then the next cell in reading order that is still unsolved
+    #[cfg(any(feature = "tui", test))]
+    pub(crate) fn hint_target(&self, row: usize, col: usize) -> Option<(usize, usize)> {
+        let start = row * SIZE + col;
+        (0..SIZE * SIZE)
+            .map(|offset| (start + offset) % (SIZE * SIZE))
+            .map(|index| (index / SIZE, index % SIZE))
+            .find(|&(r, c)| self.grid[r][c].value != self.solution[r][c].value)
+    }
+
+    // previous empty cell before (row, col) in reading order, wrapping around the grid
+    pub fn prev_empty_cell(&self, row: usize, col: usize) -> Option<(usize, usize)> {
+        let start = row * SIZE + col;
+        (1..=SIZE * SIZE)
+            .map(|offset| (start + SIZE * SIZE - offset) % (SIZE * SIZE))
+            .map(|index| (index / SIZE, index % SIZE))
+            .find(|&(r, c)| self.grid[r][c].value == 0)
     }
 
     fn check_if_solved(&self) -> bool {
@@ -129,27 +750,32 @@ impl Puzzle {
         self.validate() // ensure the Sudoku is valid
     }
 
-    fn generate_full_solution(&mut self) {
-        fill_grid(&mut self.grid);
+    fn generate_full_solution(&mut self, rng: &mut impl Rng) {
+        fill_grid(&mut self.grid, rng);
         self.solution = self.grid;
     }
 
-    // remove numbers from the grid while leaving 'clues' numbers
-    fn remove_numbers(&mut self) {
-        let mut rng = rand::thread_rng();
+    // remove numbers from the grid while leaving 'clues' numbers; a low count
+    // can run out of cells that keep the solution unique, so 'clues' ends up
+    // as the number actually kept
+    fn remove_numbers(&mut self, rng: &mut impl Rng) {
         let mut positions: Vec<(usize, usize)> = (0..SIZE)
             .flat_map(|r| (0..SIZE).map(move |c| (r, c)))
             .collect();
-        positions.shuffle(&mut rng);
+        positions.shuffle(rng);
 
         let mut cells_removed = 0;
         let cells_to_remove = SIZE * SIZE - self.clues;
 
         for &(row, col) in &positions {
+            if cells_removed >= cells_to_remove {
+                break;
+            }
+
             let original_value = self.grid[row][col].value;
             self.grid[row][col] = Cell::new(0, true);
 
-            let mut grid = self.grid.clone();
+            let mut grid = self.grid;
             let mut solutions = 0;
             count_solutions(&mut grid, &mut solutions);
 
@@ -159,11 +785,8 @@ impl Puzzle {
             } else {
                 self.grid[row][col] = Cell::new(original_value, true);
             }
-
-            if cells_removed >= cells_to_remove {
-                break;
-            }
         }
+        self.clues = SIZE * SIZE - cells_removed;
     }
 
     // validate if the current grid is a valid Sudoku solution
@@ -188,7 +811,9 @@ fn count_solutions(grid: &mut Grid, count: &mut usize) {
                     }
                 }
 
-                return; // no valid number found, backtrack
+                // leave the cell empty for the other branches
+                grid[row][col].value = 0;
+                return;
             }
         }
     }
@@ -197,18 +822,17 @@ fn count_solutions(grid: &mut Grid, count: &mut usize) {
 }
 
 // recursive function to fill the grid with numbers that follow Sudoku rules
-fn fill_grid(grid: &mut Grid) -> bool {
+fn fill_grid(grid: &mut Grid, rng: &mut impl Rng) -> bool {
     let mut numbers: Vec<u8> = (1..=9).collect();
-    let mut rng = rand::thread_rng();
 
     for row in 0..SIZE {
         for col in 0..SIZE {
             if grid[row][col].value == 0 {
-                numbers.shuffle(&mut rng);
+                numbers.shuffle(rng);
                 for &num in &numbers {
                     if is_safe(grid, row, col, num) {
                         grid[row][col].value = num;
-                        if fill_grid(grid) {
+                        if fill_grid(grid, rng) {
                             return true;
                         }
                         grid[row][col].value = 0;
@@ -221,6 +845,15 @@ fn fill_grid(grid: &mut Grid) -> bool {
     true
 }
 
+pub(crate) fn candidates(grid: &Grid, row: usize, col: usize) -> Vec<u8> {
+    if grid[row][col].value != 0 {
+        return Vec::new();
+    }
+    (1..=SIZE as u8)
+        .filter(|&num| is_safe(grid, row, col, num))
+        .collect()
+}
+
 // check if placing the number is safe in the current position
 fn is_safe(grid: &Grid, row: usize, col: usize, num: u8) -> bool {
     !is_in_row(grid, row, num)
@@ -247,7 +880,7 @@ fn is_in_subgrid(grid: &Grid, start_row: usize, start_col: usize, num: u8) -> bo
 }
 
 // validate the entire grid for a valid Sudoku solution
-fn validate_sudoku(grid: &Grid) -> bool {
+pub(crate) fn validate_sudoku(grid: &Grid) -> bool {
     for row in grid.iter() {
         if !is_valid_set(&row.iter().map(|cell| cell.value).collect::<Vec<_>>()) {
             return false;
@@ -281,12 +914,20 @@ fn validate_sudoku(grid: &Grid) -> bool {
     true
 }
 
+fn check_bounds(row: usize, col: usize) -> Result<(), MoveError> {
+    match row < SIZE && col < SIZE {
+        true => Ok(()),
+        false => Err(MoveError::OutOfBounds(row, col)),
+    }
+}
+
 fn is_valid_set(nums: &[u8]) -> bool {
-    let mut set = HashSet::new();
+    let mut seen = 0u16;
     for &num in nums {
-        if num != 0 && !set.insert(num) {
+        if num != 0 && seen & 1 << num != 0 {
             return false;
         }
+        seen |= 1 << num;
     }
     true
 }
@@ -305,10 +946,88 @@ impl FromStr for Difficulty {
     }
 }
 
+// a puzzle as 81 cells in reading order, '0' or '.' for an empty cell, with
+// any whitespace in between ignored so grids pasted over several lines work
+impl FromStr for Puzzle {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let board: String = s
+            .chars()
+            .filter(|c| !c.is_whitespace())
+            .map(|c| if c == '.' { '0' } else { c })
+            .collect();
+        if let Some(c) = board.chars().find(|c| !c.is_ascii_digit()) {
+            return Err(format!("Invalid cell: {}", c));
+        }
+        if board.len() != SIZE * SIZE {
+            return Err(format!(
+                "A puzzle has {} cells, got {}",
+                SIZE * SIZE,
+                board.len()
+            ));
+        }
+        Puzzle::from_board(&board).ok_or_else(|| {
+            let mut clues = Self::empty().grid;
+            for (cell, value) in clues.iter_mut().flatten().zip(board.bytes()) {
+                cell.value = value - b'0';
+            }
+            if validate_sudoku(&clues) {
+                "The puzzle has no solution".to_string()
+            } else {
+                "The clues conflict".to_string()
+            }
+        })
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
 
+    #[test]
+    fn test_board_string_round_trips() {
+        let mut puzzle = Puzzle::new(Difficulty::Easy);
+        let clues = puzzle.to_board(false);
+        assert_eq!(clues.len(), 81);
+        assert_eq!(Puzzle::from_board(&clues).unwrap().grid(), puzzle.grid());
+
+        let (row, col) = puzzle.next_empty_cell(8, 8).unwrap();
+        puzzle.insert_number(row, col, 1).unwrap();
+        assert_eq!(puzzle.to_board(false), clues);
+        assert_eq!(puzzle.to_board(true).as_bytes()[row * 9 + col], b'1');
+    }
+
+    #[test]
+    fn test_parse_puzzle() {
+        let puzzle = Puzzle::new(Difficulty::Hard);
+        let dotted = puzzle.to_board(false).replace('0', ".");
+        let lines: Vec<&str> = (0..9).map(|row| &dotted[row * 9..row * 9 + 9]).collect();
+        let parsed: Puzzle = lines.join("\n").parse().unwrap();
+        assert_eq!(parsed.grid(), puzzle.grid());
+        assert_eq!(parsed.clues(), HARD_CLUES);
+
+        assert!("123".parse::<Puzzle>().is_err());
+        assert!(dotted.replacen('.', "x", 1).parse::<Puzzle>().is_err());
+        // two 1s in the first row
+        let conflict = format!("11{}", &dotted[2..]);
+        assert_eq!(
+            conflict.parse::<Puzzle>().unwrap_err(),
+            "The clues conflict"
+        );
+    }
+
+    #[test]
+    fn test_difficulties_order_and_print() {
+        let all = Difficulty::all();
+        assert!(all.windows(2).all(|pair| pair[0] < pair[1]));
+        assert_eq!(all.iter().max(), Some(&Difficulty::Expert));
+        for difficulty in all {
+            assert_eq!(difficulty.to_string().parse(), Ok(difficulty));
+        }
+        assert_eq!(Difficulty::Medium.to_string(), "Medium");
+    }
+
     #[test]
     fn test_puzzle_generation_easy() {
         let puzzle = Puzzle::new(Difficulty::Easy);
@@ -361,12 +1080,409 @@ mod tests {
         assert_eq!(empty_cells, SIZE * SIZE - EXPERT_CLUES);
     }
 
+    #[test]
+    fn test_puzzle_generation_custom_clues() {
+        let puzzle = Puzzle::with_clues(40);
+        assert_eq!(puzzle.clues(), 40);
+        let filled = puzzle
+            .grid()
+            .iter()
+            .flatten()
+            .filter(|cell| cell.value != 0)
+            .count();
+        assert_eq!(filled, 40);
+    }
+
+    #[test]
+    fn test_generation_keeps_every_clue_up_to_max() {
+        let puzzle = Puzzle::with_clues(MAX_CLUES + 1);
+        assert_eq!(puzzle.clues(), MAX_CLUES);
+        assert!(puzzle
+            .grid()
+            .iter()
+            .flatten()
+            .all(|cell| cell.is_clue() && cell.value() != 0));
+    }
+
+    #[test]
+    fn test_generation_counts_the_clues_kept_at_the_low_end() {
+        // the fewest clues can be more than a unique solution allows, then
+        // the count is whatever could be kept
+        let puzzle = Puzzle::with_clues(5);
+        let kept = puzzle
+            .grid()
+            .iter()
+            .flatten()
+            .filter(|cell| cell.is_clue())
+            .count();
+        assert_eq!(puzzle.clues(), kept);
+        assert!(kept >= MIN_CLUES);
+        assert_eq!(puzzle.solution_count(), 1);
+    }
+
+    #[test]
+    fn test_seeded_generation_repeats() {
+        use rand::{rngs::StdRng, SeedableRng};
+
+        let generate = |seed| Puzzle::with_rng(HARD_CLUES, &mut StdRng::seed_from_u64(seed));
+        assert_eq!(generate(42).grid(), generate(42).grid());
+        assert_ne!(generate(42).grid(), generate(43).grid());
+    }
+
+    #[test]
+    fn test_solution_count_and_solved_copy() {
+        let puzzle = Puzzle::new(Difficulty::Expert);
+        assert_eq!(puzzle.solution_count(), 1);
+        let solved = puzzle.to_solved();
+        assert!(solved.is_solved());
+        assert!(!puzzle.is_solved());
+
+        // a single clue leaves the board wide open
+        let open: Puzzle = format!("1{}", "0".repeat(80)).parse().unwrap();
+        assert_eq!(open.solution_count(), 2);
+    }
+
     #[test]
     fn test_grid_has_valid_solution_after_generation() {
         let puzzle = Puzzle::new(Difficulty::Medium);
         assert!(puzzle.validate());
     }
 
+    #[test]
+    fn test_next_and_prev_empty_cell_wrap_around() {
+        let mut puzzle = Puzzle::new(Difficulty::Easy);
+        puzzle.grid = puzzle.solution;
+        puzzle.grid[0][4].value = 0;
+        puzzle.grid[8][2].value = 0;
+
+        assert_eq!(puzzle.next_empty_cell(0, 0), Some((0, 4)));
+        assert_eq!(puzzle.next_empty_cell(0, 4), Some((8, 2)));
+        assert_eq!(puzzle.next_empty_cell(8, 2), Some((0, 4)));
+        assert_eq!(puzzle.prev_empty_cell(0, 4), Some((8, 2)));
+        assert_eq!(puzzle.prev_empty_cell(5, 5), Some((0, 4)));
+
+        puzzle.grid = puzzle.solution;
+        assert_eq!(puzzle.next_empty_cell(3, 3), None);
+    }
+
+    #[test]
+    fn test_insert_zero_clears_cell() {
+        let mut puzzle = Puzzle::new(Difficulty::Easy);
+        let (row, col) = puzzle.next_empty_cell(0, 0).unwrap();
+
+        puzzle.insert_number(row, col, 5).unwrap();
+        assert_eq!(puzzle.grid()[row][col].value(), 5);
+
+        puzzle.insert_number(row, col, 0).unwrap();
+        assert_eq!(puzzle.grid()[row][col].value(), 0);
+        assert!(!puzzle.grid()[row][col].possible_wrong());
+    }
+
+    #[test]
+    fn test_rejected_moves() {
+        let mut puzzle = Puzzle::new(Difficulty::Easy);
+        let (row, col) = puzzle.next_empty_cell(0, 0).unwrap();
+        let clue = (0..SIZE)
+            .flat_map(|r| (0..SIZE).map(move |c| (r, c)))
+            .find(|&(r, c)| puzzle.grid()[r][c].is_clue())
+            .unwrap();
+
+        assert_eq!(
+            puzzle.insert_number(clue.0, clue.1, 1),
+            Err(MoveError::Clue)
+        );
+        assert_eq!(puzzle.clear_cell(clue.0, clue.1), Err(MoveError::Clue));
+        assert_eq!(
+            puzzle.insert_number(row, col, 10),
+            Err(MoveError::InvalidNumber(10))
+        );
+
+        puzzle.insert_number(row, col, 1).unwrap();
+        assert_eq!(puzzle.insert_number(row, col, 2), Err(MoveError::Occupied));
+
+        let grid = puzzle.grid();
+        assert_eq!(
+            puzzle.insert_number(9, 0, 1),
+            Err(MoveError::OutOfBounds(9, 0))
+        );
+        assert_eq!(puzzle.clear_cell(0, 12), Err(MoveError::OutOfBounds(0, 12)));
+        assert_eq!(puzzle.grid(), grid);
+    }
+
+    #[test]
+    fn test_strict_insert_refuses_wrong_numbers() {
+        let mut puzzle = Puzzle::new(Difficulty::Easy);
+        let (row, col) = puzzle.next_empty_cell(0, 0).unwrap();
+        let answer = puzzle.solution[row][col].value();
+        let wrong = answer % 9 + 1;
+
+        assert_eq!(
+            puzzle.insert_if_right(row, col, wrong),
+            Err(MoveError::Wrong(wrong))
+        );
+        assert_eq!(puzzle.grid()[row][col].value(), 0);
+        assert_eq!(puzzle.mistakes(), 1);
+
+        puzzle.insert_if_right(row, col, answer).unwrap();
+        assert_eq!(puzzle.grid()[row][col].value(), answer);
+        assert_eq!(
+            puzzle.insert_if_right(row, col, wrong),
+            Err(MoveError::Occupied)
+        );
+        assert_eq!(puzzle.mistakes(), 1);
+    }
+
+    #[test]
+    fn test_mistakes_and_hints_are_counted() {
+        let mut puzzle = Puzzle::new(Difficulty::Easy);
+        let (row, col) = puzzle.next_empty_cell(0, 0).unwrap();
+        let answer = puzzle.solution[row][col].value();
+        let wrong = answer % 9 + 1;
+
+        puzzle.insert_number(row, col, wrong).unwrap();
+        puzzle.undo();
+        assert_eq!(puzzle.mistakes(), 1);
+
+        puzzle.insert_number(row, col, answer).unwrap();
+        assert_eq!(puzzle.mistakes(), 1);
+
+        let (row, col) = puzzle.next_empty_cell(row, col).unwrap();
+        puzzle.hint(row, col).unwrap();
+        assert_eq!(puzzle.hints(), 1);
+    }
+
+    #[test]
+    fn test_restart_clears_entries_and_counters() {
+        let mut puzzle = Puzzle::new(Difficulty::Easy);
+        let clues = puzzle.grid();
+        let (row, col) = puzzle.next_empty_cell(0, 0).unwrap();
+        let wrong = puzzle.solution[row][col].value() % 9 + 1;
+        puzzle.insert_number(row, col, wrong).unwrap();
+
+        puzzle.restart();
+        assert_eq!(puzzle.grid(), clues);
+        assert_eq!(puzzle.mistakes(), 0);
+        assert!(puzzle.undo().is_none());
+    }
+
+    #[test]
+    fn test_restart_takes_hints_back() {
+        let mut puzzle = Puzzle::new(Difficulty::Easy);
+        let (clues, board, count) = (puzzle.grid(), puzzle.to_board(false), puzzle.clues());
+        let (row, col) = puzzle.next_empty_cell(0, 0).unwrap();
+        puzzle.hint(row, col).unwrap();
+        assert!(!puzzle.grid()[row][col].is_clue());
+        assert_eq!(puzzle.to_board(false), board);
+
+        puzzle.restart();
+        assert_eq!(puzzle.grid()[row][col].value(), 0);
+        assert_eq!(puzzle.grid(), clues);
+        assert_eq!(puzzle.to_board(false), board);
+        assert_eq!(puzzle.clues(), count);
+        assert_eq!(puzzle.hints(), 0);
+    }
+
+    #[test]
+    fn test_hint_target_skips_clues_and_correct_cells() {
+        let mut puzzle = Puzzle::new(Difficulty::Easy);
+        // the first empty cell in reading order
+        let (row, col) = puzzle.next_empty_cell(8, 8).unwrap();
+        assert_eq!(puzzle.hint_target(row, col), Some((row, col)));
+
+        let answer = puzzle.solution[row][col].value();
+        puzzle.insert_number(row, col, answer).unwrap();
+        assert_eq!(
+            puzzle.hint_target(row, col),
+            puzzle.next_empty_cell(row, col)
+        );
+
+        let wrong = answer % 9 + 1;
+        puzzle.clear_cell(row, col).unwrap();
+        puzzle.insert_number(row, col, wrong).unwrap();
+        assert_eq!(puzzle.hint_target(0, 0), Some((row, col)));
+    }
+
+    #[test]
+    fn test_wrong_entries_are_found_and_cleared() {
+        let mut puzzle = Puzzle::new(Difficulty::Easy);
+        let (r1, c1) = puzzle.next_empty_cell(0, 0).unwrap();
+        let (r2, c2) = puzzle.next_empty_cell(r1, c1).unwrap();
+        let wrong = puzzle.solution[r1][c1].value() % 9 + 1;
+        puzzle.insert_number(r1, c1, wrong).unwrap();
+        puzzle
+            .insert_number(r2, c2, puzzle.solution[r2][c2].value())
+            .unwrap();
+        assert_eq!(puzzle.wrong_entries(), vec![(r1, c1)]);
+
+        puzzle.clear_wrong_entries();
+        assert!(puzzle.wrong_entries().is_empty());
+        assert_eq!(puzzle.grid()[r1][c1].value(), 0);
+        assert_ne!(puzzle.grid()[r2][c2].value(), 0);
+    }
+
+    #[test]
+    fn test_candidates() {
+        let mut puzzle = Puzzle::empty();
+        for row in puzzle.grid.iter_mut() {
+            for cell in row.iter_mut() {
+                cell.is_clue = false;
+            }
+        }
+        for col in 0..8 {
+            puzzle.insert_number(0, col, col as u8 + 1).unwrap();
+        }
+        assert_eq!(puzzle.candidates(0, 8), vec![9]);
+        assert!(puzzle.candidates(0, 0).is_empty());
+        assert_eq!(puzzle.candidates(1, 0), vec![4, 5, 6, 7, 8, 9]);
+    }
+
+    #[test]
+    fn test_notes_by_hand() {
+        let mut puzzle = Puzzle::new(Difficulty::Easy);
+        let (row, col) = puzzle.next_empty_cell(0, 0).unwrap();
+        puzzle.set_note(row, col, 4, true).unwrap();
+        puzzle.set_note(row, col, 7, true).unwrap();
+        puzzle.set_note(row, col, 4, false).unwrap();
+        assert_eq!(puzzle.grid()[row][col].notes(), [7]);
+        puzzle.undo();
+        assert_eq!(puzzle.grid()[row][col].notes(), [4, 7]);
+
+        assert_eq!(
+            puzzle.set_note(row, col, 0, true),
+            Err(MoveError::InvalidNumber(0))
+        );
+        let solution = puzzle.to_solved().grid();
+        puzzle
+            .insert_number(row, col, solution[row][col].value())
+            .unwrap();
+        assert_eq!(puzzle.set_note(row, col, 1, true), Err(MoveError::Occupied));
+    }
+
+    #[test]
+    fn test_auto_notes_follow_moves() {
+        let mut puzzle = Puzzle::new(Difficulty::Easy);
+        let (row, col) = puzzle.next_empty_cell(8, 8).unwrap();
+        puzzle.fill_notes();
+        assert_eq!(puzzle.grid()[row][col].notes(), puzzle.candidates(row, col));
+
+        puzzle.set_auto_notes(true);
+        let num = puzzle.candidates(row, col)[0];
+        puzzle.insert_number(row, col, num).unwrap();
+        let (next_row, next_col) = puzzle.next_empty_cell(row, col).unwrap();
+        assert_eq!(
+            puzzle.grid()[next_row][next_col].notes(),
+            puzzle.candidates(next_row, next_col)
+        );
+
+        // the notes come back with the undo
+        puzzle.undo();
+        assert_eq!(puzzle.grid()[row][col].notes(), puzzle.candidates(row, col));
+    }
+
+    #[test]
+    fn test_fill_singles_is_one_move() {
+        let mut puzzle = Puzzle::new(Difficulty::Easy);
+        let clues = puzzle.grid();
+        puzzle.fill_singles();
+        assert!(puzzle.wrong_entries().is_empty());
+        assert!((0..SIZE).all(|r| (0..SIZE).all(|c| puzzle.candidates(r, c).len() != 1)));
+
+        puzzle.undo();
+        assert_eq!(puzzle.grid(), clues);
+    }
+
+    #[test]
+    fn test_reveal_solves_the_puzzle() {
+        let mut puzzle = Puzzle::new(Difficulty::Hard);
+        puzzle.reveal();
+        assert!(puzzle.is_solved());
+        assert_eq!(puzzle.grid(), puzzle.solution);
+
+        puzzle.undo();
+        assert!(!puzzle.is_solved());
+    }
+
+    #[test]
+    fn test_conflicts() {
+        let mut puzzle = Puzzle::empty();
+        for row in puzzle.grid.iter_mut() {
+            for cell in row.iter_mut() {
+                cell.is_clue = false;
+            }
+        }
+        puzzle.insert_number(0, 0, 5).unwrap();
+        puzzle.insert_number(0, 8, 5).unwrap();
+        puzzle.insert_number(2, 2, 5).unwrap();
+        puzzle.insert_number(4, 4, 5).unwrap();
+
+        assert_eq!(puzzle.conflicts(0, 0), vec![(0, 8), (2, 2)]);
+        assert!(puzzle.conflicts(4, 4).is_empty());
+        assert!(puzzle.conflicts(5, 5).is_empty());
+    }
+
+    #[test]
+    fn test_undo_and_redo_insert() {
+        let mut puzzle = Puzzle::new(Difficulty::Easy);
+        let (row, col) = puzzle.next_empty_cell(0, 0).unwrap();
+
+        puzzle.insert_number(row, col, 4).unwrap();
+        let undone = puzzle.undo().unwrap();
+        assert_eq!(undone.len(), 1);
+        assert_eq!(undone[0].after.value(), 4);
+        assert_eq!(puzzle.grid()[row][col].value(), 0);
+
+        puzzle.redo();
+        assert_eq!(puzzle.grid()[row][col].value(), 4);
+        assert!(puzzle.redo().is_none());
+    }
+
+    #[test]
+    fn test_reset_is_a_single_undoable_move() {
+        let mut puzzle = Puzzle::new(Difficulty::Easy);
+        let (r1, c1) = puzzle.next_empty_cell(0, 0).unwrap();
+        let (r2, c2) = puzzle.next_empty_cell(r1, c1).unwrap();
+        puzzle.insert_number(r1, c1, 1).unwrap();
+        puzzle.insert_number(r2, c2, 2).unwrap();
+
+        puzzle.reset();
+        assert_eq!(puzzle.grid()[r1][c1].value(), 0);
+
+        puzzle.undo();
+        assert_eq!(puzzle.grid()[r1][c1].value(), 1);
+        assert_eq!(puzzle.grid()[r2][c2].value(), 2);
+    }
+
+    #[test]
+    fn test_grouped_moves_undo_together() {
+        let mut puzzle = Puzzle::new(Difficulty::Easy);
+        let clues = puzzle.grid();
+        let (r1, c1) = puzzle.next_empty_cell(0, 0).unwrap();
+        let (r2, c2) = puzzle.next_empty_cell(r1, c1).unwrap();
+
+        puzzle.grouped(|puzzle| {
+            puzzle.insert_number(r1, c1, 1).unwrap();
+            puzzle.insert_number(r2, c2, 2).unwrap();
+            puzzle.clear_cell(r1, c1).unwrap();
+        });
+        assert_eq!(puzzle.history.len(), 1);
+
+        puzzle.undo();
+        assert_eq!(puzzle.grid(), clues);
+        puzzle.redo();
+        assert_eq!(puzzle.grid()[r1][c1].value(), 0);
+        assert_eq!(puzzle.grid()[r2][c2].value(), 2);
+    }
+
+    #[test]
+    fn test_new_move_discards_redo() {
+        let mut puzzle = Puzzle::new(Difficulty::Easy);
+        let (row, col) = puzzle.next_empty_cell(0, 0).unwrap();
+        puzzle.insert_number(row, col, 3).unwrap();
+        puzzle.undo();
+        puzzle.insert_number(row, col, 6).unwrap();
+        assert!(puzzle.redo().is_none());
+    }
+
     #[test]
     fn test_is_valid_set() {
         let valid_row = vec![1, 2, 3, 4, 5, 6, 7, 8, 9];
@@ -382,22 +1498,30 @@ mod tests {
             value: 0,
             is_clue: false,
             possible_wrong: false,
+            notes: 0,
+            color: 0,
         }; SIZE]; SIZE];
 
         grid[0][0] = Cell {
             value: 1,
             is_clue: false,
             possible_wrong: false,
+            notes: 0,
+            color: 0,
         };
         grid[0][1] = Cell {
             value: 2,
             is_clue: false,
             possible_wrong: false,
+            notes: 0,
+            color: 0,
         };
         grid[0][2] = Cell {
             value: 3,
             is_clue: false,
             possible_wrong: false,
+            notes: 0,
+            color: 0,
         };
 
         assert!(!is_safe(&grid, 0, 3, 1));
@@ -410,17 +1534,23 @@ mod tests {
             value: 0,
             is_clue: false,
             possible_wrong: false,
+            notes: 0,
+            color: 0,
         }; SIZE]; SIZE];
 
         grid[0][0] = Cell {
             value: 1,
             possible_wrong: false,
             is_clue: false,
+            notes: 0,
+            color: 0,
         };
         grid[0][1] = Cell {
             value: 2,
             possible_wrong: false,
             is_clue: false,
+            notes: 0,
+            color: 0,
         };
 
         assert!(!is_safe(&grid, 0, 2, 1));
@@ -433,17 +1563,23 @@ mod tests {
             value: 0,
             is_clue: false,
             possible_wrong: false,
+            notes: 0,
+            color: 0,
         }; SIZE]; SIZE];
 
         grid[0][0] = Cell {
             value: 1,
             is_clue: false,
             possible_wrong: false,
+            notes: 0,
+            color: 0,
         };
         grid[1][0] = Cell {
             value: 2,
             is_clue: false,
             possible_wrong: false,
+            notes: 0,
+            color: 0,
         };
 
         assert!(!is_safe(&grid, 2, 0, 1));
@@ -456,17 +1592,23 @@ mod tests {
             value: 0,
             is_clue: false,
             possible_wrong: false,
+            notes: 0,
+            color: 0,
         }; SIZE]; SIZE];
 
         grid[0][0] = Cell {
             value: 1,
             is_clue: false,
             possible_wrong: false,
+            notes: 0,
+            color: 0,
         };
         grid[1][1] = Cell {
             value: 2,
             is_clue: false,
             possible_wrong: false,
+            notes: 0,
+            color: 0,
         };
 
         assert!(!is_safe(&grid, 1, 1, 1));
@@ -479,6 +1621,8 @@ mod tests {
             value: 0,
             possible_wrong: false,
             is_clue: false,
+            notes: 0,
+            color: 0,
         }; SIZE]; SIZE];
 
         assert!(is_safe(&grid, 4, 4, 5));
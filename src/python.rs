@@ -0,0 +1,195 @@
+// clippy reads the error conversion in pyo3's generated wrappers as useless
+#![allow(clippy::useless_conversion)]
+
+use pyo3::{exceptions::PyValueError, prelude::*};
+use rand::{rngs::StdRng, SeedableRng};
+
+use crate::{rate, Difficulty, Puzzle};
+
+// what generate takes for a level: a difficulty's name or a clue count
+#[derive(FromPyObject)]
+enum Level {
+    Name(String),
+    Clues(usize),
+}
+
+fn value_error(err: impl ToString) -> PyErr {
+    PyValueError::new_err(err.to_string())
+}
+
+/// A board of 81 cells with its solution, from `Puzzle.generate` or from the
+/// cells as a string, 0 or . for an empty cell.
+#[pyclass(name = "Puzzle", module = "rsudoku")]
+struct PyPuzzle(Puzzle);
+
+#[pymethods]
+impl PyPuzzle {
+    #[new]
+    fn new(cells: &str) -> PyResult<Self> {
+        cells.parse().map(PyPuzzle).map_err(value_error)
+    }
+
+    /// A new puzzle at a difficulty name or a clue count, the same puzzle for
+    /// the same seed.
+    #[staticmethod]
+    #[pyo3(signature = (difficulty = Level::Name("medium".into()), seed = None))]
+    fn generate(difficulty: Level, seed: Option<u64>) -> PyResult<Self> {
+        let clues = match difficulty {
+            Level::Name(name) => name.parse::<Difficulty>().map_err(value_error)? as usize,
+            Level::Clues(clues) => clues,
+        };
+        Ok(PyPuzzle(match seed {
+            Some(seed) => Puzzle::with_rng(clues, &mut StdRng::seed_from_u64(seed)),
+            None => Puzzle::with_clues(clues),
+        }))
+    }
+
+    /// The board with every cell filled in from the solution.
+    fn solve(&self) -> Self {
+        PyPuzzle(self.0.to_solved())
+    }
+
+    /// How hard the clues are by the techniques it takes to finish them.
+    fn rate(&self) -> PyRating {
+        let mut clues = self.0.grid();
+        for cell in clues.iter_mut().flatten() {
+            if !cell.is_clue() {
+                *cell = crate::Cell::new(0, false);
+            }
+        }
+        let rating = rate(&clues);
+        PyRating {
+            difficulty: rating.difficulty.to_string().to_lowercase(),
+            score: rating.score,
+            hardest: rating.hardest.map(|technique| technique.name().to_string()),
+            stuck: rating.stuck,
+        }
+    }
+
+    /// How many ways the clues can be completed, counted up to 2.
+    fn solution_count(&self) -> usize {
+        self.0.solution_count()
+    }
+
+    /// Puts `num` in the empty cell at `row` and `col`, counting from 0; 0
+    /// clears it. Raises ValueError on a clue, a filled cell or a bad number.
+    fn insert_number(&mut self, row: usize, col: usize, num: u8) -> PyResult<()> {
+        self.0.insert_number(row, col, num).map_err(value_error)
+    }
+
+    fn clear_cell(&mut self, row: usize, col: usize) -> PyResult<()> {
+        self.0.clear_cell(row, col).map_err(value_error)
+    }
+
+    /// Empties every cell but the clues.
+    fn reset(&mut self) {
+        self.0.reset();
+    }
+
+    /// The 81 numbers in reading order, 0 for an empty cell.
+    #[getter]
+    fn cells(&self) -> Vec<u8> {
+        self.0
+            .grid()
+            .iter()
+            .flatten()
+            .map(|cell| cell.value())
+            .collect()
+    }
+
+    #[getter]
+    fn clues(&self) -> usize {
+        self.0.clues()
+    }
+
+    #[getter]
+    fn mistakes(&self) -> usize {
+        self.0.mistakes()
+    }
+
+    #[getter]
+    fn is_solved(&self) -> bool {
+        self.0.is_solved()
+    }
+
+    fn __str__(&self) -> String {
+        self.0.to_board(true)
+    }
+
+    fn __repr__(&self) -> String {
+        format!("Puzzle('{}')", self.0.to_board(true))
+    }
+
+    fn __eq__(&self, other: &Self) -> bool {
+        self.0.grid() == other.0.grid()
+    }
+}
+
+/// How hard a board is: its difficulty's name, the technique score, the
+/// hardest technique used and the cells the techniques could not place.
+#[pyclass(name = "Rating", module = "rsudoku", get_all)]
+struct PyRating {
+    difficulty: String,
+    score: u32,
+    hardest: Option<String>,
+    stuck: usize,
+}
+
+#[pymethods]
+impl PyRating {
+    fn __repr__(&self) -> String {
+        format!(
+            "Rating(difficulty='{}', score={}, stuck={})",
+            self.difficulty, self.score, self.stuck
+        )
+    }
+}
+
+#[pymodule]
+fn rsudoku(module: &Bound<'_, PyModule>) -> PyResult<()> {
+    module.add_class::<PyPuzzle>()?;
+    module.add_class::<PyRating>()?;
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_generate_solve_and_rate() {
+        let puzzle = PyPuzzle::generate(Level::Name("Hard".into()), Some(7)).unwrap();
+        assert_eq!(puzzle.clues(), Difficulty::Hard as usize);
+        assert_eq!(puzzle.solution_count(), 1);
+        let again = PyPuzzle::generate(Level::Clues(Difficulty::Hard as usize), Some(7)).unwrap();
+        assert!(puzzle.__eq__(&again));
+
+        let solved = puzzle.solve();
+        assert!(solved.is_solved());
+        for (clue, value) in puzzle.cells().into_iter().zip(solved.cells()) {
+            assert!(clue == 0 || clue == value);
+        }
+        assert!(puzzle.rate().score > 0);
+        assert_eq!(solved.rate().score, 0);
+    }
+
+    #[test]
+    fn test_moves_and_bad_input_raise() {
+        assert!(PyPuzzle::generate(Level::Name("impossible".into()), None).is_err());
+        assert!(PyPuzzle::new("123").is_err());
+
+        let mut puzzle = PyPuzzle::new(
+            &PyPuzzle::generate(Level::Clues(40), Some(1))
+                .unwrap()
+                .__str__(),
+        )
+        .unwrap();
+        let empty = puzzle.cells().iter().position(|&value| value == 0).unwrap();
+        let (row, col) = (empty / 9, empty % 9);
+        puzzle.insert_number(row, col, 10).unwrap_err();
+        puzzle.insert_number(row, col, 5).unwrap();
+        puzzle.insert_number(row, col, 6).unwrap_err();
+        puzzle.reset();
+        assert_eq!(puzzle.cells()[empty], 0);
+    }
+}
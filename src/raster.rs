@@ -0,0 +1,224 @@
+// the board drawn into pixels, shared by the kitty graphics and png images
+
+use crate::puzzle::Grid;
+
+// each image is drawn at SUPERSAMPLE times its size and averaged down so
+// edges come out smooth
+const SUPERSAMPLE: usize = 2;
+
+pub(crate) type Rgb = [u8; 3];
+
+// the colors an image is drawn in
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub(crate) struct Palette {
+    pub(crate) background: Rgb,
+    pub(crate) selected: Rgb,
+    pub(crate) thin_line: Rgb,
+    pub(crate) thick_line: Rgb,
+    pub(crate) clue: Rgb,
+    pub(crate) entry: Rgb,
+    pub(crate) wrong: Rgb,
+    pub(crate) note: Rgb,
+    pub(crate) cell_colors: [Rgb; 5],
+}
+
+// matches the dark terminal the board is usually shown in, with cell colors
+// close to the terminal colors of the player's color slots
+pub(crate) const DARK: Palette = Palette {
+    background: [24, 24, 28],
+    selected: [60, 60, 72],
+    thin_line: [90, 90, 100],
+    thick_line: [220, 220, 230],
+    clue: [230, 200, 60],
+    entry: [90, 140, 255],
+    wrong: [235, 80, 80],
+    note: [140, 140, 150],
+    cell_colors: [[95, 0, 0], [0, 95, 0], [95, 95, 0], [0, 0, 95], [95, 0, 95]],
+};
+
+// black on white, for printing and light chat themes
+#[cfg(feature = "image")]
+pub(crate) const LIGHT: Palette = Palette {
+    background: [255, 255, 255],
+    selected: [220, 220, 235],
+    thin_line: [170, 170, 170],
+    thick_line: [20, 20, 20],
+    clue: [20, 20, 20],
+    entry: [30, 80, 170],
+    wrong: [200, 40, 40],
+    note: [130, 130, 130],
+    cell_colors: [
+        [250, 205, 205],
+        [205, 240, 205],
+        [245, 240, 190],
+        [205, 215, 250],
+        [240, 205, 240],
+    ],
+};
+
+// 5x7 digits 1-9, one string per row
+const GLYPHS: [[&str; 7]; 9] = [
+    [
+        "..#..", ".##..", "..#..", "..#..", "..#..", "..#..", ".###.",
+    ],
+    [
+        ".###.", "#...#", "....#", "...#.", "..#..", ".#...", "#####",
+    ],
+    [
+        "#####", "...#.", "..#..", "...#.", "....#", "#...#", ".###.",
+    ],
+    [
+        "...#.", "..##.", ".#.#.", "#..#.", "#####", "...#.", "...#.",
+    ],
+    [
+        "#####", "#....", "####.", "....#", "....#", "#...#", ".###.",
+    ],
+    [
+        "..##.", ".#...", "#....", "####.", "#...#", "#...#", ".###.",
+    ],
+    [
+        "#####", "....#", "...#.", "..#..", ".#...", ".#...", ".#...",
+    ],
+    [
+        ".###.", "#...#", "#...#", ".###.", "#...#", "#...#", ".###.",
+    ],
+    [
+        ".###.", "#...#", "#...#", ".####", "....#", "...#.", ".##..",
+    ],
+];
+
+// a pixel buffer at the supersampled size
+struct Canvas {
+    size: usize,
+    pixels: Vec<Rgb>,
+}
+
+impl Canvas {
+    fn new(size: usize) -> Self {
+        Canvas {
+            size,
+            pixels: vec![[0; 3]; size * size],
+        }
+    }
+
+    fn fill(&mut self, x: usize, y: usize, width: usize, height: usize, color: Rgb) {
+        for py in y..(y + height).min(self.size) {
+            for px in x..(x + width).min(self.size) {
+                self.pixels[py * self.size + px] = color;
+            }
+        }
+    }
+
+    // a digit centered on (x, y) with each font pixel `scale` pixels wide
+    fn digit(&mut self, num: u8, x: usize, y: usize, scale: usize, color: Rgb) {
+        let left = x - 5 * scale / 2;
+        let top = y - 7 * scale / 2;
+        for (row, line) in GLYPHS[(num - 1) as usize].iter().enumerate() {
+            for (col, bit) in line.chars().enumerate() {
+                if bit == '#' {
+                    self.fill(left + col * scale, top + row * scale, scale, scale, color);
+                }
+            }
+        }
+    }
+
+    // average each SUPERSAMPLE square into one rgb pixel
+    fn downsample(&self) -> Vec<u8> {
+        let size = self.size / SUPERSAMPLE;
+        let mut out = Vec::with_capacity(size * size * 3);
+        for y in 0..size {
+            for x in 0..size {
+                for channel in 0..3 {
+                    let mut sum = 0;
+                    for dy in 0..SUPERSAMPLE {
+                        for dx in 0..SUPERSAMPLE {
+                            let pixel = (y * SUPERSAMPLE + dy) * self.size + x * SUPERSAMPLE + dx;
+                            sum += self.pixels[pixel][channel] as usize;
+                        }
+                    }
+                    out.push((sum / (SUPERSAMPLE * SUPERSAMPLE)) as u8);
+                }
+            }
+        }
+        out
+    }
+}
+
+// the board as rgb pixels, 9 * cell_pixels square
+pub(crate) fn rasterize(
+    grid: &Grid,
+    selected: Option<(usize, usize)>,
+    cell_pixels: usize,
+    palette: &Palette,
+) -> Vec<u8> {
+    let cell = cell_pixels * SUPERSAMPLE;
+    let mut canvas = Canvas::new(9 * cell);
+
+    for (row, cells) in grid.iter().enumerate() {
+        for (col, &cell_value) in cells.iter().enumerate() {
+            let background = match cell_value.color() {
+                _ if Some((row, col)) == selected => palette.selected,
+                0 => palette.background,
+                color => palette.cell_colors[(color - 1) as usize],
+            };
+            canvas.fill(col * cell, row * cell, cell, cell, background);
+
+            let (x, y) = (col * cell + cell / 2, row * cell + cell / 2);
+            match cell_value.value() {
+                0 => {
+                    // notes in a 3x3 pattern, each in its third of the cell
+                    for num in cell_value.notes() {
+                        let index = (num - 1) as usize;
+                        let note_x = col * cell + cell / 6 + index % 3 * cell / 3;
+                        let note_y = row * cell + cell / 6 + index / 3 * cell / 3;
+                        canvas.digit(num, note_x, note_y, (cell / 32).max(1), palette.note);
+                    }
+                }
+                num => {
+                    let color = if cell_value.is_clue() {
+                        palette.clue
+                    } else if cell_value.possible_wrong() {
+                        palette.wrong
+                    } else {
+                        palette.entry
+                    };
+                    canvas.digit(num, x, y, (cell / 12).max(1), color);
+                }
+            }
+        }
+    }
+
+    for line in 0..=9 {
+        let (width, color) = if line % 3 == 0 {
+            (3 * SUPERSAMPLE, palette.thick_line)
+        } else {
+            (SUPERSAMPLE, palette.thin_line)
+        };
+        let at = (line * cell)
+            .saturating_sub(width / 2)
+            .min(canvas.size - width);
+        canvas.fill(at, 0, width, canvas.size, color);
+        canvas.fill(0, at, canvas.size, width, color);
+    }
+
+    canvas.downsample()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::Puzzle;
+
+    #[test]
+    fn test_rasterize_fills_the_square() {
+        let grid = Puzzle::new(crate::Difficulty::Easy).grid();
+        for cell_pixels in [10, 48] {
+            let pixels = rasterize(&grid, None, cell_pixels, &DARK);
+            assert_eq!(pixels.len(), 9 * cell_pixels * 9 * cell_pixels * 3);
+        }
+        assert_ne!(
+            rasterize(&grid, None, 20, &DARK),
+            rasterize(&grid, Some((0, 0)), 20, &DARK)
+        );
+    }
+}
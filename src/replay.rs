@@ -0,0 +1,228 @@
+use serde::{Deserialize, Serialize};
+use std::time::Duration;
+
+use crate::{puzzle::Grid, Cell};
+
+// the cells a move left behind
+type Cells = Vec<(usize, usize, Cell)>;
+
+// every cell change of a game with when it happened, enough to play the
+// game back from its first position
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub(crate) struct MoveLog {
+    start: Grid,
+    moves: Vec<(Duration, Cells)>,
+    hints: Vec<(Duration, usize, usize)>, // the cells hints filled in
+}
+
+impl MoveLog {
+    pub(crate) fn new(start: Grid) -> Self {
+        MoveLog {
+            start,
+            moves: Vec::new(),
+            hints: Vec::new(),
+        }
+    }
+
+    // the cell a hint just filled, on top of the move that filled it
+    pub(crate) fn record_hint(&mut self, at: Duration, row: usize, col: usize) {
+        self.hints.push((at, row, col));
+    }
+
+    pub(crate) fn hints(&self) -> &[(Duration, usize, usize)] {
+        &self.hints
+    }
+
+    // every number put in a cell with when, in the order they went in,
+    // including ones later erased or brought back by undo and redo
+    pub(crate) fn entries(&self) -> Vec<(Duration, usize, usize, u8)> {
+        let mut grid = self.start;
+        let mut entries = Vec::new();
+        for (at, cells) in &self.moves {
+            for &(row, col, cell) in cells {
+                if cell.value() != 0 && cell.value() != grid[row][col].value() {
+                    entries.push((*at, row, col, cell.value()));
+                }
+                grid[row][col] = cell;
+            }
+        }
+        entries
+    }
+
+    // log whatever differs between the two grids as one move
+    pub(crate) fn record(&mut self, at: Duration, before: &Grid, after: &Grid) {
+        let cells: Cells = (0..9)
+            .flat_map(|row| (0..9).map(move |col| (row, col)))
+            .filter(|&(row, col)| before[row][col] != after[row][col])
+            .map(|(row, col)| (row, col, after[row][col]))
+            .collect();
+
+        if !cells.is_empty() {
+            self.moves.push((at, cells));
+        }
+    }
+
+    // when the board first held one more filled cell than the start, then two
+    // more and so on, the pace a ghost of this game races at
+    pub(crate) fn fill_times(&self) -> Vec<Duration> {
+        let filled = |grid: &Grid| {
+            grid.iter()
+                .flatten()
+                .filter(|cell| cell.value() != 0)
+                .count()
+        };
+        let mut grid = self.start;
+        let start = filled(&grid);
+        let mut times = Vec::new();
+
+        for (at, cells) in &self.moves {
+            for &(row, col, cell) in cells {
+                grid[row][col] = cell;
+            }
+            while start + times.len() < filled(&grid) {
+                times.push(*at);
+            }
+        }
+        times
+    }
+}
+
+const MAX_SPEED: u32 = 64;
+
+// plays a move log back on its own clock
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub(crate) struct Replay {
+    log: MoveLog,
+    grid: Grid,
+    next: usize, // index of the first move not shown yet
+    clock: Duration,
+    speed: u32, // multiple of real time
+    paused: bool,
+}
+
+impl Replay {
+    pub(crate) fn new(log: MoveLog) -> Self {
+        Replay {
+            grid: log.start,
+            log,
+            next: 0,
+            clock: Duration::default(),
+            speed: 4,
+            paused: false,
+        }
+    }
+
+    pub(crate) fn grid(&self) -> Grid {
+        self.grid
+    }
+
+    pub(crate) fn clock(&self) -> Duration {
+        self.clock
+    }
+
+    pub(crate) fn speed(&self) -> u32 {
+        self.speed
+    }
+
+    pub(crate) fn is_paused(&self) -> bool {
+        self.paused
+    }
+
+    pub(crate) fn is_finished(&self) -> bool {
+        self.next == self.log.moves.len()
+    }
+
+    pub(crate) fn toggle_pause(&mut self) {
+        self.paused = !self.paused;
+    }
+
+    pub(crate) fn faster(&mut self) {
+        self.speed = (self.speed * 2).min(MAX_SPEED);
+    }
+
+    pub(crate) fn slower(&mut self) {
+        self.speed = (self.speed / 2).max(1);
+    }
+
+    // move the clock on by `elapsed` real time and show every move it passes
+    pub(crate) fn advance(&mut self, elapsed: Duration) {
+        if self.paused {
+            return;
+        }
+
+        self.clock += elapsed * self.speed;
+        while let Some((at, cells)) = self.log.moves.get(self.next) {
+            if *at > self.clock {
+                break;
+            }
+            for &(row, col, cell) in cells {
+                self.grid[row][col] = cell;
+            }
+            self.next += 1;
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_replay_shows_moves_as_the_clock_passes_them() {
+        let start = [[Cell::new(0, false); 9]; 9];
+        let mut first = start;
+        first[0][0] = Cell::new(5, false);
+        let mut second = first;
+        second[8][8] = Cell::new(3, false);
+
+        let mut log = MoveLog::new(start);
+        log.record(Duration::from_secs(4), &start, &first);
+        log.record(Duration::from_secs(5), &first, &first);
+        log.record(Duration::from_secs(20), &first, &second);
+        assert_eq!(log.moves.len(), 2);
+
+        let mut replay = Replay::new(log);
+        replay.advance(Duration::from_secs(1)); // 4x speed
+        assert_eq!(replay.grid(), first);
+        assert!(!replay.is_finished());
+
+        replay.toggle_pause();
+        replay.advance(Duration::from_secs(10));
+        assert_eq!(replay.grid(), first);
+
+        replay.toggle_pause();
+        replay.faster();
+        replay.advance(Duration::from_secs(2));
+        assert_eq!(replay.grid(), second);
+        assert!(replay.is_finished());
+    }
+
+    #[test]
+    fn test_fill_times_count_new_highs_only() {
+        let start = [[Cell::new(0, false); 9]; 9];
+        let mut first = start;
+        first[0][0] = Cell::new(5, false);
+        let mut erased = first;
+        erased[0][0] = Cell::new(0, false);
+        let mut second = first;
+        second[8][8] = Cell::new(3, false);
+
+        let mut log = MoveLog::new(start);
+        log.record(Duration::from_secs(4), &start, &first);
+        log.record(Duration::from_secs(6), &first, &erased);
+        log.record(Duration::from_secs(8), &erased, &first);
+        log.record(Duration::from_secs(20), &first, &second);
+        assert_eq!(
+            log.fill_times(),
+            vec![Duration::from_secs(4), Duration::from_secs(20)]
+        );
+        assert_eq!(
+            log.entries(),
+            vec![
+                (Duration::from_secs(4), 0, 0, 5),
+                (Duration::from_secs(8), 0, 0, 5),
+                (Duration::from_secs(20), 8, 8, 3),
+            ]
+        );
+    }
+}
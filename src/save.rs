@@ -0,0 +1,386 @@
+use serde::{Deserialize, Serialize};
+use serde_json::{Map, Value};
+use std::{
+    fs,
+    io::{self, Write},
+    path::{Path, PathBuf},
+    sync::Mutex,
+    time::Duration,
+};
+
+use crate::{replay::MoveLog, Difficulty, Puzzle};
+
+const SAVE_FILE: &str = "save.json";
+// named saves kept with `:save <name>`, one file each
+const SLOTS_DIR: &str = "saves";
+// the game in play when the game panicked, offered back on the next start
+const RECOVERY_FILE: &str = "recovery.json";
+// written into every save as "version"; saves from before it was have none
+// and count as version 1
+const SAVE_VERSION: u64 = 4;
+// each turns a save of one version into the next, the first one version 1
+const MIGRATIONS: [fn(&mut Map<String, Value>); SAVE_VERSION as usize - 1] = [
+    // daily puzzles came in
+    |save| {
+        save.entry("daily").or_insert(Value::Null);
+    },
+    // the move log started keeping hints
+    |save| {
+        if let Some(Value::Object(log)) = save.get_mut("move_log") {
+            log.entry("hints").or_insert(Value::Array(Vec::new()));
+        }
+    },
+    // the games in other tabs came along
+    |save| {
+        save.entry("tabs").or_insert(Value::Array(Vec::new()));
+        save.entry("tab").or_insert(0.into());
+    },
+];
+
+// kept up to date by the game loop for the panic hook, which can't reach the
+// game itself, with the data directory to write it to
+static IN_PLAY: Mutex<Option<(PathBuf, SavedGame)>> = Mutex::new(None);
+
+// a game left unfinished on quitting, with everything needed to carry on: the
+// board with its notes and undo history, the clock and the moves for replays
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub(crate) struct SavedGame {
+    pub(crate) puzzle: Puzzle,
+    pub(crate) elapsed: Duration,
+    pub(crate) level: Difficulty,
+    pub(crate) custom_clues: Option<usize>,
+    pub(crate) daily: Option<u64>,
+    pub(crate) move_log: MoveLog,
+    pub(crate) selected: (usize, usize),
+    // the unfinished games in the other tabs in order, this one sitting at
+    // `tab` among them; always empty in a named save
+    pub(crate) tabs: Vec<SavedGame>,
+    pub(crate) tab: usize,
+}
+
+impl SavedGame {
+    pub(crate) fn path(dir: &Path) -> PathBuf {
+        dir.join(SAVE_FILE)
+    }
+
+    // None when no game was left unfinished
+    pub(crate) fn load(dir: &Path) -> io::Result<Option<SavedGame>> {
+        Self::read(&Self::path(dir))
+    }
+
+    pub(crate) fn save(&self, dir: &Path) -> io::Result<()> {
+        self.write(&Self::path(dir))
+    }
+
+    fn read(path: &Path) -> io::Result<Option<SavedGame>> {
+        match fs::read_to_string(path) {
+            Ok(json) => Self::migrate(serde_json::from_str(&json)?).map(Some),
+            Err(err) if err.kind() == io::ErrorKind::NotFound => Ok(None),
+            Err(err) => Err(err),
+        }
+    }
+
+    // written next to the save and renamed over it, so a crash half way
+    // leaves the old save whole rather than a truncated one
+    fn write(&self, path: &Path) -> io::Result<()> {
+        if let Some(parent) = path.parent() {
+            fs::create_dir_all(parent)?;
+        }
+        let mut save = serde_json::to_value(self)?;
+        if let Value::Object(fields) = &mut save {
+            fields.insert("version".into(), SAVE_VERSION.into());
+        }
+        let json = save.to_string();
+        let temp = path.with_extension("json.tmp");
+        let mut file = fs::File::create(&temp)?;
+        file.write_all(json.as_bytes())?;
+        file.sync_all()?;
+        fs::rename(temp, path)
+    }
+
+    // brings a save of any earlier version up to date before reading it, and
+    // refuses one from a later version rather than guess at what it holds
+    fn migrate(mut save: Value) -> io::Result<SavedGame> {
+        let Value::Object(fields) = &mut save else {
+            return Err(io::Error::new(
+                io::ErrorKind::InvalidData,
+                "not a saved game",
+            ));
+        };
+        let version = match fields.remove("version") {
+            None => 1,
+            Some(version) => version.as_u64().filter(|&v| v >= 1).ok_or_else(|| {
+                io::Error::new(io::ErrorKind::InvalidData, "the save version is not valid")
+            })?,
+        };
+        if version > SAVE_VERSION {
+            return Err(io::Error::new(
+                io::ErrorKind::InvalidData,
+                format!("saved by a newer rsudoku, in format version {}", version),
+            ));
+        }
+        for migration in &MIGRATIONS[version as usize - 1..] {
+            migration(fields);
+        }
+        Ok(serde_json::from_value(save)?)
+    }
+
+    // names are kept to letters, digits, - and _ so they make safe file names
+    fn slot_path(dir: &Path, name: &str) -> io::Result<PathBuf> {
+        let is_valid = !name.is_empty()
+            && name
+                .chars()
+                .all(|c| c.is_ascii_alphanumeric() || c == '-' || c == '_');
+        if !is_valid {
+            return Err(io::Error::new(
+                io::ErrorKind::InvalidInput,
+                format!("save names take letters, digits, - and _, not {}", name),
+            ));
+        }
+        Ok(dir.join(SLOTS_DIR).join(name).with_extension("json"))
+    }
+
+    // replaces an earlier save of the same name
+    pub(crate) fn save_slot(&self, dir: &Path, name: &str) -> io::Result<()> {
+        self.write(&Self::slot_path(dir, name)?)
+    }
+
+    // loading leaves the save in place, to come back to again
+    pub(crate) fn load_slot(dir: &Path, name: &str) -> io::Result<SavedGame> {
+        Self::read(&Self::slot_path(dir, name)?)?
+            .ok_or_else(|| io::Error::new(io::ErrorKind::NotFound, "there is no save by that name"))
+    }
+
+    // every named save by name, leaving out files that don't read as one
+    pub(crate) fn slots(dir: &Path) -> io::Result<Vec<(String, SavedGame)>> {
+        let entries = match fs::read_dir(dir.join(SLOTS_DIR)) {
+            Ok(entries) => entries,
+            Err(err) if err.kind() == io::ErrorKind::NotFound => return Ok(Vec::new()),
+            Err(err) => return Err(err),
+        };
+        let mut slots = Vec::new();
+        for entry in entries {
+            let path = entry?.path();
+            // leaving out a write still in progress
+            if path.extension().is_none_or(|ext| ext != "json") {
+                continue;
+            }
+            let Some(name) = path.file_stem().and_then(|name| name.to_str()) else {
+                continue;
+            };
+            if let Ok(Some(game)) = Self::read(&path) {
+                slots.push((name.to_string(), game));
+            }
+        }
+        slots.sort_by(|(a, _), (b, _)| a.cmp(b));
+        Ok(slots)
+    }
+
+    // how much of what the clues left empty has been filled, from 0 to 1
+    pub(crate) fn progress(&self) -> f64 {
+        let grid = self.puzzle.grid();
+        let filled = grid
+            .iter()
+            .flatten()
+            .filter(|cell| cell.value() != 0)
+            .count();
+        let clues = self.puzzle.clues();
+        match 81 - clues {
+            0 => 1.0,
+            empty => filled.saturating_sub(clues) as f64 / empty as f64,
+        }
+    }
+
+    // done once the game is picked up again, so finishing it doesn't leave
+    // an old copy to resume
+    pub(crate) fn remove(dir: &Path) -> io::Result<()> {
+        remove_file(&Self::path(dir))
+    }
+
+    pub(crate) fn recovery_path(dir: &Path) -> PathBuf {
+        dir.join(RECOVERY_FILE)
+    }
+
+    pub(crate) fn load_recovery(dir: &Path) -> io::Result<Option<SavedGame>> {
+        Self::read(&Self::recovery_path(dir))
+    }
+
+    pub(crate) fn remove_recovery(dir: &Path) -> io::Result<()> {
+        remove_file(&Self::recovery_path(dir))
+    }
+
+    // the game for `write_recovery` to keep should the game panic, None when
+    // nothing is in play
+    pub(crate) fn keep_for_recovery(game: Option<(&Path, SavedGame)>) {
+        let game = game.map(|(dir, game)| (dir.to_path_buf(), game));
+        match IN_PLAY.lock() {
+            Ok(mut in_play) => *in_play = game,
+            Err(poisoned) => *poisoned.into_inner() = game,
+        }
+    }
+}
+
+// called from the panic hook; a panic while the game was being kept leaves
+// the lock taken, and then there is nothing whole to write
+pub(crate) fn write_recovery() -> io::Result<()> {
+    let Ok(in_play) = IN_PLAY.try_lock() else {
+        return Ok(());
+    };
+    match &*in_play {
+        Some((dir, game)) => game.write(&SavedGame::recovery_path(dir)),
+        None => Ok(()),
+    }
+}
+
+fn remove_file(path: &Path) -> io::Result<()> {
+    match fs::remove_file(path) {
+        Err(err) if err.kind() == io::ErrorKind::NotFound => Ok(()),
+        result => result,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_save_and_load_round_trip() {
+        let dir = std::env::temp_dir().join(format!("rsudoku-save-{}", std::process::id()));
+        assert_eq!(SavedGame::load(&dir).unwrap(), None);
+
+        let mut puzzle = Puzzle::new(Difficulty::Easy);
+        let (row, col) = puzzle.next_empty_cell(0, 0).unwrap();
+        let before = puzzle.grid();
+        puzzle.set_auto_notes(true);
+        puzzle.insert_number(row, col, 1).unwrap();
+        puzzle.set_color(row, col, 2);
+        let mut move_log = MoveLog::new(before);
+        move_log.record(Duration::from_secs(4), &before, &puzzle.grid());
+
+        let game = SavedGame {
+            puzzle,
+            elapsed: Duration::from_millis(61_250),
+            level: Difficulty::Easy,
+            custom_clues: None,
+            daily: Some(20_740),
+            move_log,
+            selected: (row, col),
+            tabs: Vec::new(),
+            tab: 0,
+        };
+        game.save(&dir).unwrap();
+        assert_eq!(SavedGame::load(&dir).unwrap(), Some(game));
+
+        SavedGame::remove(&dir).unwrap();
+        assert_eq!(SavedGame::load(&dir).unwrap(), None);
+        SavedGame::remove(&dir).unwrap();
+        fs::remove_dir_all(&dir).unwrap();
+    }
+
+    #[test]
+    fn test_old_saves_are_migrated() {
+        let dir = std::env::temp_dir().join(format!("rsudoku-versions-{}", std::process::id()));
+        let puzzle = Puzzle::new(Difficulty::Medium);
+        let game = SavedGame {
+            move_log: MoveLog::new(puzzle.grid()),
+            puzzle,
+            elapsed: Duration::from_secs(30),
+            level: Difficulty::Medium,
+            custom_clues: None,
+            daily: None,
+            selected: (4, 4),
+            tabs: Vec::new(),
+            tab: 0,
+        };
+        game.save(&dir).unwrap();
+        let json = fs::read_to_string(SavedGame::path(&dir)).unwrap();
+        assert!(json.contains("\"version\":4"));
+
+        // version 1 had no version, no daily puzzles, no hints in the log and
+        // no tabs
+        let mut old = serde_json::to_value(&game).unwrap();
+        old.as_object_mut().unwrap().remove("daily");
+        old.as_object_mut().unwrap().remove("tabs");
+        old.as_object_mut().unwrap().remove("tab");
+        old["move_log"].as_object_mut().unwrap().remove("hints");
+        fs::write(SavedGame::path(&dir), old.to_string()).unwrap();
+        assert_eq!(SavedGame::load(&dir).unwrap(), Some(game));
+
+        old["version"] = 99.into();
+        fs::write(SavedGame::path(&dir), old.to_string()).unwrap();
+        assert_eq!(
+            SavedGame::load(&dir).unwrap_err().to_string(),
+            "saved by a newer rsudoku, in format version 99"
+        );
+        fs::remove_dir_all(&dir).unwrap();
+    }
+
+    #[test]
+    fn test_named_saves() {
+        let dir = std::env::temp_dir().join(format!("rsudoku-slots-{}", std::process::id()));
+        assert_eq!(SavedGame::slots(&dir).unwrap(), Vec::new());
+
+        let puzzle = Puzzle::new(Difficulty::Hard);
+        let game = SavedGame {
+            move_log: MoveLog::new(puzzle.grid()),
+            puzzle,
+            elapsed: Duration::from_secs(90),
+            level: Difficulty::Hard,
+            custom_clues: None,
+            daily: None,
+            selected: (0, 0),
+            tabs: Vec::new(),
+            tab: 0,
+        };
+        assert_eq!(game.progress(), 0.0);
+        game.save_slot(&dir, "weekend-puzzle").unwrap();
+        game.save_slot(&dir, "b").unwrap();
+        assert_eq!(SavedGame::load_slot(&dir, "weekend-puzzle").unwrap(), game);
+        assert_eq!(
+            SavedGame::load_slot(&dir, "nope").unwrap_err().to_string(),
+            "there is no save by that name"
+        );
+        assert!(game.save_slot(&dir, "../escape").is_err());
+        // a write cut short is left behind as a temp file, not a save
+        fs::write(dir.join("saves").join("c.json.tmp"), "{").unwrap();
+
+        let names: Vec<String> = SavedGame::slots(&dir)
+            .unwrap()
+            .into_iter()
+            .map(|(name, _)| name)
+            .collect();
+        assert_eq!(names, ["b", "weekend-puzzle"]);
+        fs::remove_dir_all(&dir).unwrap();
+    }
+
+    #[test]
+    fn test_recovery_is_written_from_the_game_kept() {
+        let dir = std::env::temp_dir().join(format!("rsudoku-recovery-{}", std::process::id()));
+        let puzzle = Puzzle::new(Difficulty::Easy);
+        let game = SavedGame {
+            move_log: MoveLog::new(puzzle.grid()),
+            puzzle,
+            elapsed: Duration::from_secs(12),
+            level: Difficulty::Easy,
+            custom_clues: None,
+            daily: None,
+            selected: (2, 3),
+            tabs: Vec::new(),
+            tab: 0,
+        };
+        SavedGame::keep_for_recovery(None);
+        write_recovery().unwrap();
+        assert_eq!(SavedGame::load_recovery(&dir).unwrap(), None);
+
+        SavedGame::keep_for_recovery(Some((&dir, game.clone())));
+        write_recovery().unwrap();
+        SavedGame::keep_for_recovery(None);
+        assert_eq!(SavedGame::load_recovery(&dir).unwrap(), Some(game));
+        // the save of a game quit normally is left alone
+        assert_eq!(SavedGame::load(&dir).unwrap(), None);
+
+        SavedGame::remove_recovery(&dir).unwrap();
+        assert_eq!(SavedGame::load_recovery(&dir).unwrap(), None);
+        fs::remove_dir_all(&dir).unwrap();
+    }
+}
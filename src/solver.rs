@@ -0,0 +1,237 @@
+use alloc::{
+    format,
+    string::{String, ToString},
+    vec::Vec,
+};
+
+use crate::{
+    puzzle::{candidates, Grid},
+    Cell, Difficulty,
+};
+
+const SIZE: usize = 9;
+// what a cell costs in a rating when the techniques can't place it
+const STUCK_CELL_SCORE: u32 = 10;
+
+// the logical techniques the solver knows, simplest first
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+pub enum Technique {
+    // the cell has a single candidate left
+    NakedSingle,
+    // the number fits in a single cell of a row, column or box
+    HiddenSingle,
+}
+
+impl Technique {
+    pub fn name(&self) -> &'static str {
+        match self {
+            Technique::NakedSingle => "Naked single",
+            Technique::HiddenSingle => "Hidden single",
+        }
+    }
+
+    // what a placement with this technique adds to a rating
+    pub fn score(&self) -> u32 {
+        match self {
+            Technique::NakedSingle => 1,
+            Technique::HiddenSingle => 2,
+        }
+    }
+}
+
+// how hard a board is by the techniques it takes to finish
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Rating {
+    pub difficulty: Difficulty,
+    // the technique scores of every placement, plus STUCK_CELL_SCORE for
+    // each cell left when the techniques run out
+    pub score: u32,
+    pub hardest: Option<Technique>,
+    // cells the techniques could not place
+    pub stuck: usize,
+}
+
+// Easy takes naked singles only, Medium hidden singles as well; a board the
+// techniques can't finish is Hard, or Expert when most of it is left
+pub fn rate(grid: &Grid) -> Rating {
+    let mut grid = *grid;
+    let mut score = 0;
+    let mut hardest = None;
+    while let Some(step) = next_step(&grid) {
+        grid[step.row][step.col] = Cell::new(step.num, false);
+        score += step.technique.score();
+        hardest = hardest.max(Some(step.technique));
+    }
+
+    let stuck = grid
+        .iter()
+        .flatten()
+        .filter(|cell| cell.value() == 0)
+        .count();
+    let difficulty = match (stuck, hardest) {
+        (0, Some(Technique::HiddenSingle)) => Difficulty::Medium,
+        (0, _) => Difficulty::Easy,
+        (1..=30, _) => Difficulty::Hard,
+        _ => Difficulty::Expert,
+    };
+    Rating {
+        difficulty,
+        score: score + stuck as u32 * STUCK_CELL_SCORE,
+        hardest,
+        stuck,
+    }
+}
+
+// one placement found by a technique, with the cells that justify it
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Step {
+    pub technique: Technique,
+    pub row: usize,
+    pub col: usize,
+    pub num: u8,
+    pub cells: Vec<(usize, usize)>,
+}
+
+impl Step {
+    // why the number goes there, e.g. "the only place for 4 in box 7"
+    pub fn reason(&self) -> String {
+        match self.technique {
+            Technique::NakedSingle => "the only candidate left".to_string(),
+            Technique::HiddenSingle => {
+                let (row, col) = (self.row, self.col);
+                let unit = if self.cells.iter().all(|&(r, _)| r == row) {
+                    format!("row {}", row + 1)
+                } else if self.cells.iter().all(|&(_, c)| c == col) {
+                    format!("column {}", col + 1)
+                } else {
+                    format!("box {}", row / 3 * 3 + col / 3 + 1)
+                };
+                format!("the only place for {} in {}", self.num, unit)
+            }
+        }
+    }
+}
+
+// the next placement the techniques can prove, trying the simplest first
+pub fn next_step(grid: &Grid) -> Option<Step> {
+    naked_single(grid).or_else(|| hidden_single(grid))
+}
+
+fn naked_single(grid: &Grid) -> Option<Step> {
+    all_cells().find_map(|(row, col)| match candidates(grid, row, col).as_slice() {
+        [num] => Some(Step {
+            technique: Technique::NakedSingle,
+            row,
+            col,
+            num: *num,
+            cells: peers(row, col),
+        }),
+        _ => None,
+    })
+}
+
+fn hidden_single(grid: &Grid) -> Option<Step> {
+    for unit in units() {
+        for num in 1..=SIZE as u8 {
+            let spots: Vec<(usize, usize)> = unit
+                .iter()
+                .copied()
+                .filter(|&(row, col)| candidates(grid, row, col).contains(&num))
+                .collect();
+
+            if let [(row, col)] = spots.as_slice() {
+                return Some(Step {
+                    technique: Technique::HiddenSingle,
+                    row: *row,
+                    col: *col,
+                    num,
+                    cells: unit,
+                });
+            }
+        }
+    }
+    None
+}
+
+fn all_cells() -> impl Iterator<Item = (usize, usize)> {
+    (0..SIZE).flat_map(|row| (0..SIZE).map(move |col| (row, col)))
+}
+
+// every row, column and box
+fn units() -> Vec<Vec<(usize, usize)>> {
+    let rows = (0..SIZE).map(|row| (0..SIZE).map(|col| (row, col)).collect());
+    let cols = (0..SIZE).map(|col| (0..SIZE).map(|row| (row, col)).collect());
+    let boxes = (0..SIZE).map(|b| {
+        all_cells()
+            .filter(|&(row, col)| row / 3 * 3 + col / 3 == b)
+            .collect()
+    });
+    rows.chain(cols).chain(boxes).collect()
+}
+
+// the cells sharing a row, column or box with (row, col)
+fn peers(row: usize, col: usize) -> Vec<(usize, usize)> {
+    all_cells()
+        .filter(|&(r, c)| {
+            (r, c) != (row, col) && (r == row || c == col || (r / 3 == row / 3 && c / 3 == col / 3))
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::Puzzle;
+
+    fn grid(rows: [[u8; 9]; 9]) -> Grid {
+        rows.map(|row| row.map(|value| Cell::new(value, value != 0)))
+    }
+
+    #[test]
+    fn test_naked_single_comes_first() {
+        let mut rows = [[0; 9]; 9];
+        rows[0] = [1, 2, 3, 4, 5, 6, 7, 8, 0];
+        let step = next_step(&grid(rows)).unwrap();
+        assert_eq!(step.technique, Technique::NakedSingle);
+        assert_eq!((step.row, step.col, step.num), (0, 8, 9));
+        assert_eq!(step.cells.len(), 20);
+    }
+
+    #[test]
+    fn test_hidden_single() {
+        // 1 is ruled out of every cell of the top left box but one
+        let mut rows = [[0; 9]; 9];
+        rows[1][4] = 1;
+        rows[2][7] = 1;
+        rows[3][0] = 1;
+        rows[6][1] = 1;
+        let step = hidden_single(&grid(rows)).unwrap();
+        assert_eq!((step.row, step.col, step.num), (0, 2, 1));
+        assert_eq!(step.reason(), "the only place for 1 in row 1");
+    }
+
+    #[test]
+    fn test_rating_follows_the_techniques() {
+        // a solved board with its last row emptied only needs naked singles
+        let puzzle = Puzzle::new(Difficulty::Easy).to_solved();
+        let mut board = puzzle.grid();
+        board[8] = [Cell::new(0, false); 9];
+        let rating = rate(&board);
+        assert_eq!(rating.difficulty, Difficulty::Easy);
+        assert_eq!(rating.score, 9);
+        assert_eq!(rating.hardest, Some(Technique::NakedSingle));
+
+        let empty = rate(&grid([[0; 9]; 9]));
+        assert_eq!(empty.difficulty, Difficulty::Expert);
+        assert_eq!((empty.stuck, empty.score), (81, 81 * STUCK_CELL_SCORE));
+    }
+
+    #[test]
+    fn test_steps_agree_with_the_solution() {
+        let mut puzzle = Puzzle::new(Difficulty::Easy);
+        while let Some(step) = next_step(&puzzle.grid()) {
+            puzzle.insert_number(step.row, step.col, step.num).unwrap();
+        }
+        assert!(puzzle.wrong_entries().is_empty());
+    }
+}
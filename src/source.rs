@@ -0,0 +1,196 @@
+use rand::{rngs::StdRng, SeedableRng};
+use std::{fmt, fs, io, path::Path};
+
+use crate::{rate, Difficulty, Puzzle};
+
+// where the puzzles of new games come from, see `App::with_source`
+pub trait PuzzleSource: fmt::Debug {
+    // the next puzzle to play at `difficulty`, or why there is none
+    fn next_puzzle(&mut self, difficulty: Difficulty) -> io::Result<Puzzle>;
+}
+
+// new puzzles from the generator, keeping the level's clue count
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Generator {
+    rng: StdRng,
+}
+
+impl Generator {
+    #[cfg(feature = "entropy")]
+    pub fn new() -> Self {
+        Generator {
+            rng: StdRng::from_entropy(),
+        }
+    }
+
+    // the same seed deals the same puzzles in the same order
+    pub fn seeded(seed: u64) -> Self {
+        Generator {
+            rng: StdRng::seed_from_u64(seed),
+        }
+    }
+
+    // a puzzle with any clue count, see `Puzzle::with_rng`
+    pub fn with_clues(&mut self, clues: usize) -> Puzzle {
+        Puzzle::with_rng(clues, &mut self.rng)
+    }
+}
+
+#[cfg(feature = "entropy")]
+impl Default for Generator {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl PuzzleSource for Generator {
+    fn next_puzzle(&mut self, difficulty: Difficulty) -> io::Result<Puzzle> {
+        Ok(self.with_clues(difficulty as usize))
+    }
+}
+
+// the boards of a pack file, one per line as 81 cells, skipping blank lines
+// and lines starting with #; a board with more than one solution is refused,
+// or a right answer could be marked wrong against the one kept
+pub(crate) fn read_puzzles(text: &str) -> io::Result<Vec<Puzzle>> {
+    text.lines()
+        .enumerate()
+        .map(|(i, line)| (i, line.trim()))
+        .filter(|(_, line)| !line.is_empty() && !line.starts_with('#'))
+        .map(|(i, line)| {
+            let puzzle = line
+                .parse::<Puzzle>()
+                .and_then(|puzzle| match puzzle.solution_count() {
+                    1 => Ok(puzzle),
+                    _ => Err("The puzzle has more than one solution".to_string()),
+                });
+            puzzle.map_err(|err| {
+                io::Error::new(
+                    io::ErrorKind::InvalidData,
+                    format!("line {}: {}", i + 1, err),
+                )
+            })
+        })
+        .collect()
+}
+
+// puzzles read from a file, one per line as 81 cells, each going to the
+// difficulty the solver rates it at; they are dealt in the file's order and
+// start over once every one of a difficulty was played
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct PuzzlePack {
+    puzzles: Vec<(Difficulty, Puzzle)>,
+    next: usize, // where the search for the next puzzle starts
+}
+
+impl PuzzlePack {
+    pub fn new(puzzles: Vec<Puzzle>) -> Self {
+        PuzzlePack {
+            puzzles: puzzles
+                .into_iter()
+                .map(|puzzle| (rate(&puzzle.grid()).difficulty, puzzle))
+                .collect(),
+            next: 0,
+        }
+    }
+
+    pub fn load(path: &Path) -> io::Result<Self> {
+        let puzzles = read_puzzles(&fs::read_to_string(path)?)?;
+        if puzzles.is_empty() {
+            return Err(io::Error::new(
+                io::ErrorKind::InvalidData,
+                "there are no puzzles in the pack",
+            ));
+        }
+        Ok(Self::new(puzzles))
+    }
+
+    pub fn len(&self) -> usize {
+        self.puzzles.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.puzzles.is_empty()
+    }
+}
+
+impl PuzzleSource for PuzzlePack {
+    fn next_puzzle(&mut self, difficulty: Difficulty) -> io::Result<Puzzle> {
+        let count = self.puzzles.len();
+        let found = (0..count)
+            .map(|offset| (self.next + offset) % count)
+            .find(|&i| self.puzzles[i].0 == difficulty);
+        match found {
+            Some(i) => {
+                self.next = (i + 1) % count;
+                Ok(self.puzzles[i].1.clone())
+            }
+            None => Err(io::Error::new(
+                io::ErrorKind::NotFound,
+                format!(
+                    "the pack has no {} puzzles",
+                    difficulty.to_string().to_lowercase()
+                ),
+            )),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_seeded_generators_repeat() {
+        let mut a = Generator::seeded(3);
+        let mut b = Generator::seeded(3);
+        let puzzle = a.next_puzzle(Difficulty::Hard).unwrap();
+        assert_eq!(puzzle, b.next_puzzle(Difficulty::Hard).unwrap());
+        assert_eq!(puzzle.clues(), Difficulty::Hard as usize);
+    }
+
+    #[test]
+    fn test_packs_deal_by_rating_in_order() {
+        let mut generator = Generator::seeded(1);
+        let puzzles: Vec<Puzzle> = (0..3).map(|_| generator.with_clues(40)).collect();
+        let dir = std::env::temp_dir().join(format!("rsudoku-pack-{}", std::process::id()));
+        fs::create_dir_all(&dir).unwrap();
+        let path = dir.join("pack.txt");
+        let lines: Vec<String> = puzzles.iter().map(|p| p.to_board(false)).collect();
+        fs::write(
+            &path,
+            format!("# three easy ones\n\n{}\n", lines.join("\n")),
+        )
+        .unwrap();
+
+        let mut pack = PuzzlePack::load(&path).unwrap();
+        assert_eq!(pack.len(), 3);
+        let difficulty = rate(&puzzles[0].grid()).difficulty;
+        let dealt = pack.next_puzzle(difficulty).unwrap();
+        assert_eq!(dealt.grid(), puzzles[0].grid());
+        // once around the pack the first one comes up again
+        let mut seen = 1;
+        while pack.next_puzzle(difficulty).unwrap().grid() != puzzles[0].grid() {
+            seen += 1;
+        }
+        assert!(seen <= 3);
+        assert_eq!(
+            pack.next_puzzle(Difficulty::Expert)
+                .unwrap_err()
+                .to_string(),
+            "the pack has no expert puzzles"
+        );
+
+        fs::write(&path, "123\n").unwrap();
+        assert!(PuzzlePack::load(&path)
+            .unwrap_err()
+            .to_string()
+            .starts_with("line 1: "));
+        fs::write(&path, format!("{}\n{}\n", lines[0], "0".repeat(81))).unwrap();
+        assert_eq!(
+            PuzzlePack::load(&path).unwrap_err().to_string(),
+            "line 2: The puzzle has more than one solution"
+        );
+        fs::remove_dir_all(&dir).unwrap();
+    }
+}
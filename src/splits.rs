@@ -0,0 +1,122 @@
+use serde::{Deserialize, Serialize};
+use std::{str::FromStr, time::Duration};
+
+use crate::Puzzle;
+
+// what finishing a split means: every cell of a box, or all nine of a digit,
+// filled in correctly
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+pub enum SplitKind {
+    Boxes,
+    Digits,
+}
+
+impl SplitKind {
+    pub fn label(&self) -> &'static str {
+        match self {
+            SplitKind::Boxes => "Box",
+            SplitKind::Digits => "Digit",
+        }
+    }
+}
+
+impl FromStr for SplitKind {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.to_lowercase().as_str() {
+            "boxes" | "box" => Ok(SplitKind::Boxes),
+            "digits" | "digit" => Ok(SplitKind::Digits),
+            _ => Err(format!("Invalid splits: {}", s)),
+        }
+    }
+}
+
+// the time each box or digit was finished, in the order they were
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub(crate) struct Splits {
+    kind: SplitKind,
+    // (box or digit 1-9, when it was finished), a split stays even if an
+    // undo takes it apart again
+    done: Vec<(u8, Duration)>,
+}
+
+impl Splits {
+    pub(crate) fn new(kind: SplitKind) -> Self {
+        Splits {
+            kind,
+            done: Vec::new(),
+        }
+    }
+
+    pub(crate) fn kind(&self) -> SplitKind {
+        self.kind
+    }
+
+    pub(crate) fn done(&self) -> &[(u8, Duration)] {
+        &self.done
+    }
+
+    pub(crate) fn times(&self) -> Vec<Duration> {
+        self.done.iter().map(|&(_, time)| time).collect()
+    }
+
+    // split every box or digit finished since the last call at `elapsed`
+    pub(crate) fn update(&mut self, puzzle: &Puzzle, elapsed: Duration) {
+        let grid = puzzle.grid();
+        let wrong = puzzle.wrong_entries();
+        let is_right =
+            |row: usize, col: usize| grid[row][col].value() != 0 && !wrong.contains(&(row, col));
+
+        for item in 1..=9u8 {
+            if self.done.iter().any(|&(done, _)| done == item) {
+                continue;
+            }
+            let finished = match self.kind {
+                SplitKind::Boxes => {
+                    let (top, left) = ((item as usize - 1) / 3 * 3, (item as usize - 1) % 3 * 3);
+                    (0..9).all(|i| is_right(top + i / 3, left + i % 3))
+                }
+                SplitKind::Digits => {
+                    let cells = (0..81)
+                        .filter(|&i| grid[i / 9][i % 9].value() == item && is_right(i / 9, i % 9));
+                    cells.count() == 9
+                }
+            };
+            if finished {
+                self.done.push((item, elapsed));
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::Difficulty;
+
+    #[test]
+    fn test_splits_follow_the_board() {
+        let mut puzzle = Puzzle::new(Difficulty::Easy);
+        let mut boxes = Splits::new(SplitKind::Boxes);
+        let mut digits = Splits::new(SplitKind::Digits);
+        digits.update(&puzzle, Duration::ZERO);
+        assert!(digits.done().len() < 9);
+
+        let mut seconds = 0;
+        while !puzzle.is_solved() {
+            let (row, col) = puzzle.hint_target(0, 0).unwrap();
+            puzzle.hint(row, col).unwrap();
+            seconds += 1;
+            boxes.update(&puzzle, Duration::from_secs(seconds));
+            digits.update(&puzzle, Duration::from_secs(seconds));
+        }
+
+        // every split once, in the order they were reached
+        assert_eq!(boxes.done().len(), 9);
+        assert_eq!(digits.done().len(), 9);
+        let times = boxes.times();
+        assert!(times.windows(2).all(|pair| pair[0] <= pair[1]));
+        assert_eq!(times.last(), Some(&Duration::from_secs(seconds)));
+    }
+}
@@ -0,0 +1,491 @@
+use directories::ProjectDirs;
+use serde::{Deserialize, Serialize};
+use std::{
+    collections::HashMap,
+    fs, io,
+    path::{Path, PathBuf},
+    time::{Duration, SystemTime, UNIX_EPOCH},
+};
+
+use crate::{splits::SplitKind, Achievement, Difficulty};
+
+const STATS_FILE: &str = "stats.json";
+const SECS_PER_DAY: u64 = 24 * 60 * 60;
+
+// where rsudoku keeps its saves and stats, e.g. ~/.local/share/rsudoku
+pub fn data_dir() -> Option<PathBuf> {
+    ProjectDirs::from("", "", "rsudoku").map(|dirs| dirs.data_dir().to_path_buf())
+}
+
+// days since the unix epoch in UTC, the unit streaks are counted in
+pub fn today() -> u64 {
+    SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .unwrap_or_default()
+        .as_secs()
+        / SECS_PER_DAY
+}
+
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct DifficultyStats {
+    pub played: u32,
+    pub solved: u32,
+    pub total_secs: u64, // summed over solved games
+    pub best_secs: Option<u64>,
+    // the best of the games played with limited mistakes, which are kept
+    // out of best_secs
+    #[serde(default)]
+    pub hardcore_best_secs: Option<u64>,
+}
+
+impl DifficultyStats {
+    // share of started games that were solved, from 0 to 1
+    pub fn completion_rate(&self) -> Option<f64> {
+        (self.played > 0).then(|| self.solved as f64 / self.played as f64)
+    }
+
+    pub fn average_time(&self) -> Option<Duration> {
+        (self.solved > 0).then(|| Duration::from_secs(self.total_secs / self.solved as u64))
+    }
+
+    pub fn best_time(&self) -> Option<Duration> {
+        self.best_secs.map(Duration::from_secs)
+    }
+
+    pub fn hardcore_best_time(&self) -> Option<Duration> {
+        self.hardcore_best_secs.map(Duration::from_secs)
+    }
+}
+
+// the splits of the fastest solve played with splits, in milliseconds
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+struct BestSplits {
+    #[serde(default)]
+    boxes: Vec<u64>,
+    #[serde(default)]
+    digits: Vec<u64>,
+}
+
+impl BestSplits {
+    fn get(&self, kind: SplitKind) -> &[u64] {
+        match kind {
+            SplitKind::Boxes => &self.boxes,
+            SplitKind::Digits => &self.digits,
+        }
+    }
+
+    fn get_mut(&mut self, kind: SplitKind) -> &mut Vec<u64> {
+        match kind {
+            SplitKind::Boxes => &mut self.boxes,
+            SplitKind::Digits => &mut self.digits,
+        }
+    }
+}
+
+// play history persisted between runs
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Stats {
+    #[serde(default)]
+    difficulties: HashMap<Difficulty, DifficultyStats>,
+    #[serde(default)]
+    streak: u32, // consecutive days with a solve, ending on last_solve_day
+    #[serde(default)]
+    longest_streak: u32,
+    #[serde(default)]
+    last_solve_day: Option<u64>,
+    #[serde(default)]
+    best_splits: HashMap<Difficulty, BestSplits>,
+    // when the best solve filled each of its cells, in milliseconds
+    #[serde(default)]
+    ghosts: HashMap<Difficulty, Vec<u64>>,
+    // in the order they were unlocked
+    #[serde(default)]
+    achievements: Vec<UnlockedAchievement>,
+    // the first solve of each daily puzzle, oldest first
+    #[serde(default)]
+    daily: Vec<DailySolve>,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(from = "StoredUnlock")]
+pub struct UnlockedAchievement {
+    pub achievement: Achievement,
+    // as counted by `today()`, None for one unlocked before the day was kept
+    pub day: Option<u64>,
+}
+
+// stats files from before unlock days were kept list bare achievements
+#[derive(Deserialize)]
+#[serde(untagged)]
+enum StoredUnlock {
+    Dated {
+        achievement: Achievement,
+        day: Option<u64>,
+    },
+    Bare(Achievement),
+}
+
+impl From<StoredUnlock> for UnlockedAchievement {
+    fn from(stored: StoredUnlock) -> Self {
+        match stored {
+            StoredUnlock::Dated { achievement, day } => UnlockedAchievement { achievement, day },
+            StoredUnlock::Bare(achievement) => UnlockedAchievement {
+                achievement,
+                day: None,
+            },
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub struct DailySolve {
+    pub day: u64, // as counted by `today()`
+    pub difficulty: Difficulty,
+    pub secs: u64,
+}
+
+impl Stats {
+    pub fn path(dir: &Path) -> PathBuf {
+        dir.join(STATS_FILE)
+    }
+
+    // a missing file is a fresh start rather than an error
+    pub fn load(dir: &Path) -> io::Result<Stats> {
+        match fs::read_to_string(Self::path(dir)) {
+            Ok(json) => serde_json::from_str(&json).map_err(io::Error::other),
+            Err(err) if err.kind() == io::ErrorKind::NotFound => Ok(Stats::default()),
+            Err(err) => Err(err),
+        }
+    }
+
+    pub fn save(&self, dir: &Path) -> io::Result<()> {
+        fs::create_dir_all(dir)?;
+        let json = serde_json::to_string_pretty(self).map_err(io::Error::other)?;
+        fs::write(Self::path(dir), json)
+    }
+
+    pub fn difficulty(&self, difficulty: Difficulty) -> DifficultyStats {
+        self.difficulties
+            .get(&difficulty)
+            .copied()
+            .unwrap_or_default()
+    }
+
+    pub fn best_time(&self, difficulty: Difficulty, hardcore: bool) -> Option<Duration> {
+        let stats = self.difficulty(difficulty);
+        if hardcore {
+            stats.hardcore_best_time()
+        } else {
+            stats.best_time()
+        }
+    }
+
+    // solves over every difficulty
+    pub fn solved(&self) -> u32 {
+        self.difficulties.values().map(|stats| stats.solved).sum()
+    }
+
+    pub fn achievements(&self) -> &[UnlockedAchievement] {
+        &self.achievements
+    }
+
+    // the day an achievement was unlocked on, None when it is still locked
+    // and Some(None) when that day wasn't kept
+    pub fn unlocked(&self, achievement: Achievement) -> Option<Option<u64>> {
+        self.achievements
+            .iter()
+            .find(|unlocked| unlocked.achievement == achievement)
+            .map(|unlocked| unlocked.day)
+    }
+
+    // returns whether the achievement is new
+    pub fn unlock(&mut self, achievement: Achievement, today: u64) -> bool {
+        let is_new = self.unlocked(achievement).is_none();
+        if is_new {
+            self.achievements.push(UnlockedAchievement {
+                achievement,
+                day: Some(today),
+            });
+        }
+        is_new
+    }
+
+    // the longest run of days with a solve there has been, the current
+    // one included; stats from before it was kept start from the last one
+    pub fn longest_streak(&self) -> u32 {
+        self.longest_streak.max(self.streak)
+    }
+
+    // the streak still counts until a whole day passes without a solve
+    pub fn current_streak(&self, today: u64) -> u32 {
+        match self.last_solve_day {
+            Some(day) if day + 1 >= today => self.streak,
+            _ => 0,
+        }
+    }
+
+    pub fn best_splits(&self, difficulty: Difficulty, kind: SplitKind) -> Vec<Duration> {
+        self.best_splits
+            .get(&difficulty)
+            .map_or(Vec::new(), |best| {
+                best.get(kind)
+                    .iter()
+                    .map(|&millis| Duration::from_millis(millis))
+                    .collect()
+            })
+    }
+
+    // keep a solve's splits when it finished sooner than the best so far,
+    // returning whether it did
+    pub fn record_splits(
+        &mut self,
+        difficulty: Difficulty,
+        kind: SplitKind,
+        splits: &[Duration],
+    ) -> bool {
+        let best = self
+            .best_splits
+            .entry(difficulty)
+            .or_default()
+            .get_mut(kind);
+        let millis: Vec<u64> = splits.iter().map(|time| time.as_millis() as u64).collect();
+        let is_best = match (best.last(), millis.last()) {
+            (_, None) => false,
+            (None, Some(_)) => true,
+            (Some(best), Some(time)) => time < best,
+        };
+        if is_best {
+            *best = millis;
+        }
+        is_best
+    }
+
+    // the pace of the best solve to race against, empty until a personal
+    // best has been set with one recorded
+    pub fn ghost(&self, difficulty: Difficulty) -> Vec<Duration> {
+        self.ghosts.get(&difficulty).map_or(Vec::new(), |ghost| {
+            ghost
+                .iter()
+                .map(|&millis| Duration::from_millis(millis))
+                .collect()
+        })
+    }
+
+    // called with the fill times of a solve that just set a personal best
+    pub fn record_ghost(&mut self, difficulty: Difficulty, fill_times: &[Duration]) {
+        let millis = fill_times
+            .iter()
+            .map(|time| time.as_millis() as u64)
+            .collect();
+        self.ghosts.insert(difficulty, millis);
+    }
+
+    pub fn daily(&self) -> &[DailySolve] {
+        &self.daily
+    }
+
+    pub fn solved_daily(&self, day: u64, difficulty: Difficulty) -> bool {
+        self.daily_time(day, difficulty).is_some()
+    }
+
+    // how long the first solve of a day's puzzle took
+    pub fn daily_time(&self, day: u64, difficulty: Difficulty) -> Option<Duration> {
+        self.daily
+            .iter()
+            .find(|solve| solve.day == day && solve.difficulty == difficulty)
+            .map(|solve| Duration::from_secs(solve.secs))
+    }
+
+    // returns whether this is the first solve of that day's puzzle, later
+    // ones leave the record as it was
+    pub fn record_daily(&mut self, day: u64, difficulty: Difficulty, time: Duration) -> bool {
+        let is_first = !self.solved_daily(day, difficulty);
+        if is_first {
+            self.daily.push(DailySolve {
+                day,
+                difficulty,
+                secs: time.as_secs(),
+            });
+        }
+        is_first
+    }
+
+    pub fn record_start(&mut self, difficulty: Difficulty) {
+        self.difficulties.entry(difficulty).or_default().played += 1;
+    }
+
+    // record a solve, hardcore when played with limited mistakes, returning
+    // whether it beat the previous best of its kind
+    pub fn record_solve(
+        &mut self,
+        difficulty: Difficulty,
+        time: Duration,
+        hardcore: bool,
+        today: u64,
+    ) -> bool {
+        let stats = self.difficulties.entry(difficulty).or_default();
+        let secs = time.as_secs();
+        let best = if hardcore {
+            &mut stats.hardcore_best_secs
+        } else {
+            &mut stats.best_secs
+        };
+        let is_best = best.is_none_or(|best| secs < best);
+        if is_best {
+            *best = Some(secs);
+        }
+
+        stats.solved += 1;
+        stats.total_secs += secs;
+
+        self.streak = match self.last_solve_day {
+            Some(day) if day == today => self.streak,
+            Some(day) if day + 1 == today => self.streak + 1,
+            _ => 1,
+        };
+        self.longest_streak = self.longest_streak();
+        self.last_solve_day = Some(today);
+
+        is_best
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_record_solve_keeps_the_fastest_time() {
+        let mut stats = Stats::default();
+        assert!(stats.record_solve(Difficulty::Hard, Duration::from_secs(300), false, 0));
+        assert!(!stats.record_solve(Difficulty::Hard, Duration::from_secs(400), false, 0));
+        assert!(stats.record_solve(Difficulty::Hard, Duration::from_secs(200), false, 0));
+
+        assert_eq!(
+            stats.best_time(Difficulty::Hard, false),
+            Some(Duration::from_secs(200))
+        );
+        assert_eq!(stats.best_time(Difficulty::Easy, false), None);
+    }
+
+    #[test]
+    fn test_hardcore_bests_are_kept_apart() {
+        let mut stats = Stats::default();
+        assert!(stats.record_solve(Difficulty::Hard, Duration::from_secs(300), false, 0));
+        // slower than the normal best, but the first with limited mistakes
+        assert!(stats.record_solve(Difficulty::Hard, Duration::from_secs(500), true, 0));
+        assert!(!stats.record_solve(Difficulty::Hard, Duration::from_secs(600), true, 0));
+        assert!(stats.record_solve(Difficulty::Hard, Duration::from_secs(100), true, 0));
+
+        assert_eq!(
+            stats.best_time(Difficulty::Hard, false),
+            Some(Duration::from_secs(300))
+        );
+        assert_eq!(
+            stats.best_time(Difficulty::Hard, true),
+            Some(Duration::from_secs(100))
+        );
+        assert_eq!(stats.difficulty(Difficulty::Hard).solved, 4);
+    }
+
+    #[test]
+    fn test_completion_rate_and_average() {
+        let mut stats = Stats::default();
+        stats.record_start(Difficulty::Easy);
+        stats.record_start(Difficulty::Easy);
+        stats.record_solve(Difficulty::Easy, Duration::from_secs(100), false, 0);
+
+        let easy = stats.difficulty(Difficulty::Easy);
+        assert_eq!(easy.completion_rate(), Some(0.5));
+        assert_eq!(easy.average_time(), Some(Duration::from_secs(100)));
+        assert_eq!(stats.difficulty(Difficulty::Expert).completion_rate(), None);
+    }
+
+    #[test]
+    fn test_streak_counts_consecutive_days() {
+        let mut stats = Stats::default();
+        stats.record_solve(Difficulty::Easy, Duration::from_secs(60), false, 10);
+        stats.record_solve(Difficulty::Easy, Duration::from_secs(60), false, 10);
+        stats.record_solve(Difficulty::Easy, Duration::from_secs(60), false, 11);
+        assert_eq!(stats.current_streak(11), 2);
+        assert_eq!(stats.current_streak(12), 2);
+        assert_eq!(stats.current_streak(13), 0);
+
+        stats.record_solve(Difficulty::Easy, Duration::from_secs(60), false, 14);
+        assert_eq!(stats.current_streak(14), 1);
+        assert_eq!(stats.longest_streak(), 2);
+    }
+
+    #[test]
+    fn test_best_splits_come_from_the_fastest_finish() {
+        let mut stats = Stats::default();
+        let secs = |secs: &[u64]| {
+            secs.iter()
+                .map(|&s| Duration::from_secs(s))
+                .collect::<Vec<_>>()
+        };
+        assert!(stats.record_splits(Difficulty::Hard, SplitKind::Boxes, &secs(&[10, 50])));
+        assert!(!stats.record_splits(Difficulty::Hard, SplitKind::Boxes, &secs(&[5, 60])));
+        assert!(stats.record_splits(Difficulty::Hard, SplitKind::Boxes, &secs(&[20, 40])));
+
+        assert_eq!(
+            stats.best_splits(Difficulty::Hard, SplitKind::Boxes),
+            secs(&[20, 40])
+        );
+        assert!(stats
+            .best_splits(Difficulty::Hard, SplitKind::Digits)
+            .is_empty());
+    }
+
+    #[test]
+    fn test_achievements_unlock_once() {
+        let mut stats = Stats::default();
+        assert!(stats.unlock(Achievement::Flawless, 10));
+        assert!(!stats.unlock(Achievement::Flawless, 11));
+        assert!(stats.unlock(Achievement::FirstSolve, 12));
+        let unlocked: Vec<_> = stats
+            .achievements()
+            .iter()
+            .map(|unlocked| unlocked.achievement)
+            .collect();
+        assert_eq!(unlocked, [Achievement::Flawless, Achievement::FirstSolve]);
+        assert_eq!(stats.unlocked(Achievement::Flawless), Some(Some(10)));
+        assert_eq!(stats.unlocked(Achievement::Hardcore), None);
+    }
+
+    #[test]
+    fn test_undated_achievements_still_load() {
+        let stats: Stats = serde_json::from_str(
+            r#"{"achievements": ["Flawless", {"achievement": "Hardcore", "day": 3}]}"#,
+        )
+        .unwrap();
+        assert_eq!(stats.unlocked(Achievement::Flawless), Some(None));
+        assert_eq!(stats.unlocked(Achievement::Hardcore), Some(Some(3)));
+    }
+
+    #[test]
+    fn test_daily_puzzles_are_recorded_once() {
+        let mut stats = Stats::default();
+        assert!(stats.record_daily(5, Difficulty::Hard, Duration::from_secs(300)));
+        assert!(!stats.record_daily(5, Difficulty::Hard, Duration::from_secs(200)));
+        assert!(stats.record_daily(5, Difficulty::Easy, Duration::from_secs(100)));
+
+        assert!(stats.solved_daily(5, Difficulty::Hard));
+        assert!(!stats.solved_daily(6, Difficulty::Hard));
+        assert_eq!(stats.daily()[0].secs, 300);
+        assert_eq!(stats.daily().len(), 2);
+    }
+
+    #[test]
+    fn test_save_and_load_round_trip() {
+        let dir = std::env::temp_dir().join(format!("rsudoku-stats-{}", std::process::id()));
+        assert_eq!(Stats::load(&dir).unwrap(), Stats::default());
+
+        let mut stats = Stats::default();
+        stats.record_solve(Difficulty::Easy, Duration::from_secs(90), false, 0);
+        stats.save(&dir).unwrap();
+        assert_eq!(Stats::load(&dir).unwrap(), stats);
+
+        fs::remove_dir_all(&dir).unwrap();
+    }
+}
@@ -0,0 +1,61 @@
+use std::time::{Duration, Instant};
+
+// a game clock that can be paused, e.g. while the board is hidden
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub(crate) struct Stopwatch {
+    started: Option<Instant>, // None while paused
+    accumulated: Duration,
+}
+
+impl Stopwatch {
+    pub(crate) fn start() -> Self {
+        Stopwatch {
+            started: Some(Instant::now()),
+            accumulated: Duration::default(),
+        }
+    }
+
+    // paused with `elapsed` already on the clock, for a game carried on
+    pub(crate) fn paused_at(elapsed: Duration) -> Self {
+        Stopwatch {
+            started: None,
+            accumulated: elapsed,
+        }
+    }
+
+    pub(crate) fn elapsed(&self) -> Duration {
+        self.accumulated + self.started.map(|s| s.elapsed()).unwrap_or_default()
+    }
+
+    pub(crate) fn pause(&mut self) {
+        if let Some(started) = self.started.take() {
+            self.accumulated += started.elapsed();
+        }
+    }
+
+    pub(crate) fn resume(&mut self) {
+        if self.started.is_none() {
+            self.started = Some(Instant::now());
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_paused_stopwatch_does_not_advance() {
+        let mut stopwatch = Stopwatch::start();
+        stopwatch.pause();
+        assert!(stopwatch.started.is_none());
+
+        let elapsed = stopwatch.elapsed();
+        std::thread::sleep(Duration::from_millis(5));
+        assert_eq!(stopwatch.elapsed(), elapsed);
+
+        stopwatch.resume();
+        std::thread::sleep(Duration::from_millis(5));
+        assert!(stopwatch.elapsed() > elapsed);
+    }
+}
@@ -0,0 +1,105 @@
+use ratatui::{
+    buffer::Buffer,
+    layout::Rect,
+    style::Stylize,
+    symbols::border,
+    widgets::{Block, Clear, Paragraph, Widget},
+};
+use std::collections::VecDeque;
+
+// how many ticks a toast stays up, and how many are shown at once
+const TOAST_TICKS: u16 = 12;
+const MAX_TOASTS: usize = 4;
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+struct Toast {
+    text: String,
+    ticks_left: u16,
+}
+
+// short lived notifications stacked in the top right corner
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub(crate) struct Toasts {
+    toasts: VecDeque<Toast>,
+}
+
+impl Toasts {
+    pub(crate) fn push(&mut self, text: impl Into<String>) {
+        if self.toasts.len() == MAX_TOASTS {
+            self.toasts.pop_front();
+        }
+
+        self.toasts.push_back(Toast {
+            text: text.into(),
+            ticks_left: TOAST_TICKS,
+        });
+    }
+
+    // age every toast by one tick and drop the expired ones
+    pub(crate) fn tick(&mut self) {
+        for toast in self.toasts.iter_mut() {
+            toast.ticks_left = toast.ticks_left.saturating_sub(1);
+        }
+        self.toasts.retain(|toast| toast.ticks_left > 0);
+    }
+
+    pub(crate) fn is_empty(&self) -> bool {
+        self.toasts.is_empty()
+    }
+}
+
+impl Widget for &Toasts {
+    fn render(self, area: Rect, buf: &mut Buffer) {
+        let mut y = area.y;
+
+        // newest on top
+        for toast in self.toasts.iter().rev() {
+            let width = (toast.text.chars().count() as u16 + 4).min(area.width);
+            if y + 3 > area.bottom() {
+                break;
+            }
+
+            let toast_area = Rect {
+                x: area.right() - width,
+                y,
+                width,
+                height: 3,
+            };
+            Clear.render(toast_area, buf);
+            Paragraph::new(toast.text.as_str().bold())
+                .centered()
+                .block(Block::bordered().border_set(border::ROUNDED).green())
+                .render(toast_area, buf);
+
+            y += 3;
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_toasts_expire_after_a_few_ticks() {
+        let mut toasts = Toasts::default();
+        toasts.push("saved");
+        for _ in 0..TOAST_TICKS - 1 {
+            toasts.tick();
+        }
+        assert_eq!(toasts.toasts.len(), 1);
+
+        toasts.tick();
+        assert!(toasts.is_empty());
+    }
+
+    #[test]
+    fn test_oldest_toast_is_dropped_when_full() {
+        let mut toasts = Toasts::default();
+        for i in 0..MAX_TOASTS + 1 {
+            toasts.push(format!("toast {}", i));
+        }
+        assert_eq!(toasts.toasts.len(), MAX_TOASTS);
+        assert_eq!(toasts.toasts.front().unwrap().text, "toast 1");
+    }
+}
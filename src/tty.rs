@@ -0,0 +1,63 @@
+use std::{
+    io::{self, Write},
+    sync::{
+        atomic::{AtomicBool, Ordering},
+        Arc, OnceLock,
+    },
+};
+
+use crossterm::{
+    event::{DisableBracketedPaste, DisableMouseCapture},
+    execute,
+};
+
+use crate::save;
+
+// set once SIGINT, SIGTERM or SIGHUP arrives, the game loop checks it and
+// returns so the terminal is put back before the process ends
+static INTERRUPTED: OnceLock<Arc<AtomicBool>> = OnceLock::new();
+
+fn interrupt_flag() -> &'static Arc<AtomicBool> {
+    INTERRUPTED.get_or_init(Default::default)
+}
+
+pub(crate) fn interrupted() -> bool {
+    interrupt_flag().load(Ordering::Relaxed)
+}
+
+// undo what `App::run` set up on top of `ratatui::init`, for when it never
+// gets to do so itself
+fn restore() {
+    let mut stdout = io::stdout();
+    let _ = execute!(stdout, DisableBracketedPaste, DisableMouseCapture);
+    let _ = write!(stdout, "\x1b[23;0t");
+    let _ = stdout.flush();
+}
+
+// make a crash or a kill leave the shell usable: panics restore the terminal
+// before the message is printed and signals end the game loop cleanly, a
+// second signal exits at once in case the loop is stuck. A panic also writes
+// out the game in play, which the next start offers back. Call after
+// `ratatui::init`, whose own hook leaves raw mode and the alternate screen.
+pub fn install_hooks() -> io::Result<()> {
+    let hook = std::panic::take_hook();
+    std::panic::set_hook(Box::new(move |info| {
+        let recovered = save::write_recovery();
+        restore();
+        hook(info);
+        if let Err(err) = recovered {
+            eprintln!("could not keep the game in play: {}", err);
+        }
+    }));
+
+    #[cfg(unix)]
+    {
+        use signal_hook::consts::{SIGHUP, SIGINT, SIGTERM};
+
+        for signal in [SIGINT, SIGTERM, SIGHUP] {
+            signal_hook::flag::register_conditional_shutdown(signal, 1, interrupt_flag().clone())?;
+            signal_hook::flag::register(signal, interrupt_flag().clone())?;
+        }
+    }
+    Ok(())
+}
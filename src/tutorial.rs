@@ -0,0 +1,82 @@
+// a crafted board teaching one technique, finished by a single placement
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub(crate) struct Lesson {
+    pub(crate) title: &'static str,
+    pub(crate) board: &'static str, // 81 digits in reading order, 0 for empty
+    pub(crate) target: (usize, usize, u8),
+    pub(crate) highlight: &'static [(usize, usize)], // the cells that explain the placement
+    pub(crate) explanation: &'static str,
+}
+
+// each lesson continues the board of the one before
+pub(crate) const LESSONS: [Lesson; 3] = [
+    Lesson {
+        title: "Naked single",
+        board: "000095410001800050095000062070004900000070000309600000108400705064750031000010000",
+        target: (1, 6, 3),
+        highlight: &[
+            (1, 2),
+            (2, 8),
+            (0, 6),
+            (1, 7),
+            (2, 7),
+            (6, 6),
+            (1, 3),
+            (3, 6),
+        ],
+        explanation: "The row, column and box of R2C7 already hold every number except 3, \
+                      so 3 is the only one that can go there. Put a 3 in the green cell.",
+    },
+    Lesson {
+        title: "Hidden single",
+        board: "000095410001800350095000062070004900000070000309600000108400705064750031000010000",
+        target: (1, 8, 9),
+        highlight: &[(2, 1), (0, 4)],
+        explanation: "Row 2 needs a 9 somewhere. The 9s in R3C2 and R1C5 rule out every other \
+                      empty cell of the row, which leaves R2C9 even though it has other \
+                      candidates. Put a 9 in the green cell.",
+    },
+    Lesson {
+        title: "Pointing pair",
+        board: "000095410001800359095000062070004900000070000309600000108400705064750031000010000",
+        target: (0, 3, 2),
+        highlight: &[(0, 1), (0, 2)],
+        explanation: "In the top left box 3 can only go in R1C2 or R1C3. Both are in row 1, \
+                      so no other cell of row 1 can be 3. That leaves 2 as the only \
+                      candidate of R1C4. Put a 2 in the green cell.",
+    },
+];
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::Puzzle;
+
+    #[test]
+    fn test_lessons_target_their_solution() {
+        for lesson in LESSONS {
+            let puzzle = Puzzle::from_board(lesson.board).expect(lesson.title);
+            let (row, col, num) = lesson.target;
+            assert!(
+                puzzle.candidates(row, col).contains(&num),
+                "{}",
+                lesson.title
+            );
+
+            let mut solved = puzzle.clone();
+            solved.reveal();
+            assert_eq!(solved.grid()[row][col].value(), num, "{}", lesson.title);
+        }
+
+        // only the first lesson can be read off the cell's own candidates
+        let candidates = |lesson: &Lesson| {
+            let (row, col, _) = lesson.target;
+            Puzzle::from_board(lesson.board)
+                .unwrap()
+                .candidates(row, col)
+        };
+        assert_eq!(candidates(&LESSONS[0]), vec![3]);
+        assert!(candidates(&LESSONS[1]).len() > 1);
+        assert_eq!(candidates(&LESSONS[2]), vec![2, 3]);
+    }
+}
@@ -0,0 +1,228 @@
+use std::{
+    collections::HashMap,
+    fs, io,
+    path::{Path, PathBuf},
+    thread::{self, JoinHandle},
+};
+
+use crate::{http::Url, source::read_puzzles, Difficulty, Puzzle, PuzzleSource};
+
+// puzzles from a web server, which lists each level's boards in a file of
+// the directory, easy.txt to expert.txt, written like a pack file. A level's
+// list is downloaded into the cache directory when it runs out there, and
+// every puzzle dealt is taken off it, so play carries on offline until then.
+// Downloads run on their own thread; a level with nothing cached has no
+// puzzle until its download is in.
+#[derive(Debug)]
+pub struct WebSource {
+    url: Url,
+    cache: PathBuf,
+    downloads: HashMap<Difficulty, JoinHandle<io::Result<Vec<Puzzle>>>>,
+}
+
+impl WebSource {
+    pub fn new(url: &str, cache: impl Into<PathBuf>) -> Result<Self, String> {
+        let url = url
+            .parse()
+            .map_err(|err| format!("Invalid source url, {}", err))?;
+        Ok(WebSource {
+            url,
+            cache: cache.into(),
+            downloads: HashMap::new(),
+        })
+    }
+
+    fn start_download(&mut self, difficulty: Difficulty) {
+        let url = self.url.clone();
+        self.downloads
+            .entry(difficulty)
+            .or_insert_with(|| thread::spawn(move || download(&url, difficulty)));
+    }
+
+    // the puzzles of a finished download, None while it is still running or
+    // there is none
+    fn finished_download(&mut self, difficulty: Difficulty) -> Option<io::Result<Vec<Puzzle>>> {
+        if !self.downloads.get(&difficulty)?.is_finished() {
+            return None;
+        }
+        let download = self.downloads.remove(&difficulty)?;
+        Some(
+            download
+                .join()
+                .unwrap_or_else(|_| Err(io::Error::other("the download stopped unexpectedly"))),
+        )
+    }
+}
+
+fn level_name(difficulty: Difficulty) -> String {
+    difficulty.to_string().to_lowercase()
+}
+
+fn download(url: &Url, difficulty: Difficulty) -> io::Result<Vec<Puzzle>> {
+    let level = level_name(difficulty);
+    let file = format!("{}.txt", level);
+    let no_puzzles = || {
+        io::Error::new(
+            io::ErrorKind::NotFound,
+            format!("the server has no {} puzzles", level),
+        )
+    };
+    let body = url.get(&file)?.ok_or_else(no_puzzles)?;
+    let puzzles = read_puzzles(&String::from_utf8_lossy(&body))
+        .map_err(|err| io::Error::new(err.kind(), format!("{}: {}", file, err)))?;
+    if puzzles.is_empty() {
+        return Err(no_puzzles());
+    }
+    Ok(puzzles)
+}
+
+// the puzzles left in a cache file, none when there is no file
+fn read_cache(path: &Path) -> io::Result<Vec<Puzzle>> {
+    match fs::read_to_string(path) {
+        Ok(text) => read_puzzles(&text),
+        Err(err) if err.kind() == io::ErrorKind::NotFound => Ok(Vec::new()),
+        Err(err) => Err(err),
+    }
+}
+
+fn write_cache(dir: &Path, path: &Path, puzzles: &[Puzzle]) -> io::Result<()> {
+    let lines: Vec<String> = puzzles.iter().map(|p| p.to_board(false)).collect();
+    fs::create_dir_all(dir)?;
+    let temp = path.with_extension("txt.tmp");
+    fs::write(&temp, lines.join("\n"))?;
+    fs::rename(temp, path)
+}
+
+impl PuzzleSource for WebSource {
+    // a level with nothing cached starts its download and fails with
+    // WouldBlock until the download is in; dealing the last cached puzzle
+    // starts the next download early
+    fn next_puzzle(&mut self, difficulty: Difficulty) -> io::Result<Puzzle> {
+        let path = self.cache.join(format!("{}.txt", level_name(difficulty)));
+        let mut puzzles = read_cache(&path)?;
+        if let Some(downloaded) = self.finished_download(difficulty) {
+            puzzles.extend(downloaded?);
+        }
+        if puzzles.is_empty() {
+            self.start_download(difficulty);
+            return Err(io::Error::new(
+                io::ErrorKind::WouldBlock,
+                format!(
+                    "the {} puzzles are still downloading",
+                    level_name(difficulty)
+                ),
+            ));
+        }
+
+        let puzzle = puzzles.remove(0);
+        write_cache(&self.cache, &path, &puzzles)?;
+        if puzzles.is_empty() {
+            self.start_download(difficulty);
+        }
+        Ok(puzzle)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::Generator;
+    use std::{
+        io::{Read, Write},
+        net::TcpListener,
+        sync::{
+            atomic::{AtomicUsize, Ordering},
+            Arc,
+        },
+        time::Duration,
+    };
+
+    // a server with only easy.txt, counting the requests it answers
+    fn serve(easy: String) -> (String, Arc<AtomicUsize>) {
+        let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+        let port = listener.local_addr().unwrap().port();
+        let requests = Arc::new(AtomicUsize::new(0));
+        let counted = Arc::clone(&requests);
+        thread::spawn(move || {
+            for stream in listener.incoming() {
+                let mut stream = stream.unwrap();
+                let mut request = Vec::new();
+                let mut buf = [0; 1024];
+                while !request.windows(4).any(|w| w == b"\r\n\r\n") {
+                    let n = stream.read(&mut buf).unwrap();
+                    request.extend_from_slice(&buf[..n]);
+                }
+                counted.fetch_add(1, Ordering::SeqCst);
+                let response = if request.starts_with(b"GET /puzzles/easy.txt ") {
+                    format!(
+                        "HTTP/1.1 200 OK\r\nContent-Length: {}\r\n\r\n{}",
+                        easy.len(),
+                        easy
+                    )
+                } else {
+                    "HTTP/1.1 404 Not Found\r\nContent-Length: 0\r\n\r\n".to_string()
+                };
+                stream.write_all(response.as_bytes()).unwrap();
+            }
+        });
+        (format!("http://127.0.0.1:{}/puzzles", port), requests)
+    }
+
+    // the next puzzle once any download it waits for is in
+    fn deal(source: &mut WebSource, difficulty: Difficulty) -> io::Result<Puzzle> {
+        loop {
+            match source.next_puzzle(difficulty) {
+                Err(err) if err.kind() == io::ErrorKind::WouldBlock => {
+                    thread::sleep(Duration::from_millis(10))
+                }
+                dealt => return dealt,
+            }
+        }
+    }
+
+    #[test]
+    fn test_download_then_deal_from_the_cache() {
+        let mut generator = Generator::seeded(4);
+        let puzzles: Vec<Puzzle> = (0..2).map(|_| generator.with_clues(40)).collect();
+        let lines: Vec<String> = puzzles.iter().map(|p| p.to_board(false)).collect();
+        let (url, requests) = serve(format!("# curated\n{}\n", lines.join("\n")));
+        let cache = std::env::temp_dir().join(format!("rsudoku-web-{}", std::process::id()));
+        let mut source = WebSource::new(&url, &cache).unwrap();
+
+        // nothing is cached yet, so the first ask only starts the download
+        assert_eq!(
+            source.next_puzzle(Difficulty::Easy).unwrap_err().kind(),
+            io::ErrorKind::WouldBlock
+        );
+        let dealt = deal(&mut source, Difficulty::Easy).unwrap();
+        assert_eq!(dealt.grid(), puzzles[0].grid());
+        assert_eq!(requests.load(Ordering::SeqCst), 1);
+        // the second one is still cached, and dealing it fetches the list again
+        let dealt = deal(&mut source, Difficulty::Easy).unwrap();
+        assert_eq!(dealt.grid(), puzzles[1].grid());
+        let dealt = deal(&mut source, Difficulty::Easy).unwrap();
+        assert_eq!(dealt.grid(), puzzles[0].grid());
+        assert_eq!(requests.load(Ordering::SeqCst), 2);
+
+        assert_eq!(
+            deal(&mut source, Difficulty::Expert)
+                .unwrap_err()
+                .to_string(),
+            "the server has no expert puzzles"
+        );
+        assert!(WebSource::new("https://example.com", &cache).is_err());
+        fs::remove_dir_all(&cache).unwrap();
+    }
+
+    #[test]
+    fn test_boards_with_several_solutions_are_refused() {
+        let (url, _) = serve(format!("{}\n", "0".repeat(81)));
+        let cache = std::env::temp_dir().join(format!("rsudoku-web-many-{}", std::process::id()));
+        let mut source = WebSource::new(&url, &cache).unwrap();
+        assert_eq!(
+            deal(&mut source, Difficulty::Easy).unwrap_err().to_string(),
+            "easy.txt: line 1: The puzzle has more than one solution"
+        );
+        assert!(!cache.exists());
+    }
+}